@@ -0,0 +1,380 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Cellular automata
+
+    Days 11, 17 and 24 are all variations on the game of life. This module
+    separates the three parts that vary:
+
+    - Topology: which cells are neighbors. Provided are a bounded square grid
+      (SquareGrid), an infinite lattice in any number of dimensions (Lattice),
+      and an infinite hexagonal grid (HexLattice).
+
+    - Rule: whether a cell is alive in the next generation, given whether it
+      is alive now and its number of live neighbors. Rules are given as
+      birth/survive sets (e.g. Conway's game of life is B3/S23) or as a
+      tolerance threshold (birth with no neighbors, survive with fewer than
+      the tolerance).
+
+    - Backend: how the live cells are stored.
+      SparseAutomaton stores the set of live cells, and only considers cells
+      next to a live cell; it works for infinite topologies, but not for rules
+      where a cell with no live neighbors is born.
+      DenseAutomaton stores a fixed, finite universe of cells (with neighbors
+      precomputed), so it works for any rule.
+
+    Both backends implement Automaton, which provides stepping and detection
+    of fixpoints and cycles.
+*/
+
+use crate::util::grid::{Dir, Pos, DIRS_4, DIRS_8};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+/*
+    Topologies
+*/
+
+/// The neighborhood structure of a set of cells.
+pub trait Topology {
+    type Cell: Clone + Eq + Hash + Ord;
+    fn neighbors(&self, cell: &Self::Cell) -> Vec<Self::Cell>;
+}
+
+/// Bounded 2D grid of (row, col) positions.
+#[derive(Clone, Copy, Debug)]
+pub struct SquareGrid {
+    rows: usize,
+    cols: usize,
+    dirs: &'static [Dir],
+}
+impl SquareGrid {
+    /// All 8 surrounding cells are neighbors.
+    pub fn moore(rows: usize, cols: usize) -> Self {
+        Self { rows, cols, dirs: DIRS_8 }
+    }
+    /// Only the 4 orthogonally adjacent cells are neighbors.
+    pub fn von_neumann(rows: usize, cols: usize) -> Self {
+        Self { rows, cols, dirs: DIRS_4 }
+    }
+}
+impl Topology for SquareGrid {
+    type Cell = Pos;
+    fn neighbors(&self, &(i, j): &Pos) -> Vec<Pos> {
+        self.dirs
+            .iter()
+            .map(|&(di, dj)| (i as isize + di, j as isize + dj))
+            .filter(|&(i, j)| i >= 0 && j >= 0)
+            .map(|(i, j)| (i as usize, j as usize))
+            .filter(|&(i, j)| i < self.rows && j < self.cols)
+            .collect()
+    }
+}
+
+/// Infinite lattice in any number of dimensions, where all cells in the
+/// surrounding 3 x 3 x ... x 3 box are neighbors.
+#[derive(Clone, Debug)]
+pub struct Lattice {
+    offsets: Vec<Vec<isize>>,
+}
+impl Lattice {
+    pub fn new(dimension: usize) -> Self {
+        let mut offsets = vec![vec![]];
+        for _ in 0..dimension {
+            offsets = offsets
+                .iter()
+                .flat_map(|offset| {
+                    (-1..=1).map(move |d| {
+                        let mut offset = offset.clone();
+                        offset.push(d);
+                        offset
+                    })
+                })
+                .collect();
+        }
+        offsets.retain(|offset| offset.iter().any(|&d| d != 0));
+        Self { offsets }
+    }
+}
+impl Topology for Lattice {
+    type Cell = Vec<isize>;
+    fn neighbors(&self, cell: &Vec<isize>) -> Vec<Vec<isize>> {
+        self.offsets
+            .iter()
+            .map(|offset| cell.iter().zip(offset).map(|(x, d)| x + d).collect())
+            .collect()
+    }
+}
+
+/// Infinite hexagonal grid, in axial coordinates: the neighbors of (x, y)
+/// are (x, y) plus E (1, 0), NE (0, 1), NW (-1, 1), W (-1, 0), SW (0, -1)
+/// and SE (1, -1).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HexLattice;
+pub const HEX_DIRS: &[(isize, isize)] =
+    &[(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+impl Topology for HexLattice {
+    type Cell = (isize, isize);
+    fn neighbors(&self, &(x, y): &(isize, isize)) -> Vec<(isize, isize)> {
+        HEX_DIRS.iter().map(|&(dx, dy)| (x + dx, y + dy)).collect()
+    }
+}
+
+/*
+    Rules
+*/
+
+/// Numbers of live neighbors for which a dead cell is born, and for which a
+/// live cell survives.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survive: Vec<usize>,
+}
+impl Rule {
+    pub fn new(birth: &[usize], survive: &[usize]) -> Self {
+        Self { birth: birth.to_vec(), survive: survive.to_vec() }
+    }
+    /// A dead cell is born if it has no live neighbors, and a live cell
+    /// survives if it has fewer live neighbors than the tolerance.
+    pub fn tolerance(tolerance: usize) -> Self {
+        Self { birth: vec![0], survive: (0..tolerance).collect() }
+    }
+    pub fn next(&self, alive: bool, live_neighbors: usize) -> bool {
+        if alive {
+            self.survive.contains(&live_neighbors)
+        } else {
+            self.birth.contains(&live_neighbors)
+        }
+    }
+}
+
+/*
+    Automata: stepping, fixpoints and cycles
+*/
+
+/// A cycle found by Automaton::find_cycle: the state at generation start
+/// repeats every period generations. A fixpoint has period 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+impl Cycle {
+    pub fn is_fixpoint(&self) -> bool {
+        self.period == 1
+    }
+}
+
+pub trait Automaton {
+    /// Snapshot of the state, to detect repeated states
+    type State: Eq + Hash;
+    fn state(&self) -> Self::State;
+    /// Advance one generation.
+    fn step(&mut self);
+    fn count_alive(&self) -> usize;
+
+    fn step_for(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+    /// Step until the state stops changing, for at most max_steps steps.
+    /// Returns the number of steps that changed the state.
+    fn step_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        let mut prev = self.state();
+        for steps in 0..max_steps {
+            self.step();
+            let curr = self.state();
+            if curr == prev {
+                return Some(steps);
+            }
+            prev = curr;
+        }
+        None
+    }
+    /// Step until a state repeats, for at most max_steps steps.
+    /// Generations are counted from the current state (generation 0).
+    fn find_cycle(&mut self, max_steps: usize) -> Option<Cycle> {
+        let mut seen = HashMap::new();
+        for generation in 0..=max_steps {
+            let state = self.state();
+            if let Some(&start) = seen.get(&state) {
+                return Some(Cycle { start, period: generation - start });
+            }
+            seen.insert(state, generation);
+            if generation < max_steps {
+                self.step();
+            }
+        }
+        None
+    }
+}
+
+/// Automaton storing only the set of live cells.
+#[derive(Clone, Debug)]
+pub struct SparseAutomaton<T: Topology> {
+    topology: T,
+    rule: Rule,
+    alive: HashSet<T::Cell>,
+}
+impl<T: Topology> SparseAutomaton<T> {
+    pub fn new<I>(topology: T, rule: Rule, alive: I) -> Self
+    where
+        I: IntoIterator<Item = T::Cell>,
+    {
+        // Cells far away from any live cell are never considered
+        assert!(!rule.next(false, 0), "sparse automaton with birth on 0");
+        Self { topology, rule, alive: alive.into_iter().collect() }
+    }
+    pub fn is_alive(&self, cell: &T::Cell) -> bool {
+        self.alive.contains(cell)
+    }
+    pub fn alive(&self) -> impl Iterator<Item = &T::Cell> {
+        self.alive.iter()
+    }
+}
+impl<T: Topology> Automaton for SparseAutomaton<T> {
+    type State = BTreeSet<T::Cell>;
+    fn state(&self) -> Self::State {
+        self.alive.iter().cloned().collect()
+    }
+    fn step(&mut self) {
+        // Count live neighbors of each cell next to a live cell
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for cell in &self.alive {
+            for neighbor in self.topology.neighbors(cell) {
+                *counts.entry(neighbor).or_default() += 1;
+            }
+        }
+        let mut next = HashSet::new();
+        for cell in &self.alive {
+            let count = counts.get(cell).copied().unwrap_or(0);
+            if self.rule.next(true, count) {
+                next.insert(cell.clone());
+            }
+        }
+        for (cell, count) in counts {
+            if !self.alive.contains(&cell) && self.rule.next(false, count) {
+                next.insert(cell);
+            }
+        }
+        self.alive = next;
+    }
+    fn count_alive(&self) -> usize {
+        self.alive.len()
+    }
+}
+
+/// Automaton over a fixed universe of cells; neighbors outside the universe
+/// are always dead.
+#[derive(Clone, Debug)]
+pub struct DenseAutomaton<C> {
+    rule: Rule,
+    cells: Vec<C>,
+    index: HashMap<C, usize>,
+    neighbors: Vec<Vec<usize>>,
+    alive: Vec<bool>,
+}
+impl<C: Clone + Eq + Hash> DenseAutomaton<C> {
+    pub fn new<T, F>(topology: &T, rule: Rule, cells: Vec<C>, alive: F) -> Self
+    where
+        T: Topology<Cell = C>,
+        F: Fn(&C) -> bool,
+    {
+        let index: HashMap<C, usize> =
+            cells.iter().cloned().enumerate().map(|(i, c)| (c, i)).collect();
+        let neighbors = cells
+            .iter()
+            .map(|cell| {
+                topology
+                    .neighbors(cell)
+                    .iter()
+                    .filter_map(|neighbor| index.get(neighbor).copied())
+                    .collect()
+            })
+            .collect();
+        let alive = cells.iter().map(alive).collect();
+        Self { rule, cells, index, neighbors, alive }
+    }
+    pub fn is_alive(&self, cell: &C) -> bool {
+        self.index.get(cell).is_some_and(|&i| self.alive[i])
+    }
+    pub fn alive(&self) -> impl Iterator<Item = &C> {
+        self.cells.iter().zip(&self.alive).filter(|(_, &a)| a).map(|(c, _)| c)
+    }
+}
+impl<C: Clone + Eq + Hash> Automaton for DenseAutomaton<C> {
+    type State = Vec<bool>;
+    fn state(&self) -> Self::State {
+        self.alive.clone()
+    }
+    fn step(&mut self) {
+        self.alive = (0..self.cells.len())
+            .map(|i| {
+                let count = self.neighbors[i]
+                    .iter()
+                    .filter(|&&j| self.alive[j])
+                    .count();
+                self.rule.next(self.alive[i], count)
+            })
+            .collect();
+    }
+    fn count_alive(&self) -> usize {
+        self.alive.iter().filter(|&&a| a).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conway() -> Rule {
+        Rule::new(&[3], &[2, 3])
+    }
+    fn blinker() -> Vec<Pos> {
+        vec![(2, 1), (2, 2), (2, 3)]
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(SquareGrid::moore(3, 3).neighbors(&(0, 0)).len(), 3);
+        assert_eq!(SquareGrid::von_neumann(3, 3).neighbors(&(1, 1)).len(), 4);
+        assert_eq!(Lattice::new(3).neighbors(&vec![0, 0, 0]).len(), 26);
+        assert_eq!(Lattice::new(4).neighbors(&vec![0, 0, 0, 0]).len(), 80);
+        assert_eq!(HexLattice.neighbors(&(0, 0)).len(), 6);
+    }
+
+    #[test]
+    fn test_blinker_cycle() {
+        let grid = SquareGrid::moore(5, 5);
+        let cells = (0..5).flat_map(|i| (0..5).map(move |j| (i, j))).collect();
+        let mut dense = DenseAutomaton::new(&grid, conway(), cells, |pos| {
+            blinker().contains(pos)
+        });
+        assert_eq!(dense.find_cycle(10), Some(Cycle { start: 0, period: 2 }));
+        let mut sparse = SparseAutomaton::new(grid, conway(), blinker());
+        sparse.step();
+        assert!(sparse.is_alive(&(1, 2)) && !sparse.is_alive(&(2, 1)));
+        assert_eq!(sparse.step_until_stable(10), None);
+    }
+
+    #[test]
+    fn test_fixpoint() {
+        // Block: a still life
+        let block = vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1]];
+        let mut life = SparseAutomaton::new(Lattice::new(2), conway(), block);
+        assert_eq!(life.step_until_stable(10), Some(0));
+        let cycle = life.find_cycle(10).unwrap();
+        assert!(cycle.is_fixpoint());
+        assert_eq!(life.count_alive(), 4);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let rule = Rule::tolerance(4);
+        assert!(rule.next(false, 0) && !rule.next(false, 1));
+        assert!(rule.next(true, 3) && !rule.next(true, 4));
+    }
+}
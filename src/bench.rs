@@ -0,0 +1,229 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Benchmarking
+
+    Each day is timed in three phases (parse, part 1 and part 2), repeated
+    several times on the same input. A run can be saved as a JSON baseline,
+    and later runs compared against it: a phase is a regression if its median
+    time grew by more than a threshold (as a fraction of the baseline).
+*/
+
+use crate::solution::{runner, BOTH_PARTS};
+use crate::util::ParseResult;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::time::Duration;
+
+/// Differences below this many milliseconds are noise, not regressions.
+pub const MIN_REGRESSION_MS: f64 = 1.0;
+
+/// Summary of repeated timings, in milliseconds.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Stats {
+    pub runs: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub stddev_ms: f64,
+}
+impl Stats {
+    pub fn from_durations(times: &[Duration]) -> Self {
+        assert!(!times.is_empty(), "no timings");
+        let mut ms: Vec<f64> =
+            times.iter().map(|t| t.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let runs = ms.len();
+        let median_ms = if runs % 2 == 1 {
+            ms[runs / 2]
+        } else {
+            (ms[runs / 2 - 1] + ms[runs / 2]) / 2.0
+        };
+        let mean_ms = ms.iter().sum::<f64>() / runs as f64;
+        let variance =
+            ms.iter().map(|x| (x - mean_ms).powi(2)).sum::<f64>() / runs as f64;
+        Self {
+            runs,
+            min_ms: ms[0],
+            median_ms,
+            mean_ms,
+            max_ms: ms[runs - 1],
+            stddev_ms: variance.sqrt(),
+        }
+    }
+}
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>10.3} ±{:>8.3}", self.median_ms, self.stddev_ms)
+    }
+}
+
+/// Timings for one day.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DayBench {
+    pub day: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+impl DayBench {
+    pub fn phases(&self) -> [(&'static str, &Stats); 3] {
+        [("parse", &self.parse), ("part1", &self.part1), ("part2", &self.part2)]
+    }
+}
+
+/// Time parsing and both parts of a day, repeated the given number of times.
+pub fn bench_day(
+    day: usize,
+    lines: &[String],
+    repeats: usize,
+) -> ParseResult<DayBench> {
+    let runner = runner(day).unwrap_or_else(|| {
+        panic!("No solution for day {}", day);
+    });
+    let (mut parse, mut part1, mut part2) = (vec![], vec![], vec![]);
+    for _ in 0..repeats.max(1) {
+        let outcome = runner(lines, BOTH_PARTS)?;
+        parse.push(outcome.parse_time);
+        part1.push(outcome.answers[0].elapsed);
+        part2.push(outcome.answers[1].elapsed);
+    }
+    Ok(DayBench {
+        day,
+        parse: Stats::from_durations(&parse),
+        part1: Stats::from_durations(&part1),
+        part2: Stats::from_durations(&part2),
+    })
+}
+
+/*
+    Baselines and regressions
+*/
+
+/// Saved benchmark results, to compare later runs against.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Baseline {
+    pub days: Vec<DayBench>,
+}
+impl Baseline {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path, err))?;
+        serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid baseline {}: {}", path, err))
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, json + "\n")
+            .map_err(|err| format!("Could not write {}: {}", path, err))
+    }
+    pub fn get(&self, day: usize) -> Option<&DayBench> {
+        self.days.iter().find(|bench| bench.day == day)
+    }
+    /// Update (or add) the results for the days in other.
+    pub fn merge(&mut self, other: &Baseline) {
+        for bench in &other.days {
+            self.days.retain(|old| old.day != bench.day);
+            self.days.push(bench.clone());
+        }
+        self.days.sort_by_key(|bench| bench.day);
+    }
+}
+
+/// A phase of a day that got slower than the baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub day: usize,
+    pub phase: &'static str,
+    pub baseline_ms: f64,
+    pub current_ms: f64,
+}
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pct = (self.current_ms / self.baseline_ms - 1.0) * 100.0;
+        write!(
+            f,
+            "day {} {}: {:.3} ms -> {:.3} ms (+{:.1}%)",
+            self.day, self.phase, self.baseline_ms, self.current_ms, pct
+        )
+    }
+}
+
+/// Phases whose median time exceeds the baseline by more than threshold
+/// (e.g. 0.1 for 10%). Days missing from the baseline are not compared.
+pub fn find_regressions(
+    baseline: &Baseline,
+    current: &Baseline,
+    threshold: f64,
+) -> Vec<Regression> {
+    let mut result = Vec::new();
+    for bench in &current.days {
+        let old = match baseline.get(bench.day) {
+            Some(old) => old,
+            None => continue,
+        };
+        for ((phase, new), (_, old)) in bench.phases().iter().zip(&old.phases())
+        {
+            let (baseline_ms, current_ms) = (old.median_ms, new.median_ms);
+            if current_ms > baseline_ms * (1.0 + threshold)
+                && current_ms - baseline_ms > MIN_REGRESSION_MS
+            {
+                let day = bench.day;
+                result.push(Regression { day, phase, baseline_ms, current_ms });
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::file_to_vec;
+
+    fn stats(median_ms: f64) -> Stats {
+        let time = Duration::from_secs_f64(median_ms / 1000.0);
+        Stats::from_durations(&[time])
+    }
+    fn baseline(day: usize, part2_ms: f64) -> Baseline {
+        let (parse, part1) = (stats(0.5), stats(10.0));
+        let part2 = stats(part2_ms);
+        Baseline { days: vec![DayBench { day, parse, part1, part2 }] }
+    }
+
+    #[test]
+    fn test_stats() {
+        let ms = |n| Duration::from_millis(n);
+        let stats = Stats::from_durations(&[ms(4), ms(1), ms(3), ms(2)]);
+        assert_eq!(stats.runs, 4);
+        assert_eq!((stats.min_ms, stats.max_ms), (1.0, 4.0));
+        assert_eq!((stats.median_ms, stats.mean_ms), (2.5, 2.5));
+    }
+
+    #[test]
+    fn test_regressions() {
+        let old = baseline(3, 100.0);
+        assert!(find_regressions(&old, &baseline(3, 105.0), 0.1).is_empty());
+        assert!(find_regressions(&old, &baseline(4, 500.0), 0.1).is_empty());
+        let regressions = find_regressions(&old, &baseline(3, 120.0), 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].phase, "part2");
+        // Small absolute differences are ignored
+        let old = baseline(3, 0.1);
+        assert!(find_regressions(&old, &baseline(3, 0.5), 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_bench_and_json() {
+        let lines = file_to_vec("input/day01.txt").unwrap();
+        let bench = bench_day(1, &lines, 2).unwrap();
+        assert_eq!(bench.part1.runs, 2);
+        let mut all = Baseline::default();
+        all.merge(&Baseline { days: vec![bench] });
+        let json = serde_json::to_string(&all).unwrap();
+        assert_eq!(serde_json::from_str::<Baseline>(&json).unwrap(), all);
+    }
+}
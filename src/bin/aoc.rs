@@ -0,0 +1,688 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Unified runner for all days
+
+    Usage:
+        aoc run <day> [--part <1|2>] [--input <path>] [--format <plain|json>]
+        aoc run --all [--jobs <n>] [--format <plain|json>]
+        aoc bench [<day>...] [--repeat <n>] [--save <path>]
+                  [--compare <path>] [--threshold <percent>]
+        aoc verify [<day>...] [--answers <path>]
+        aoc debug [--input <path>] [--break <pc>]...
+        aoc cfg [--input <path>]
+        aoc repair [--input <path>] [--max-edits <n>] [--flips-only]
+        aoc parens [--input <path>] [--part <1|2>] [--full]
+        aoc rules [--input <path>] [--part <1|2>] [--engine <name>]
+                  [--trees <n>]
+
+    The input path defaults to input/dayNN.txt; use "-" to read from stdin.
+    Any command accepts -v (summaries) or -vv (step-by-step traces) to
+    print debug output from the solvers to stderr.
+    With --format json, run prints one JSON record per part on stdout
+    (see solution::Record); anything else the solvers print goes to stderr.
+    If a part has no answer for the input, run reports the error in its
+    place and exits with status 1.
+
+    run --all solves every day on its default input, concurrently on a pool
+    of threads (one per CPU by default), and prints a summary table. A day
+    that fails or panics is reported in the table without affecting the
+    others, but makes the exit status 1.
+
+    bench times each day (all days by default) on its input, saves the
+    timings as a JSON baseline with --save (updating any days already in the
+    file), and with --compare exits with an error if any phase is slower
+    than the baseline by more than the threshold (default 10%).
+
+    verify runs each day (all days by default) on its input and compares
+    both parts against the expected answers (in answers.toml by default),
+    exiting with an error if any differ.
+
+    debug loads a handheld console program (input/day08.txt by default) and
+    starts an interactive debugger on it, reading commands from stdin (type
+    help for a list; see vm::debugger). cfg prints the control-flow graph
+    of a program in Graphviz DOT format, and lists any dead code on stderr.
+    repair prints every smallest set of edits (up to --max-edits, default 2)
+    which makes a program terminate normally, with the final accumulator;
+    --flips-only only considers flipping jmp and nop (see vm::repair).
+
+    parens prints each day 18 expression (input/day18.txt by default) the
+    way the given part reads it (part 2 by default): with the parentheses
+    needed to read it left-to-right as in part 1, or with every operation
+    parenthesized with --full (see day18::pretty).
+
+    rules counts the day 19 messages (input/day19.txt by default) which
+    match rule 0 under the given part's rules (part 2 by default), using the
+    given engine: auto (the default), smart or earley (see day19::Engine).
+    With --trees, it instead parses each message with the Earley parser
+    and prints the number of parse trees for each match, and up to n of
+    them (see day19::earley).
+*/
+
+use aoc2020::bench::{bench_day, find_regressions, Baseline};
+use aoc2020::day18::{Expression, PART1, PART2};
+use aoc2020::day19::earley::EarleyParser;
+use aoc2020::day19::{self, Engine};
+use aoc2020::parallel::{run_days, summary_table};
+use aoc2020::solution::{
+    input_path, print_plain, solve_day, Part, BOTH_PARTS, DAYS,
+};
+use aoc2020::trace;
+use aoc2020::util::file_to_vec;
+use aoc2020::verify::{verify_day, Answers, ANSWERS_PATH};
+use aoc2020::vm::cfg::Cfg;
+use aoc2020::vm::debugger::Debugger;
+use aoc2020::vm::repair::{find_repairs, RepairOptions};
+use aoc2020::vm::{assemble, Program, State};
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::thread;
+
+const USAGE: &str = "\
+Usage: aoc run <day> [--part <1|2>] [--input <path>] [--format <plain|json>]
+       aoc run --all [--jobs <n>] [--format <plain|json>]
+       aoc bench [<day>...] [--repeat <n>] [--save <path>]
+                 [--compare <path>] [--threshold <percent>]
+       aoc verify [<day>...] [--answers <path>]
+       aoc debug [--input <path>] [--break <pc>]...
+       aoc cfg [--input <path>]
+       aoc repair [--input <path>] [--max-edits <n>] [--flips-only]
+       aoc parens [--input <path>] [--part <1|2>] [--full]
+       aoc rules [--input <path>] [--part <1|2>] [--engine <name>]
+                 [--trees <n>]
+Options: -v, -vv (debug output on stderr)";
+
+/// The command-line arguments, with -v flags counted and skipped as they
+/// are read. Option values are read with value, so that they are never
+/// taken as flags (e.g. --input -v reads the file -v).
+struct Args {
+    args: std::vec::IntoIter<String>,
+    verbosity: usize,
+}
+impl Args {
+    fn new(args: Vec<String>) -> Self {
+        Self { args: args.into_iter(), verbosity: 0 }
+    }
+    fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.args.next().ok_or_else(|| format!("Missing value for {}", flag))
+    }
+}
+impl Iterator for Args {
+    type Item = String;
+    fn next(&mut self) -> Option<String> {
+        for arg in self.args.by_ref() {
+            match trace::verbosity_flag(&arg) {
+                Some(level) => self.verbosity += level,
+                None => return Some(arg),
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    Plain,
+    Json,
+}
+impl std::str::FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Not a valid format (plain or json): {}", s)),
+        }
+    }
+}
+
+struct RunArgs {
+    day: Option<usize>, // None for --all
+    parts: Vec<Part>,
+    input: Option<String>,
+    format: Format,
+    jobs: Option<usize>,
+}
+
+fn parse_day(raw: &str) -> Result<usize, String> {
+    match raw.parse::<usize>() {
+        Ok(day) if (1..=DAYS).contains(&day) => Ok(day),
+        _ => Err(format!("Not a valid day (should be 1 to {}): {}", DAYS, raw)),
+    }
+}
+
+fn parse_run_args(args: &mut Args) -> Result<RunArgs, String> {
+    let day = match args.next().ok_or("Missing day")?.as_str() {
+        "--all" => None,
+        raw => Some(parse_day(raw)?),
+    };
+    let mut parts = BOTH_PARTS.to_vec();
+    let mut input = None;
+    let mut format = Format::Plain;
+    let mut jobs = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jobs" if day.is_none() => {
+                let raw = args.value("--jobs")?;
+                jobs = match raw.parse() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => return Err(format!("Not a valid job count: {}", raw)),
+                };
+            }
+            "--format" => {
+                format = args.value("--format")?.parse()?;
+            }
+            "--part" | "--input" if day.is_none() => {
+                return Err(format!("{} cannot be used with --all", arg));
+            }
+            "--part" => {
+                let raw = args.value("--part")?;
+                parts = vec![raw.parse()?];
+            }
+            "--input" => {
+                input = Some(args.value("--input")?);
+            }
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(RunArgs { day, parts, input, format, jobs })
+}
+
+fn run_all(format: Format, jobs: Option<usize>) {
+    let jobs = jobs.unwrap_or_else(|| {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    });
+    let days: Vec<usize> = (1..=DAYS).collect();
+    let results = run_days(&days, jobs, input_path);
+    match format {
+        Format::Plain => print!("{}", summary_table(&results)),
+        Format::Json => {
+            for result in &results {
+                for answer in result.outcome.iter().flat_map(|o| &o.answers) {
+                    let record = answer.record(result.day);
+                    println!("{}", serde_json::to_string(&record).unwrap());
+                }
+                if !result.status.is_ok() {
+                    eprintln!("Day {}: {}", result.day, result.status);
+                }
+            }
+        }
+    }
+    let failed = results.iter().filter(|r| !r.status.is_ok()).count();
+    if failed > 0 {
+        eprintln!("Error: {} day(s) failed", failed);
+        process::exit(1);
+    }
+}
+
+fn run(args: RunArgs) {
+    let day = match args.day {
+        Some(day) => day,
+        None => return run_all(args.format, args.jobs),
+    };
+    let path = args.input.unwrap_or_else(|| input_path(day));
+    let outcome = solve_day(day, &path, &args.parts).unwrap_or_else(|err| {
+        // Malformed input is not a usage error, so no usage message
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    });
+    for answer in &outcome.answers {
+        match args.format {
+            Format::Plain => print_plain(answer),
+            Format::Json => {
+                let record = answer.record(day);
+                println!("{}", serde_json::to_string(&record).unwrap());
+            }
+        }
+    }
+    if outcome.first_error().is_some() {
+        process::exit(1);
+    }
+}
+
+struct BenchArgs {
+    days: Vec<usize>,
+    repeat: usize,
+    save: Option<String>,
+    compare: Option<String>,
+    threshold: f64,
+}
+
+fn parse_bench_args(args: &mut Args) -> Result<BenchArgs, String> {
+    let mut bench = BenchArgs {
+        days: vec![],
+        repeat: 3,
+        save: None,
+        compare: None,
+        threshold: 10.0,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--repeat" => {
+                bench.repeat = match args.value("--repeat")?.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err("Not a valid repeat count".to_owned()),
+                }
+            }
+            "--save" => bench.save = Some(args.value("--save")?),
+            "--compare" => bench.compare = Some(args.value("--compare")?),
+            "--threshold" => {
+                bench.threshold = args
+                    .value("--threshold")?
+                    .parse()
+                    .map_err(|_| "Not a valid threshold percentage")?
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("Unrecognized argument: {}", other))
+            }
+            day => bench.days.push(parse_day(day)?),
+        }
+    }
+    if bench.days.is_empty() {
+        bench.days = (1..=DAYS).collect();
+    }
+    Ok(bench)
+}
+
+fn bench(args: BenchArgs) {
+    let fail = |err: String| -> ! {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    };
+    println!(
+        "{:>3}  {:>20}  {:>20}  {:>20}",
+        "day", "parse (ms)", "part 1 (ms)", "part 2 (ms)"
+    );
+    let mut current = Baseline::default();
+    for &day in &args.days {
+        let path = input_path(day);
+        let result = file_to_vec(&path)
+            .and_then(|lines| bench_day(day, &lines, args.repeat))
+            .map_err(|err| err.in_file(&path));
+        let bench = result.unwrap_or_else(|err| fail(err.to_string()));
+        println!(
+            "{:>3}  {}  {}  {}",
+            day, bench.parse, bench.part1, bench.part2
+        );
+        current.days.push(bench);
+    }
+    let mut regressions = vec![];
+    if let Some(path) = &args.compare {
+        let baseline = Baseline::load(path).unwrap_or_else(|err| fail(err));
+        regressions =
+            find_regressions(&baseline, &current, args.threshold / 100.0);
+        if regressions.is_empty() {
+            println!("No regressions against {}", path);
+        }
+        for regression in &regressions {
+            println!("Regression: {}", regression);
+        }
+    }
+    if let Some(path) = &args.save {
+        let mut baseline = if Path::new(path).exists() {
+            Baseline::load(path).unwrap_or_else(|err| fail(err))
+        } else {
+            Baseline::default()
+        };
+        baseline.merge(&current);
+        baseline.save(path).unwrap_or_else(|err| fail(err));
+        println!("Saved baseline to {}", path);
+    }
+    if !regressions.is_empty() {
+        process::exit(1);
+    }
+}
+
+struct VerifyArgs {
+    days: Vec<usize>,
+    answers: String,
+}
+
+fn parse_verify_args(args: &mut Args) -> Result<VerifyArgs, String> {
+    let mut days = vec![];
+    let mut answers = ANSWERS_PATH.to_owned();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => {
+                answers = args.value("--answers")?;
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("Unrecognized argument: {}", other))
+            }
+            day => days.push(parse_day(day)?),
+        }
+    }
+    if days.is_empty() {
+        days = (1..=DAYS).collect();
+    }
+    Ok(VerifyArgs { days, answers })
+}
+
+fn verify(args: VerifyArgs) {
+    let answers = Answers::load(&args.answers).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    });
+    let mut failed = 0;
+    for &day in &args.days {
+        let expected = match answers.get(day) {
+            Some(expected) => expected,
+            None => {
+                println!("day {}: no expected answers", day);
+                continue;
+            }
+        };
+        match verify_day(day, &input_path(day), expected) {
+            Ok(mismatches) if mismatches.is_empty() => {
+                println!("day {}: ok", day)
+            }
+            Ok(mismatches) => {
+                failed += 1;
+                for mismatch in mismatches {
+                    println!("{}", mismatch);
+                }
+            }
+            Err(err) => {
+                failed += 1;
+                println!("day {}: error: {}", day, err);
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("Error: {} day(s) failed verification", failed);
+        process::exit(1);
+    }
+}
+
+struct DebugArgs {
+    input: String,
+    breakpoints: Vec<isize>,
+}
+
+fn parse_debug_args(args: &mut Args) -> Result<DebugArgs, String> {
+    let mut input = input_path(8);
+    let mut breakpoints = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = args.value("--input")?;
+            }
+            "--break" => {
+                let raw = args.value("--break")?;
+                let pc = raw
+                    .parse()
+                    .map_err(|_| format!("Not a valid breakpoint: {}", raw))?;
+                breakpoints.push(pc);
+            }
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(DebugArgs { input, breakpoints })
+}
+
+fn load_program(path: &str) -> Program {
+    file_to_vec(path)
+        .and_then(|lines| assemble(&lines))
+        .map_err(|err| err.in_file(path))
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        })
+}
+
+fn debug(args: DebugArgs) {
+    let program = load_program(&args.input);
+    let mut debugger = Debugger::new(State::new(program));
+    for pc in args.breakpoints {
+        debugger.add_breakpoint(pc);
+    }
+    println!("{}", debugger.print());
+    let stdin = io::stdin();
+    if let Err(err) = debugger.repl(stdin.lock(), io::stdout()) {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn parse_cfg_args(args: &mut Args) -> Result<String, String> {
+    let mut input = input_path(8);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = args.value("--input")?;
+            }
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(input)
+}
+
+fn cfg(input: String) {
+    let cfg = Cfg::new(&load_program(&input));
+    print!("{}", cfg.to_dot());
+    let dead: Vec<String> =
+        cfg.dead_code().iter().map(|i| i.to_string()).collect();
+    if !dead.is_empty() {
+        eprintln!("Dead code: {}", dead.join(", "));
+    }
+}
+
+struct RepairArgs {
+    input: String,
+    options: RepairOptions,
+}
+
+fn parse_repair_args(args: &mut Args) -> Result<RepairArgs, String> {
+    let mut input = input_path(8);
+    let mut options = RepairOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = args.value("--input")?;
+            }
+            "--max-edits" => {
+                let raw = args.value("--max-edits")?;
+                options.max_edits = raw
+                    .parse()
+                    .map_err(|_| format!("Not a valid edit count: {}", raw))?;
+            }
+            "--flips-only" => {
+                options = RepairOptions::flips_only(options.max_edits);
+            }
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(RepairArgs { input, options })
+}
+
+fn repair(args: RepairArgs) {
+    let program = load_program(&args.input);
+    let repairs = find_repairs(&program, &args.options);
+    if repairs.is_empty() {
+        eprintln!(
+            "Error: no repair with at most {} edit(s)",
+            args.options.max_edits
+        );
+        process::exit(1);
+    }
+    if repairs.len() > 1 {
+        eprintln!("{} minimal repairs", repairs.len());
+    }
+    for repair in repairs {
+        println!("{}", repair);
+    }
+}
+
+struct ParensArgs {
+    input: String,
+    part: Part,
+    full: bool,
+}
+
+fn parse_parens_args(args: &mut Args) -> Result<ParensArgs, String> {
+    let mut input = input_path(18);
+    let mut part = Part::Two;
+    let mut full = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = args.value("--input")?;
+            }
+            "--part" => {
+                part = args.value("--part")?.parse()?;
+            }
+            "--full" => full = true,
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(ParensArgs { input, part, full })
+}
+
+fn parens(args: ParensArgs) {
+    let table = match args.part {
+        Part::One => PART1,
+        Part::Two => PART2,
+    };
+    let lines = file_to_vec(&args.input).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err.in_file(&args.input));
+        process::exit(1);
+    });
+    for (i, line) in lines.iter().enumerate() {
+        let printed = line
+            .parse::<Expression>()
+            .and_then(|expr| expr.to_expr(table))
+            .and_then(|expr| {
+                if args.full {
+                    Ok(expr.fully_parenthesized())
+                } else {
+                    expr.pretty(PART1)
+                }
+            })
+            .unwrap_or_else(|err| {
+                let err = err.at_line(i + 1, line).in_file(&args.input);
+                eprintln!("Error: {}", err);
+                process::exit(1);
+            });
+        println!("{}", printed);
+    }
+}
+
+struct RulesArgs {
+    input: String,
+    part: Part,
+    engine: Engine,
+    trees: Option<usize>,
+}
+
+fn parse_rules_args(args: &mut Args) -> Result<RulesArgs, String> {
+    let mut input = input_path(19);
+    let mut part = Part::Two;
+    let mut engine = Engine::Auto;
+    let mut trees = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = args.value("--input")?;
+            }
+            "--part" => {
+                part = args.value("--part")?.parse()?;
+            }
+            "--engine" => {
+                let raw = args.value("--engine")?;
+                engine = raw.parse().map_err(|err| format!("{}", err))?;
+            }
+            "--trees" => {
+                let raw = args.value("--trees")?;
+                trees = Some(raw.parse().map_err(|_| {
+                    format!("Not a valid number of trees: {}", raw)
+                })?);
+            }
+            other => return Err(format!("Unrecognized argument: {}", other)),
+        }
+    }
+    Ok(RulesArgs { input, part, engine, trees })
+}
+
+fn rules(args: RulesArgs) {
+    let (matcher, msgs) = file_to_vec(&args.input)
+        .and_then(|lines| day19::parse_input(&lines))
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err.in_file(&args.input));
+            process::exit(1);
+        });
+    let matcher = match args.part {
+        Part::One => matcher,
+        Part::Two => day19::part2_rules(&matcher),
+    };
+    let limit = match args.trees {
+        Some(limit) => limit,
+        None => {
+            let count = day19::count_matches_with(args.engine, &matcher, &msgs);
+            println!("{}", count);
+            return;
+        }
+    };
+    let parser = EarleyParser::new(&matcher);
+    for msg in &msgs {
+        let parse = parser.parse(0, msg);
+        match parse.count() {
+            Some(count) if count == 0u32.into() => continue,
+            Some(count) if count == 1u32.into() => {
+                println!("{}: 1 parse tree", msg)
+            }
+            Some(count) => println!("{}: {} parse trees", msg, count),
+            None => println!("{}: infinitely many parse trees", msg),
+        }
+        for tree in parse.trees(limit) {
+            println!("    {}", tree);
+        }
+    }
+}
+
+enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Verify(VerifyArgs),
+    Debug(DebugArgs),
+    Cfg(String),
+    Repair(RepairArgs),
+    Parens(ParensArgs),
+    Rules(RulesArgs),
+}
+
+fn parse_command(args: &mut Args) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run_args(args).map(Command::Run),
+        Some("bench") => parse_bench_args(args).map(Command::Bench),
+        Some("verify") => parse_verify_args(args).map(Command::Verify),
+        Some("debug") => parse_debug_args(args).map(Command::Debug),
+        Some("cfg") => parse_cfg_args(args).map(Command::Cfg),
+        Some("repair") => parse_repair_args(args).map(Command::Repair),
+        Some("parens") => parse_parens_args(args).map(Command::Parens),
+        Some("rules") => parse_rules_args(args).map(Command::Rules),
+        Some(other) => Err(format!("Unrecognized command: {}", other)),
+        None => Err("Missing command".to_owned()),
+    }
+}
+
+fn main() {
+    let mut args = Args::new(env::args().skip(1).collect());
+    let command = parse_command(&mut args).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    trace::set_verbosity(args.verbosity);
+    match command {
+        Command::Run(args) => run(args),
+        Command::Bench(args) => bench(args),
+        Command::Verify(args) => verify(args),
+        Command::Debug(args) => debug(args),
+        Command::Cfg(input) => cfg(input),
+        Command::Repair(args) => repair(args),
+        Command::Parens(args) => parens(args),
+        Command::Rules(args) => rules(args),
+    }
+}
@@ -0,0 +1,96 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 1 Solution
+    2020-12-05
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines, ParseError, ParseResult};
+use std::collections::{HashMap, HashSet};
+
+/* Solution */
+
+/// Find two numbers in `nums` summing to `target`.
+pub fn find_sum2(nums: &[usize], target: usize) -> Option<(usize, usize)> {
+    let mut seen = HashSet::new();
+    for &num in nums {
+        let other = target.checked_sub(num);
+        if let Some(other) = other.filter(|other| seen.contains(other)) {
+            return Some((other, num));
+        }
+        seen.insert(num);
+    }
+    None
+}
+
+/// Find three numbers in `nums` summing to `target`.
+pub fn find_sum3(
+    nums: &[usize],
+    target: usize,
+) -> Option<(usize, usize, usize)> {
+    let mut seen_sums = HashMap::new();
+    for &x1 in nums {
+        for &x2 in nums {
+            if let Some(sum) = x1.checked_add(x2) {
+                seen_sums.insert(sum, (x1, x2));
+            }
+        }
+    }
+    for &x3 in nums {
+        let rest = target.checked_sub(x3);
+        if let Some(&(x1, x2)) = rest.and_then(|rest| seen_sums.get(&rest)) {
+            return Some((x1, x2, x3));
+        }
+    }
+    None
+}
+
+/// Solution for day 1
+pub struct Day01;
+impl Solution for Day01 {
+    type Input = Vec<usize>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let nums = parse_lines(lines)?;
+        if find_sum2(&nums, 2020).is_none() {
+            return Err(ParseError::new("no two numbers sum to 2020"));
+        }
+        if find_sum3(&nums, 2020).is_none() {
+            return Err(ParseError::new("no three numbers sum to 2020"));
+        }
+        Ok(nums)
+    }
+    fn part1(nums: &Self::Input) -> SolveResult<String> {
+        let (x1, x2) =
+            find_sum2(nums, 2020).ok_or("no two numbers sum to 2020")?;
+        Ok((x1 * x2).to_string())
+    }
+    fn part2(nums: &Self::Input) -> SolveResult<String> {
+        let (x1, x2, x3) =
+            find_sum3(nums, 2020).ok_or("no three numbers sum to 2020")?;
+        Ok((x1 * x2 * x3).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    const EXAMPLE: &str = "1721\n979\n366\n299\n675\n1456";
+
+    #[test]
+    fn test_example() {
+        let nums = Day01::parse(&str_to_vec(EXAMPLE)).unwrap();
+        assert_eq!(Day01::part1(&nums).unwrap(), "514579");
+        assert_eq!(Day01::part2(&nums).unwrap(), "241861950");
+    }
+
+    #[test]
+    fn test_no_sum() {
+        assert_eq!(find_sum2(&[3000, 20, 10], 2020), None);
+        assert_eq!(find_sum3(&[3000, 20, 10, usize::MAX], 2020), None);
+        assert!(Day01::parse(&str_to_vec("3000\n20")).is_err());
+        assert!(Day01::parse(&str_to_vec("1010\n1010")).is_err());
+    }
+}
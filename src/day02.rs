@@ -0,0 +1,102 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 2 Solution
+    2020-12-05
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines_with, parse_str, ParseError, ParseResult};
+use ascii::{AsAsciiStr, AsciiStr, AsciiString};
+use regex::Regex;
+
+/// Password policy and password: (low, high, letter, password).
+pub type PasswordInfo = (usize, usize, char, AsciiString);
+
+/// Parse a line of the form `1-3 a: abcde`.
+pub fn parse_input_line(line: &str) -> ParseResult<PasswordInfo> {
+    // Note: this compiles a regex multiple times, not optimal.
+    // Use lazy_static for better performance.
+    let re = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]*)$").unwrap();
+    // Extract capture groups
+    let mat = re.captures(line).ok_or_else(|| {
+        ParseError::new("expected a policy and password like `1-3 a: abcde`")
+    })?;
+    let lb: usize = parse_str(&mat[1])?;
+    let ub: usize = parse_str(&mat[2])?;
+    let ch: char = parse_str(&mat[3])?;
+    // The regex only matches [a-z], so the password is ASCII
+    let pass: AsciiString = mat[4].as_ascii_str().unwrap().to_owned();
+    Ok((lb, ub, ch, pass))
+}
+
+fn count_char_occurences(c: char, s: &AsciiStr) -> usize {
+    // count occurences of char in string
+    s.chars().filter(|&ch| ch == c).count()
+}
+
+/// Count passwords where the letter occurs between low and high times.
+pub fn solve_part1(data: &[PasswordInfo]) -> usize {
+    data.iter()
+        .filter(|&dat| {
+            let pass: &AsciiStr = &dat.3;
+            let occurences = count_char_occurences(dat.2, pass);
+            dat.0 <= occurences && occurences <= dat.1
+        })
+        .count()
+}
+
+/// Count passwords where exactly one of positions low and high (1-indexed)
+/// has the letter.
+pub fn solve_part2(data: &[PasswordInfo]) -> usize {
+    data.iter()
+        .filter(|&dat| {
+            let pass: &AsciiStr = &dat.3;
+            let i1: usize = dat.0 - 1;
+            let i2: usize = dat.1 - 1;
+            (pass[i1] == dat.2) ^ (pass[i2] == dat.2)
+        })
+        .count()
+}
+
+/// Solution for day 2
+pub struct Day02;
+impl Solution for Day02 {
+    type Input = Vec<PasswordInfo>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_lines_with(lines, parse_input_line)
+    }
+    fn part1(data: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(data).to_string())
+    }
+    fn part2(data: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(data).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ascii(raw: &str) -> AsciiString {
+        AsciiString::from_ascii(raw).unwrap()
+    }
+
+    fn example_data() -> Vec<PasswordInfo> {
+        vec![
+            (1, 3, 'a', ascii("abcde")),
+            (1, 3, 'b', ascii("cdefg")),
+            (2, 9, 'c', ascii("ccccccccc")),
+        ]
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(solve_part1(&example_data()), 2);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(solve_part2(&example_data()), 1);
+    }
+}
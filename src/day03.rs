@@ -0,0 +1,108 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 3 Solution
+    2020-12-06
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{Grid, ParseResult};
+
+use std::fmt::{Display, Formatter, Result};
+
+/// Map of trees (`#`) and open squares (`.`), repeating to the right.
+#[derive(Clone, Debug)]
+pub struct TobogganMap {
+    grid: Grid<bool>,
+}
+impl TobogganMap {
+    /// Get map from input lines of '#' and '.'
+    pub fn parse_input(input: &[String]) -> ParseResult<Self> {
+        let parse_char = |ch| match ch {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        let grid = Grid::parse_lines_with(input, parse_char)?;
+        Ok(Self { grid })
+    }
+
+    /// Iterate over a toboggan route
+    pub fn path(
+        &self,
+        down: usize,
+        right: usize,
+    ) -> impl Iterator<Item = bool> + '_ {
+        (0..(self.grid.rows())).step_by(down).enumerate().map(
+            move |(step, row)| {
+                *self.grid.get_wrapping((row, step * right)).unwrap()
+            },
+        )
+    }
+    /// Count the trees along a toboggan route
+    pub fn count_trees(&self, down: usize, right: usize) -> usize {
+        self.path(down, right).filter(|&x| x).count()
+    }
+}
+impl Display for TobogganMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let chars = self.grid.map(|&tree| if tree { '#' } else { '.' });
+        write!(f, "{}", chars)
+    }
+}
+
+pub fn solve_part1(tob_map: &TobogganMap) -> usize {
+    tob_map.count_trees(1, 3)
+}
+
+pub fn solve_part2(tob_map: &TobogganMap) -> usize {
+    let slopes = &[(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+    slopes.iter().map(|&(x, y)| tob_map.count_trees(x, y)).product()
+}
+
+/// Solution for day 3
+pub struct Day03;
+impl Solution for Day03 {
+    type Input = TobogganMap;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        TobogganMap::parse_input(lines)
+    }
+    fn part1(tob_map: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(tob_map).to_string())
+    }
+    fn part2(tob_map: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(tob_map).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    const EXAMPLE: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_example() {
+        let tob_map = Day03::parse(&str_to_vec(EXAMPLE)).unwrap();
+        assert_eq!(solve_part1(&tob_map), 7);
+        assert_eq!(solve_part2(&tob_map), 336);
+    }
+
+    #[test]
+    fn test_invalid_square() {
+        let err = Day03::parse(&str_to_vec("..#\n.?.")).unwrap_err();
+        assert_eq!((err.line, err.col), (Some(2), Some(2)));
+    }
+}
@@ -0,0 +1,87 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 5 Solution
+    2020-12-07
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines_with, ParseError, ParseResult};
+
+/// Check that a line is a boarding pass, i.e. only uses F, B, L, and R.
+pub fn check_board_pass(board_pass: &str) -> ParseResult<String> {
+    match board_pass.find(|ch| !"FBLR".contains(ch)) {
+        Some(j) => Err(ParseError::new(format!(
+            "invalid character in boarding pass: {:?}",
+            board_pass[j..].chars().next().unwrap()
+        ))
+        .at_col(j + 1)),
+        None => Ok(board_pass.to_owned()),
+    }
+}
+
+/// Decode a boarding pass (binary space partitioning) into a seat ID.
+pub fn seat_id(board_pass: &str) -> ParseResult<usize> {
+    check_board_pass(board_pass)?;
+    let mut seat: usize = 0;
+    for ch in board_pass.chars() {
+        let bit = usize::from(ch == 'B' || ch == 'R');
+        seat = seat
+            .checked_mul(2)
+            .and_then(|seat| seat.checked_add(bit))
+            .ok_or_else(|| ParseError::new("boarding pass too long"))?;
+    }
+    Ok(seat)
+}
+
+fn triangle_number(n: usize) -> usize {
+    n * (n + 1) / 2
+}
+
+/* Solutions */
+
+pub fn solve_part1(seat_ids: &[usize]) -> usize {
+    *seat_ids.iter().max().unwrap()
+}
+
+pub fn solve_part2(seat_ids: &[usize]) -> usize {
+    let min = *seat_ids.iter().min().unwrap();
+    let max = *seat_ids.iter().max().unwrap();
+    let total: usize = seat_ids.iter().sum();
+    // Formula for the answer
+    triangle_number(max) - triangle_number(min) + min - total
+}
+
+/// Solution for day 5
+pub struct Day05;
+impl Solution for Day05 {
+    type Input = Vec<usize>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        if lines.is_empty() {
+            return Err(ParseError::new("no boarding passes"));
+        }
+        parse_lines_with(lines, seat_id)
+    }
+    fn part1(seat_ids: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(seat_ids).to_string())
+    }
+    fn part2(seat_ids: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(seat_ids).to_string())
+    }
+}
+
+/* Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sead_id() {
+        assert_eq!(seat_id("BFFFBBFRRR"), Ok(567));
+        assert_eq!(seat_id("FFFBBBFRRR"), Ok(119));
+        assert_eq!(seat_id("BBFFBBFRLL"), Ok(820));
+        assert_eq!(seat_id("BFXFBBFRRR").unwrap_err().col, Some(3));
+        assert!(seat_id(&"B".repeat(100)).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 6 Solution
+    2020-12-07
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{line_groups, ParseResult};
+use std::collections::HashSet;
+
+/// Part 1: Total that at least one member in group answered yes
+pub fn some_yes(group: &[String]) -> usize {
+    group.iter().flat_map(|s| s.chars()).collect::<HashSet<char>>().len()
+}
+
+/// Part 2: Total # in group that every member answered yes
+pub fn all_yes(group: &[String]) -> usize {
+    let alphabet: HashSet<char> =
+        "abcdefghijklmnopqrstuvwxyz".chars().collect();
+    group
+        .iter()
+        .map(|s| s.chars().collect::<HashSet<char>>())
+        .fold(alphabet, |x, y| x.intersection(&y).cloned().collect())
+        .len()
+}
+
+pub fn solve_part1(data: &[Vec<String>]) -> usize {
+    data.iter().map(|group| some_yes(group)).sum()
+}
+
+pub fn solve_part2(data: &[Vec<String>]) -> usize {
+    data.iter().map(|group| all_yes(group)).sum()
+}
+
+/// Parse answers into groups (separated by blank lines).
+pub fn parse_input(lines: &[String]) -> Vec<Vec<String>> {
+    line_groups(lines).map(|(_, group)| group.to_vec()).collect()
+}
+
+/// Solution for day 6
+pub struct Day06;
+impl Solution for Day06 {
+    type Input = Vec<Vec<String>>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        Ok(parse_input(lines))
+    }
+    fn part1(data: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(data).to_string())
+    }
+    fn part2(data: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(data).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    const EXAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+
+    #[test]
+    fn test_example() {
+        let data = Day06::parse(&str_to_vec(EXAMPLE)).unwrap();
+        assert_eq!(data.len(), 5);
+        assert_eq!(solve_part1(&data), 11);
+        assert_eq!(solve_part2(&data), 6);
+    }
+}
@@ -0,0 +1,266 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 7 Solution
+    2020-12-07
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{line_to_words, parse_str, ParseError, ParseResult};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/*
+    BagGraph<V> gives a basic implementation of a directed multi-graph:
+    a bag contains a multiset of other bags.
+
+    It stores edges in both directions, and uses Clone on V to simplify
+    ownership issues.
+
+    It implements a basic DFS reachability search for part 1,
+    and querying the size of a bag (number of bags inside) for part 2.
+
+    Note: we assume that the graph is acylic for both part 1 and part 2.
+*/
+
+/// Directed multi-graph of bags: an edge (v1, v2) means v1 contains v2.
+#[derive(Clone, Debug)]
+pub struct BagGraph<V> {
+    bags: HashSet<V>,
+    bag_sources: HashMap<V, Vec<V>>,
+    bag_targets: HashMap<V, Vec<V>>,
+    bags_inside_memo: HashMap<V, usize>, // memoization for part 2
+}
+impl<V> BagGraph<V>
+where
+    V: Clone + Eq + Hash + PartialEq,
+{
+    /// Create an empty graph.
+    pub fn new() -> Self {
+        Self {
+            bags: HashSet::new(),
+            bag_sources: HashMap::new(),
+            bag_targets: HashMap::new(),
+            bags_inside_memo: HashMap::new(),
+        }
+    }
+    /// Add a bag (if not already present).
+    pub fn add_bag(&mut self, v: &V) {
+        if self.bags.insert(v.clone()) {
+            self.bag_sources.insert(v.clone(), Vec::new());
+            self.bag_targets.insert(v.clone(), Vec::new());
+        }
+    }
+    /// Add one copy of bag v2 inside bag v1.
+    pub fn add_edge(&mut self, v1: &V, v2: &V) {
+        self.add_bag(v1);
+        self.add_bag(v2);
+        self.bag_sources.get_mut(v2).unwrap().push(v1.clone());
+        self.bag_targets.get_mut(v1).unwrap().push(v2.clone());
+    }
+    /// Whether the bag has a rule (or appears in one).
+    pub fn contains(&self, v: &V) -> bool {
+        self.bags.contains(v)
+    }
+    /// Whether no bag (eventually) contains itself, by repeatedly removing
+    /// bags that contain nothing left.
+    pub fn is_acyclic(&self) -> bool {
+        let mut remaining: HashMap<&V, usize> = self
+            .bag_targets
+            .iter()
+            .map(|(v, targets)| (v, targets.len()))
+            .collect();
+        let mut empty: Vec<&V> = remaining
+            .iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&v, _)| v)
+            .collect();
+        let mut removed = 0;
+        while let Some(v) = empty.pop() {
+            removed += 1;
+            for source in &self.bag_sources[v] {
+                let count = remaining.get_mut(source).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    empty.push(source);
+                }
+            }
+        }
+        removed == self.bags.len()
+    }
+
+    // For part 1: reachability analysis using DFS
+    fn dfs(edges: &HashMap<V, Vec<V>>, start: &V) -> Vec<V> {
+        let mut visited = HashSet::new();
+        let mut to_visit = Vec::new();
+        let mut result = Vec::new();
+        to_visit.push(start);
+        while let Some(u) = to_visit.pop() {
+            if !visited.contains(u) {
+                result.push(u.clone());
+                visited.insert(u);
+                for v in edges.get(u).unwrap() {
+                    to_visit.push(v);
+                }
+            }
+        }
+        result
+    }
+    /// All bags which (eventually) contain sink, including sink itself.
+    pub fn reachable_to(&self, sink: &V) -> HashSet<V> {
+        Self::dfs(&self.bag_sources, sink).into_iter().collect()
+    }
+    /// Number of bags which (eventually) contain sink, including sink.
+    pub fn count_reachable_inclusive(&self, sink: &V) -> usize {
+        self.reachable_to(sink).len()
+    }
+    /// Number of bags which (eventually) contain sink.
+    pub fn count_reachable(&self, sink: &V) -> usize {
+        // subtract one for this bag itself
+        // Note: this assumes acyclicity
+        self.count_reachable_inclusive(sink) - 1
+    }
+
+    // For part 2: querying number of bags
+    /// Total number of bags inside bag (memoized).
+    pub fn bags_inside(&mut self, bag: &V) -> usize {
+        if self.bags_inside_memo.contains_key(bag) {
+            *self.bags_inside_memo.get(bag).unwrap()
+        } else {
+            let answer = self.bags_inside_rec(bag);
+            self.bags_inside_memo.insert(bag.clone(), answer);
+            answer
+        }
+    }
+    /// Total number of bags inside bag, plus one for bag itself.
+    pub fn bags_inside_inclusive(&mut self, bag: &V) -> usize {
+        // including this bag itself
+        self.bags_inside(bag) + 1
+    }
+    fn bags_inside_rec(&mut self, bag: &V) -> usize {
+        // Note: this is recursive, assumes acyclicity and will loop forever
+        // otherwise
+        let mut total = 0;
+        let nested_bags = self.bag_targets.get(bag).unwrap().clone();
+        for nested_bag in nested_bags {
+            total += self.bags_inside_inclusive(&nested_bag);
+        }
+        total
+    }
+}
+impl<V> Default for BagGraph<V>
+where
+    V: Clone + Eq + Hash + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of bags that can eventually contain a shiny gold bag.
+pub fn solve_part1(bag_graph: &BagGraph<String>) -> usize {
+    let shiny_gold = "shiny gold".to_owned();
+    bag_graph.count_reachable(&shiny_gold)
+}
+
+/// Number of bags inside a shiny gold bag.
+pub fn solve_part2(bag_graph: &mut BagGraph<String>) -> usize {
+    let shiny_gold = "shiny gold".to_owned();
+    bag_graph.bags_inside(&shiny_gold)
+}
+
+/// Parse the bag rules into a BagGraph.
+pub fn parse_input(lines: &[String]) -> ParseResult<BagGraph<String>> {
+    let mut bag_graph = BagGraph::new();
+    for (line_num, line) in lines.iter().enumerate() {
+        let err =
+            |msg: &str| Err(ParseError::new(msg).at_line(line_num + 1, line));
+        let words = line_to_words(line);
+        if words.len() < 4 || words[2] != "bags" || words[3] != "contain" {
+            return err("expected a rule like `X Y bags contain ...`");
+        }
+        let name = format!("{} {}", words[0], words[1]);
+        // Insert vertex
+        bag_graph.add_bag(&name);
+        // Two cases: "X Y bags contain no other bags" vs contains a list
+        if words[4..] == ["no", "other", "bags."] {
+            continue;
+        }
+        if words.len() == 4 || !words.len().is_multiple_of(4) {
+            return err("expected a list of `N X Y bags`");
+        }
+        for i in 1..(words.len() / 4) {
+            let item_num: usize = parse_str(&words[4 * i])
+                .map_err(|e| e.at_line(line_num + 1, line))?;
+            let item_name =
+                format!("{} {}", words[4 * i + 1], words[4 * i + 2]);
+            let rem = words[4 * i + 3].as_str();
+            if !["bag,", "bags,", "bag.", "bags."].contains(&rem) {
+                return err(&format!(
+                    "expected `bag` or `bags`, found {}",
+                    rem
+                ));
+            }
+            // Insert edges
+            for _i in 0..item_num {
+                bag_graph.add_edge(&name, &item_name);
+            }
+        }
+    }
+    Ok(bag_graph)
+}
+
+/// Solution for day 7
+pub struct Day07;
+impl Solution for Day07 {
+    type Input = BagGraph<String>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let bag_graph = parse_input(lines)?;
+        if !bag_graph.contains(&"shiny gold".to_owned()) {
+            return Err(ParseError::new("no rule for shiny gold bags"));
+        }
+        if !bag_graph.is_acyclic() {
+            return Err(ParseError::new("a bag (eventually) contains itself"));
+        }
+        Ok(bag_graph)
+    }
+    fn part1(bag_graph: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(bag_graph).to_string())
+    }
+    fn part2(bag_graph: &Self::Input) -> SolveResult<String> {
+        // Part 2 memoizes results in the graph, so work on a copy
+        Ok(solve_part2(&mut bag_graph.clone()).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    const EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    #[test]
+    fn test_example() {
+        let bag_graph = Day07::parse(&str_to_vec(EXAMPLE)).unwrap();
+        assert_eq!(Day07::part1(&bag_graph).unwrap(), "3");
+        assert_eq!(Day07::part2(&bag_graph).unwrap(), "32");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing = "light red bags contain 1 bright white bag.";
+        assert!(Day07::parse(&str_to_vec(missing)).is_err());
+        let cycle = "shiny gold bags contain 1 dark olive bag.\n\
+                     dark olive bags contain 2 shiny gold bags.";
+        assert!(Day07::parse(&str_to_vec(cycle)).is_err());
+    }
+}
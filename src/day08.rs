@@ -0,0 +1,138 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 8 Solution
+    2020-12-08
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{ParseError, ParseResult};
+use crate::vm::assemble;
+use crate::vm::cfg::Cfg;
+
+/*
+    The console itself (instructions, program state and execution) is in
+    the vm module, which also supports registers, arithmetic and
+    conditional jumps; day 8 programs only use acc, jmp and nop.
+*/
+pub use crate::vm::{Instruction, Program, State, Status};
+
+/// Value of the accumulator just before any instruction repeats.
+pub fn solve_part1(program: &[Instruction]) -> isize {
+    let mut st = State::new(program.to_vec());
+    st.execute();
+    st.acc()
+}
+
+/// Positions of the jmp/nop instructions whose swap makes the program
+/// terminate normally.
+pub fn find_swaps(program: &[Instruction]) -> Vec<usize> {
+    // The control-flow graph finds the instruction without rerunning the
+    // program for every possible swap (see vm::cfg), but only if it has
+    // no conditional jumps; otherwise, try every swap
+    let cfg = Cfg::new(program);
+    if cfg.is_exact() {
+        cfg.find_corrupted()
+    } else {
+        (0..program.len())
+            .filter(|&i| match with_flip(program, i) {
+                Some(prog_fixed) => {
+                    run(prog_fixed).status() == Status::HaltBottom
+                }
+                None => false,
+            })
+            .collect()
+    }
+}
+
+/// Value of the accumulator after fixing the program (by swapping a
+/// single jmp/nop) so that it terminates normally.
+pub fn solve_part2(program: &[Instruction]) -> isize {
+    let corrupted = find_swaps(program);
+    assert_eq!(corrupted.len(), 1, "expected exactly one jmp/nop to swap");
+    let st = run(with_flip(program, corrupted[0]).unwrap());
+    assert_eq!(st.status(), Status::HaltBottom);
+    st.acc()
+}
+
+// The program with instruction i swapped, if it is a jmp or nop
+fn with_flip(program: &[Instruction], i: usize) -> Option<Program> {
+    let mut prog_fixed = program.to_vec();
+    prog_fixed[i] = program[i].flipped()?;
+    Some(prog_fixed)
+}
+
+fn run(program: Program) -> State {
+    let mut st = State::new(program);
+    st.execute();
+    st
+}
+
+/// Solution for day 8
+pub struct Day08;
+impl Solution for Day08 {
+    type Input = Program;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let program = assemble(lines)?;
+        let swaps = find_swaps(&program).len();
+        if swaps != 1 {
+            return Err(ParseError::new(format!(
+                "expected exactly one jmp/nop to swap, found {}",
+                swaps
+            )));
+        }
+        Ok(program)
+    }
+    fn part1(program: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(program).to_string())
+    }
+    fn part2(program: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(program).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+    use crate::vm::Disassembly;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_example() {
+        let program = Day08::parse(&str_to_vec(EXAMPLE)).unwrap();
+        assert_eq!(solve_part1(&program), 5);
+        assert_eq!(solve_part2(&program), 8);
+        let listing = Disassembly(&program).to_string();
+        assert_eq!(listing.trim_end(), EXAMPLE);
+    }
+
+    #[test]
+    fn test_conditional_jump() {
+        // No exact control-flow graph: falls back to trying every swap
+        let raw = "jz acc +2\nacc +5\njmp -1\nacc +1";
+        let program = Day08::parse(&str_to_vec(raw)).unwrap();
+        assert_eq!(solve_part2(&program), 1);
+        let raw = "jz acc +2\njmp -1\nacc +1";
+        let program = Day08::parse(&str_to_vec(raw)).unwrap();
+        assert_eq!(solve_part2(&program), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // No swap, or more than one swap, makes the program terminate
+        assert!(Day08::parse(&[]).is_err());
+        assert!(Day08::parse(&str_to_vec("acc +1")).is_err());
+        assert!(Day08::parse(&str_to_vec("nop +0\nnop +0")).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 9 Solution
+    2020-12-09
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines, ParseResult};
+use std::collections::{HashMap, HashSet};
+
+/// Whether curr_num is the sum of two of the (25) previous numbers.
+pub fn is_valid(prev_nums: &[isize], curr_num: isize) -> bool {
+    assert!(prev_nums.len() == 25);
+    let mut nums_set = HashSet::new();
+    for &num in prev_nums {
+        if nums_set.contains(&(curr_num - num)) {
+            return true;
+        }
+        nums_set.insert(num);
+    }
+    false
+}
+
+/// First number which is not the sum of two of the 25 numbers before it.
+pub fn solve_part1(nums: &[isize]) -> isize {
+    for i in 25..nums.len() {
+        let prev_nums = &nums[(i - 25)..i];
+        if !is_valid(prev_nums, nums[i]) {
+            return nums[i];
+        }
+    }
+    unreachable!();
+}
+
+/// Sum of the min and max of a contiguous range summing to target.
+pub fn solve_part2(nums: &[isize], target: isize) -> isize {
+    let mut partial_sums = HashMap::new();
+    let mut sum = 0;
+    for i in 0..nums.len() {
+        partial_sums.insert(sum, i);
+        sum += nums[i];
+        if let Some(&j) = partial_sums.get(&(sum - target)) {
+            // Set of numbers we are after is index j through i, inclusive
+            let target_set: Vec<isize> = nums[j..(i + 1)].to_vec();
+            let min = target_set.iter().min().unwrap();
+            let max = target_set.iter().max().unwrap();
+            return min + max;
+        }
+    }
+    unreachable!();
+}
+
+/// Solution for day 9
+pub struct Day09;
+impl Solution for Day09 {
+    type Input = Vec<isize>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_lines(lines)
+    }
+    fn part1(nums: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(nums).to_string())
+    }
+    fn part2(nums: &Self::Input) -> SolveResult<String> {
+        // The target for part 2 is the answer to part 1
+        Ok(solve_part2(nums, solve_part1(nums)).to_string())
+    }
+}
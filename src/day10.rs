@@ -0,0 +1,121 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 10 Solution
+    2020-12-10
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines, ParseError, ParseResult};
+
+/// General setup: sort the joltages and add min/max
+pub fn preprocess_joltages(joltages: &mut Vec<usize>) {
+    let low = 0;
+    let high = joltages.iter().max().unwrap().saturating_add(3);
+    joltages.push(low);
+    joltages.push(high);
+    joltages.sort_unstable();
+}
+
+/// Part 1: output # of 1 diffs, # of 3 diffs
+/// Assumes joltages is sorted
+pub fn get_differences(joltages: &[usize]) -> (usize, usize) {
+    let mut ones = 0;
+    let mut threes = 0;
+    for i in 1..joltages.len() {
+        match joltages[i] - joltages[i - 1] {
+            1 => ones += 1,
+            3 => threes += 1,
+            _ => (),
+        }
+    }
+    (ones, threes)
+}
+
+/// Check that every adapter can be used: each joltage is 1 to 3 above
+/// the previous one.
+/// Assumes joltages is sorted
+pub fn check_chain(joltages: &[usize]) -> ParseResult<()> {
+    for pair in joltages.windows(2) {
+        let diff = pair[1] - pair[0];
+        if !(1..=3).contains(&diff) {
+            return Err(ParseError::new(format!(
+                "cannot chain adapters from {} to {} jolts",
+                pair[0], pair[1]
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Part 2: count # of arrangements
+/// Assumes joltages is sorted
+pub fn count_arrangements(joltages: &[usize]) -> usize {
+    let mut counts = vec![1]; // # of arrangements ending in i
+    for i in 1..joltages.len() {
+        let mut new_count = 0;
+        for j in 1..=i {
+            if joltages[i] - joltages[i - j] <= 3 {
+                new_count += counts[i - j];
+            } else {
+                break;
+            }
+        }
+        counts.push(new_count);
+    }
+    assert_eq!(joltages.len(), counts.len());
+    counts[counts.len() - 1]
+}
+
+/// Solution for day 10
+pub struct Day10;
+impl Solution for Day10 {
+    type Input = Vec<usize>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let mut joltages: Vec<usize> = parse_lines(lines)?;
+        if joltages.is_empty() {
+            return Err(ParseError::new("no adapters"));
+        }
+        preprocess_joltages(&mut joltages);
+        check_chain(&joltages)?;
+        Ok(joltages)
+    }
+    fn part1(joltages: &Self::Input) -> SolveResult<String> {
+        let (ones, threes) = get_differences(joltages);
+        Ok((ones * threes).to_string())
+    }
+    fn part2(joltages: &Self::Input) -> SolveResult<String> {
+        Ok(count_arrangements(joltages).to_string())
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joltages_example() -> Vec<usize> {
+        let mut joltages = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+        preprocess_joltages(&mut joltages);
+        joltages
+    }
+
+    #[test]
+    fn test_get_differences() {
+        assert_eq!(get_differences(&joltages_example()), (7, 5));
+    }
+
+    #[test]
+    fn test_count_arrangements() {
+        assert_eq!(count_arrangements(&joltages_example()), 8)
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use crate::util::str_to_vec;
+        assert!(Day10::parse(&[]).is_err());
+        assert!(Day10::parse(&str_to_vec("1\n5")).is_err());
+        assert!(Day10::parse(&str_to_vec("1\n1")).is_err());
+        assert!(Day10::parse(&str_to_vec("2\n4")).is_ok());
+    }
+}
@@ -0,0 +1,146 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 11 Solution
+    2020-12-11
+*/
+
+use crate::automaton::{Automaton, DenseAutomaton, Rule, SquareGrid, Topology};
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::util::grid::{Pos, DIRS_8};
+use crate::util::{Grid, ParseResult};
+use std::fmt;
+
+/*
+    The seating rules are a cellular automaton: occupied seats are alive,
+    floor is not part of the automaton at all (so it never changes), and a
+    seat is occupied if there are no occupied neighbors and emptied if there
+    are at least as many as the tolerance.
+
+    For part 1 the neighbors are the adjacent seats; for part 2 they are the
+    first seat seen in each direction, which is fixed since floor never
+    changes.
+*/
+
+/// Neighbors for part 2: the first seat in each of the 8 directions.
+struct LineOfSight<'a> {
+    seats: &'a Grid<char>,
+}
+impl Topology for LineOfSight<'_> {
+    type Cell = Pos;
+    fn neighbors(&self, &pos: &Pos) -> Vec<Pos> {
+        DIRS_8
+            .iter()
+            .filter_map(|&dir| {
+                self.seats.ray(pos, dir).find(|&pos| self.seats[pos] != '.')
+            })
+            .collect()
+    }
+}
+
+/// Seat layout: floor (`.`), empty seats (`L`) and occupied seats (`#`).
+#[derive(Clone, Debug)]
+pub struct SeatMap {
+    layout: Grid<char>,
+    occupied: DenseAutomaton<Pos>,
+}
+impl SeatMap {
+    /// Seat map using either the adjacency rules (part 1) or the line of
+    /// sight rules (part 2).
+    pub fn new(seats: &Grid<char>, use_sight_rules: bool) -> Self {
+        let cells = seats.positions().filter(|&pos| seats[pos] != '.');
+        let cells = cells.collect();
+        let is_occupied = |&pos: &Pos| seats[pos] == '#';
+        let occupied = if use_sight_rules {
+            let topology = LineOfSight { seats };
+            DenseAutomaton::new(
+                &topology,
+                Rule::tolerance(5),
+                cells,
+                is_occupied,
+            )
+        } else {
+            let topology = SquareGrid::moore(seats.rows(), seats.cols());
+            DenseAutomaton::new(
+                &topology,
+                Rule::tolerance(4),
+                cells,
+                is_occupied,
+            )
+        };
+        Self { layout: seats.clone(), occupied }
+    }
+    /// Apply one round of the seating rules. Returns true if changed.
+    pub fn step(&mut self) -> bool {
+        let before = self.occupied.state();
+        self.occupied.step();
+        self.occupied.state() != before
+    }
+    /// Apply rounds until no seats change.
+    pub fn step_until_stable(&mut self) {
+        let count = self.occupied.step_until_stable(usize::MAX).unwrap();
+        diagnostic(format!("reached stable after {} steps", count));
+    }
+    pub fn count_occupied(&self) -> usize {
+        self.occupied.count_alive()
+    }
+}
+impl fmt::Display for SeatMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seats =
+            Grid::from_fn(self.layout.rows(), self.layout.cols(), |pos| {
+                match self.layout[pos] {
+                    '.' => '.',
+                    _ if self.occupied.is_alive(&pos) => '#',
+                    _ => 'L',
+                }
+            });
+        write!(f, "{}", seats)
+    }
+}
+
+pub fn solve_part1(seats: &Grid<char>) -> usize {
+    let mut seat_map = SeatMap::new(seats, false);
+    seat_map.step_until_stable();
+    seat_map.count_occupied()
+}
+
+pub fn solve_part2(seats: &Grid<char>) -> usize {
+    let mut seat_map = SeatMap::new(seats, true);
+    seat_map.step_until_stable();
+    seat_map.count_occupied()
+}
+
+/// Solution for day 11
+pub struct Day11;
+impl Solution for Day11 {
+    type Input = Grid<char>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        Grid::parse_lines_with(lines, |ch| {
+            Some(ch).filter(|ch| ".L#".contains(*ch))
+        })
+    }
+    fn part1(seats: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(seats).to_string())
+    }
+    fn part2(seats: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(seats).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::file_to_vec;
+
+    #[test]
+    fn test_sample_input() {
+        let lines = file_to_vec("input/day11_test.txt").unwrap();
+        let seats = Day11::parse(&lines).unwrap();
+        assert_eq!(solve_part1(&seats), 37);
+        assert_eq!(solve_part2(&seats), 26);
+        let mut seat_map = SeatMap::new(&seats, false);
+        assert!(seat_map.step());
+        assert_eq!(seat_map.to_string(), seats.to_string().replace('L', "#"));
+    }
+}
@@ -0,0 +1,162 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 12 Solution
+    2020-12-12
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines_with, parse_str, ParseError, ParseResult};
+
+/// A direction (or waypoint) as an (x, y) offset.
+#[derive(Clone, Copy, Debug)]
+pub struct Dir {
+    dx: isize,
+    dy: isize,
+}
+pub const DIR_N: Dir = Dir { dx: 0, dy: 1 };
+pub const DIR_E: Dir = Dir { dx: 1, dy: 0 };
+pub const DIR_S: Dir = Dir { dx: 0, dy: -1 };
+pub const DIR_W: Dir = Dir { dx: -1, dy: 0 };
+impl Dir {
+    pub fn turn_clockwise(&mut self) {
+        let tmp_dx = self.dx;
+        self.dx = self.dy;
+        self.dy = -tmp_dx;
+    }
+}
+
+/// Ship position together with its heading or waypoint.
+#[derive(Debug)]
+pub struct ShipNav {
+    waypoint: Dir,
+    x: isize,
+    y: isize,
+}
+impl ShipNav {
+    /// Ship at the origin, facing east.
+    pub fn new() -> Self {
+        Self { waypoint: DIR_E, x: 0, y: 0 }
+    }
+    pub fn set_waypoint(&mut self, dx: isize, dy: isize) {
+        self.waypoint = Dir { dx, dy };
+    }
+    fn move_ship(&mut self, dir: Dir, amount: isize) {
+        debug_assert!(amount > 0);
+        self.x += dir.dx * amount;
+        self.y += dir.dy * amount;
+    }
+    fn move_waypoint(&mut self, dir: Dir, amount: isize) {
+        debug_assert!(amount > 0);
+        self.waypoint.dx += dir.dx * amount;
+        self.waypoint.dy += dir.dy * amount;
+    }
+    fn rotate_waypoint_clockwise(&mut self, amount: isize) {
+        debug_assert!(amount % 90 == 0);
+        let turns = amount / 90;
+        for _i in 0..turns {
+            self.waypoint.turn_clockwise()
+        }
+    }
+    /// Navigation action where N/E/S/W move the ship.
+    pub fn action_part1(&mut self, action: char, amount: isize) {
+        debug_assert!(amount > 0);
+        match action {
+            'N' => self.move_ship(DIR_N, amount),
+            'E' => self.move_ship(DIR_E, amount),
+            'S' => self.move_ship(DIR_S, amount),
+            'W' => self.move_ship(DIR_W, amount),
+            'F' => self.move_ship(self.waypoint, amount),
+            'R' => self.rotate_waypoint_clockwise(amount),
+            'L' => self.rotate_waypoint_clockwise(360 - amount),
+            _ => panic!(),
+        }
+    }
+    /// Navigation action where N/E/S/W move the waypoint.
+    pub fn action_part2(&mut self, action: char, amount: isize) {
+        debug_assert!(amount > 0);
+        match action {
+            'N' => self.move_waypoint(DIR_N, amount),
+            'E' => self.move_waypoint(DIR_E, amount),
+            'S' => self.move_waypoint(DIR_S, amount),
+            'W' => self.move_waypoint(DIR_W, amount),
+            'F' => self.move_ship(self.waypoint, amount),
+            'R' => self.rotate_waypoint_clockwise(amount),
+            'L' => self.rotate_waypoint_clockwise(360 - amount),
+            _ => panic!(),
+        }
+    }
+    /// Manhattan distance from the origin.
+    pub fn manhattan(&self) -> usize {
+        (self.x.abs() + self.y.abs()) as usize
+    }
+}
+impl Default for ShipNav {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a navigation instruction like `F10` or `R90`.
+pub fn parse_action(line: &str) -> ParseResult<(char, isize)> {
+    let action = line.chars().next().ok_or("empty instruction")?;
+    if !"NESWFRL".contains(action) {
+        let msg = format!("invalid action: {:?}", action);
+        return Err(ParseError::new(msg).at_col(1));
+    }
+    let amount: isize =
+        parse_str(&line[action.len_utf8()..]).map_err(|err| err.at_col(2))?;
+    let valid = match action {
+        'R' | 'L' => amount > 0 && amount <= 360 && amount % 90 == 0,
+        _ => amount > 0,
+    };
+    if !valid {
+        let msg = format!("invalid amount for {}: {}", action, amount);
+        return Err(ParseError::new(msg).at_col(2));
+    }
+    Ok((action, amount))
+}
+
+pub fn solve_part1(input: &[(char, isize)]) -> usize {
+    let mut ship = ShipNav::new();
+    for &(ch, amt) in input {
+        ship.action_part1(ch, amt);
+    }
+    ship.manhattan()
+}
+
+pub fn solve_part2(input: &[(char, isize)]) -> usize {
+    let mut ship = ShipNav::new();
+    ship.set_waypoint(10, 1);
+    for &(ch, amt) in input {
+        ship.action_part2(ch, amt);
+    }
+    ship.manhattan()
+}
+
+/// Solution for day 12
+pub struct Day12;
+impl Solution for Day12 {
+    type Input = Vec<(char, isize)>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_lines_with(lines, parse_action)
+    }
+    fn part1(input: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(input).to_string())
+    }
+    fn part2(input: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(input).to_string())
+    }
+}
+
+#[test]
+fn test_part1() {
+    let input = &[('F', 10), ('N', 3), ('F', 7), ('R', 90), ('F', 11)];
+    assert_eq!(solve_part1(input), 25);
+}
+
+#[test]
+fn test_part2() {
+    let input = &[('F', 10), ('N', 3), ('F', 7), ('R', 90), ('F', 11)];
+    assert_eq!(solve_part2(input), 286);
+}
@@ -0,0 +1,152 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 13 Solution
+    2020-12-13
+*/
+
+use crate::debug;
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::util::{parse_str, ParseError, ParseResult};
+
+/// Return the smallest multiple of n >= target
+pub fn smallest_multiple(n: usize, target: usize) -> usize {
+    n * target.div_ceil(n)
+}
+
+/// Earliest bus after target, times the wait for it (None if there are no
+/// buses).
+pub fn solve_part1(target: usize, buses: &[Option<usize>]) -> Option<usize> {
+    let (bus, time) = buses
+        .iter()
+        .flatten()
+        .map(|&bus| {
+            let multiple = smallest_multiple(bus, target);
+            debug!("    Bus {}: smallest multiple {}", bus, multiple);
+            (bus, multiple)
+        })
+        .min_by_key(|(_bus, time)| *time)?;
+    diagnostic(format!("best: bus {} with delay {}", bus, time - target));
+    Some(bus * (time - target))
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Chinese remainder theorem implementation.
+/// Returns the unique remainder mod lcm(mod1, mod2) which is rem1 mod mod1
+/// and rem2 mod mod2, with the lcm, or None if there is none (the moduli
+/// aren't relatively prime and the remainders disagree) or it overflows.
+pub fn chinese_remainder(
+    rem1: usize,
+    mod1: usize,
+    rem2: usize,
+    mod2: usize,
+) -> Option<(usize, usize)> {
+    if mod1 < mod2 {
+        chinese_remainder(rem2, mod2, rem1, mod1)
+    } else {
+        let lcm = (mod1 / gcd(mod1, mod2)).checked_mul(mod2)?;
+        let mut rem = rem1;
+        while (rem % mod2) != rem2 {
+            rem = rem.checked_add(mod1).filter(|&rem| rem < lcm)?;
+        }
+        Some((rem, lcm))
+    }
+}
+
+/// True modulus function that works for negative numbers
+pub fn modulo(num: isize, modulus: usize) -> usize {
+    let modulus = modulus as isize;
+    let result = ((num % modulus) + modulus) % modulus;
+    result as usize
+}
+
+/// Earliest timestamp where each bus departs at its offset in the list
+/// (None if there is no such time, or it overflows).
+pub fn solve_part2(buses: &[Option<usize>]) -> Option<usize> {
+    let (rem, _modulus) = buses
+        .iter()
+        .enumerate()
+        .filter_map(|(i, bus)| bus.map(|bus| (modulo(-(i as isize), bus), bus)))
+        .try_fold((0, 1), |(rem1, mod1), (rem2, mod2)| {
+            debug!(
+                "    Bus {}: folding ({}, {}), ({}, {})",
+                mod2, rem1, mod1, rem2, mod2
+            );
+            chinese_remainder(rem1, mod1, rem2, mod2)
+        })?;
+    Some(rem)
+}
+
+/// Solution for day 13
+pub struct Day13;
+impl Solution for Day13 {
+    type Input = (usize, Vec<Option<usize>>);
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        if lines.len() != 2 {
+            let msg = format!("expected 2 lines, found {}", lines.len());
+            return Err(ParseError::new(msg));
+        }
+        let target: usize =
+            parse_str(&lines[0]).map_err(|err| err.at_line(1, &lines[0]))?;
+        let mut buses = Vec::new();
+        let mut col = 1;
+        for s in lines[1].split(',') {
+            let bus = match s {
+                "x" => None,
+                _ => Some(parse_str(s).and_then(|bus| match bus {
+                    0 => Err(ParseError::new("bus ID must be positive")),
+                    _ => Ok(bus),
+                })),
+            };
+            let bus = bus
+                .transpose()
+                .map_err(|err| err.at_line(2, &lines[1]).at_col(col))?;
+            buses.push(bus);
+            col += s.len() + 1;
+        }
+        if buses.iter().all(|bus| bus.is_none()) {
+            return Err(ParseError::new("no buses").at_line(2, &lines[1]));
+        }
+        Ok((target, buses))
+    }
+    fn part1((target, buses): &Self::Input) -> SolveResult<String> {
+        let ans = solve_part1(*target, buses).ok_or("no buses")?;
+        Ok(ans.to_string())
+    }
+    fn part2((_target, buses): &Self::Input) -> SolveResult<String> {
+        let ans = solve_part2(buses).ok_or("no timestamp fits every bus")?;
+        Ok(ans.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    #[test]
+    fn test_example() {
+        let lines = str_to_vec("939\n7,13,x,x,59,x,31,19");
+        let (target, buses) = Day13::parse(&lines).unwrap();
+        assert_eq!(solve_part1(target, &buses), Some(295));
+        assert_eq!(solve_part2(&buses), Some(1068781));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(solve_part1(939, &[None, None]), None);
+        assert_eq!(solve_part2(&[]), Some(0));
+        // Not relatively prime
+        assert_eq!(solve_part2(&[Some(4), None, Some(6)]), Some(4));
+        assert_eq!(solve_part2(&[Some(4), Some(6)]), None);
+        assert_eq!(chinese_remainder(1, 4, 3, 6), Some((9, 12)));
+        assert_eq!(chinese_remainder(usize::MAX, usize::MAX, 0, 2), None);
+    }
+}
@@ -0,0 +1,216 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 14 Solution
+    2020-12-14
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{line_to_words, parse_lines_with, parse_str};
+use crate::util::{ParseError, ParseResult};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const MASK_LEN: usize = 36;
+
+/*
+    Bit Mask Logic
+    The mask is stored as two unsigned integers, where X = 0 and X = 1
+    respectively.
+*/
+pub type Mask = (u64, u64);
+
+/// A mask as written, e.g. `XX1X0`: at most 36 of 0, 1 or X.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaskSpec(String);
+impl MaskSpec {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+impl FromStr for MaskSpec {
+    type Err = ParseError;
+    fn from_str(raw: &str) -> ParseResult<Self> {
+        if raw.len() > MASK_LEN {
+            let msg = format!("mask longer than {}", MASK_LEN);
+            return Err(ParseError::new(msg));
+        }
+        match raw.find(|ch| !"01X".contains(ch)) {
+            Some(j) => {
+                Err(ParseError::new("expected 0, 1, or X").at_col(j + 1))
+            }
+            None => Ok(MaskSpec(raw.to_owned())),
+        }
+    }
+}
+
+// Value of a string of 0s and 1s (at most 64)
+fn parse_binary(raw: &str) -> u64 {
+    raw.chars().fold(0, |n, ch| 2 * n + u64::from(ch == '1'))
+}
+
+/// Parse a mask, e.g. `XX1X0`.
+pub fn parse_mask(mask: &MaskSpec) -> Mask {
+    let raw = mask.as_str();
+    (parse_binary(&raw.replace("X", "0")), parse_binary(&raw.replace("X", "1")))
+}
+/// Parse all the floating masks for a mask (0 is unchanged, X floating).
+pub fn parse_all_masks(mask: &MaskSpec) -> Vec<Mask> {
+    // For part 2: parse all possible masks.
+    // 0 becomes X, 1 becomes 1, and X becomes either 0 or 1.
+    let mut results = vec!["".to_owned()];
+    for ch in mask.as_str().chars() {
+        let to_append = match ch {
+            '0' => vec!['X'],
+            '1' => vec!['1'],
+            _ => vec!['0', '1'],
+        };
+        let mut new_results = Vec::new();
+        for prev in &results {
+            for new_ch in &to_append {
+                new_results.push(prev.to_owned() + &new_ch.to_string());
+            }
+        }
+        results = new_results;
+    }
+    results.into_iter().map(|s| parse_mask(&MaskSpec(s))).collect()
+}
+/// Apply a mask to a value.
+pub fn apply_mask(m: Mask, n: u64) -> u64 {
+    m.0 | (m.1 & n)
+}
+
+/*
+    Available commands
+    (and how they are executed)
+*/
+/// A line of the initialization program.
+#[derive(Clone, Debug)]
+pub enum Command {
+    SetMask(MaskSpec),
+    SetMem(u64, u64),
+}
+/// Parse `mask = ...` or `mem[...] = ...`.
+pub fn parse_command(raw: &str) -> ParseResult<Command> {
+    let words = line_to_words(raw);
+    if words.len() != 3 || words[1] != "=" {
+        return Err(ParseError::new("expected a command like `lhs = rhs`"));
+    }
+    // The right side is the last word
+    let rhs_col = raw.trim_end().len() - words[2].len() + 1;
+    if words[0] == "mask" {
+        let mask = &words[2];
+        if mask.len() != MASK_LEN || !mask.chars().all(|c| "01X".contains(c)) {
+            let msg = format!("expected {} of 0, 1, or X", MASK_LEN);
+            return Err(ParseError::new(msg).at_col(rhs_col));
+        }
+        Ok(Command::SetMask(MaskSpec(mask.to_owned())))
+    } else {
+        let loc = words[0]
+            .strip_prefix("mem[")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(|| ParseError::new("expected `mask` or `mem[N]`"))?;
+        let loc = parse_str(loc).map_err(|err| err.at_col(5))?;
+        let val = parse_str(&words[2]).map_err(|err| err.at_col(rhs_col))?;
+        Ok(Command::SetMem(loc, val))
+    }
+}
+/// Current mask and memory contents.
+pub struct ProgState {
+    mask: MaskSpec,
+    memory: HashMap<u64, u64>,
+}
+impl ProgState {
+    pub fn new() -> Self {
+        ProgState { mask: MaskSpec("X".to_owned()), memory: HashMap::new() }
+    }
+    /// Execute a command, masking values written to memory.
+    pub fn execute_part1(&mut self, command: &Command) {
+        match command {
+            Command::SetMask(m) => {
+                self.mask = m.clone();
+            }
+            &Command::SetMem(loc, val) => {
+                let masked_val = apply_mask(parse_mask(&self.mask), val);
+                self.memory.insert(loc, masked_val);
+            }
+        }
+    }
+    /// Execute a command, masking (floating) memory addresses.
+    pub fn execute_part2(&mut self, command: &Command) {
+        match command {
+            Command::SetMask(m) => {
+                self.mask = m.clone();
+            }
+            &Command::SetMem(loc, val) => {
+                let masks = parse_all_masks(&self.mask);
+                for &mask in &masks {
+                    let masked_loc = apply_mask(mask, loc);
+                    self.memory.insert(masked_loc, val);
+                }
+            }
+        }
+    }
+}
+impl Default for ProgState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn solve_part1(prog: &[Command]) -> u64 {
+    let mut state = ProgState::new();
+    for comm in prog {
+        state.execute_part1(comm);
+    }
+    state.memory.iter().map(|(&_k, &v)| v).sum()
+}
+
+pub fn solve_part2(prog: &[Command]) -> u64 {
+    let mut state = ProgState::new();
+    for comm in prog {
+        state.execute_part2(comm);
+    }
+    state.memory.iter().map(|(&_k, &v)| v).sum()
+}
+
+/// Solution for day 14
+pub struct Day14;
+impl Solution for Day14 {
+    type Input = Vec<Command>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_lines_with(lines, parse_command)
+    }
+    fn part1(commands: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(commands).to_string())
+    }
+    fn part2(commands: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(commands).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masks() {
+        let mask: MaskSpec = "X1X0".parse().unwrap();
+        assert_eq!(parse_mask(&mask), (0b0100, 0b1110));
+        assert_eq!(apply_mask(parse_mask(&mask), 0b1011), 0b1110);
+        assert_eq!(parse_all_masks(&mask).len(), 4);
+        assert_eq!("X12".parse::<MaskSpec>().unwrap_err().col, Some(3));
+        assert!("X".repeat(37).parse::<MaskSpec>().is_err());
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let err = parse_command("mask  =  X1").unwrap_err();
+        assert_eq!(err.col, Some(10));
+        assert!(matches!(
+            parse_command("mem[8] = 11"),
+            Ok(Command::SetMem(8, 11))
+        ));
+        assert_eq!(parse_command("mem[8] = x1").unwrap_err().col, Some(10));
+    }
+}
@@ -0,0 +1,84 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 15 Solution
+    2020-12-15
+
+    Start time: 11:42am
+    Solved part 1: 12:11pm (29 min)
+    Solved part 2: 12:12pm (1 min)
+    Code cleanup: 12:23pm
+
+    Time (--release): 0m3.466s
+*/
+
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines, ParseError, ParseResult};
+use std::collections::HashMap;
+
+/// State of the memory game.
+#[derive(Default)]
+pub struct GameState {
+    // Most recent turn number and number spoken (None if no turns yet)
+    turn: usize,
+    last_spoken: Option<usize>,
+    // Distance when last_spoken was previously said, if it was a repeat
+    distance: Option<usize>,
+    // For each spoken number, the most recent turn it was said
+    memory: HashMap<usize, usize>,
+}
+impl GameState {
+    // Initial value and final answer
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn get_last_spoken(&self) -> usize {
+        assert!(self.turn > 0);
+        self.last_spoken.unwrap()
+    }
+    /// Starting turns call speak.
+    /// Turns after that call memory_turn.
+    pub fn speak(&mut self, num: usize) {
+        self.turn += 1;
+        self.last_spoken = Some(num);
+        self.distance = match self.memory.get(&num) {
+            None => None,
+            Some(prev) => Some(self.turn - prev),
+        };
+        self.memory.insert(num, self.turn);
+    }
+    pub fn memory_turn(&mut self) {
+        self.speak(self.distance.unwrap_or(0));
+    }
+}
+
+/// Number spoken on the given turn, starting from start_nums.
+pub fn solve_game(start_nums: &[usize], turns: usize) -> usize {
+    let mut game = GameState::new();
+    for i in 0..turns {
+        if i < start_nums.len() {
+            game.speak(start_nums[i]);
+        } else {
+            game.memory_turn();
+        }
+    }
+    game.get_last_spoken()
+}
+
+/// Solution for day 15
+pub struct Day15;
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        if lines.is_empty() {
+            return Err(ParseError::new("no starting numbers"));
+        }
+        parse_lines(lines)
+    }
+    fn part1(start_nums: &Self::Input) -> SolveResult<String> {
+        Ok(solve_game(start_nums, 2020).to_string())
+    }
+    fn part2(start_nums: &Self::Input) -> SolveResult<String> {
+        Ok(solve_game(start_nums, 30000000).to_string())
+    }
+}
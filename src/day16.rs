@@ -0,0 +1,314 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 16 Solution
+    2020-12-16
+
+    Start time: 11:41am
+    Solved part 1: 1:41pm (2 hr)
+    Solved part 2: 4:25pm (2 hr, 44 min)
+    Code cleanup: 4:55pm
+
+    Time (--release): 0m0.082s
+*/
+
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::util::{iter_to_pair, line_groups, parse_lines_with, parse_str};
+use crate::util::{ParseError, ParseResult};
+use crate::{debug, info};
+use z3::{ast::Bool, Config, Context, SatResult, Solver};
+
+/*
+    Struct to capture range constraints (e.g. 1-5 or 10-20 or 50-60)
+
+    Inspecting the input, all numbers are small (between 1 and 999), and the
+    range constraint boundaries are statically known.
+    Therefore the best way to store range constraints (unions of ranges) should
+    just be a vector<bool> of length 1000, not something fancier like a sorted
+    list of the range boundaries.
+*/
+const GLOBAL_UB: usize = 1000;
+/// Union of ranges of integers in 0 to 999.
+pub struct Ranges {
+    set: [bool; GLOBAL_UB],
+}
+impl Ranges {
+    // Constructors
+    pub fn new_empty() -> Self {
+        Self { set: [false; GLOBAL_UB] }
+    }
+    pub fn from_range(low: usize, high: usize) -> Self {
+        // Inclusive
+        let mut result = Self::new_empty();
+        for i in low..=high {
+            result.set[i] = true;
+        }
+        result
+    }
+    // Membership check
+    pub fn contains(&self, i: usize) -> bool {
+        debug_assert!(i < GLOBAL_UB);
+        self.set[i]
+    }
+    // Combining ranges (immutably)
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = Self::new_empty();
+        for i in 0..GLOBAL_UB {
+            result.set[i] = self.contains(i) || other.contains(i)
+        }
+        result
+    }
+}
+
+/*
+    Bipartite matching finder (for part 2)
+
+    Input: a square Boolean matrix of which inputs can match with which outputs
+    Output: A list of the output indices corresponding to each input index.
+
+    We outsource the constraint solving to Z3.
+*/
+/// Find a perfect matching given which inputs can match which outputs.
+pub fn find_matching(matchable: &[Vec<bool>]) -> Vec<usize> {
+    let n = matchable.len();
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let solver = Solver::new(&ctx);
+
+    // One variable per row, column (possible match)
+    let vars: Vec<Vec<_>> = (0..20)
+        .map(|i| {
+            (0..20)
+                .map(|j| Bool::new_const(&ctx, format!("match_{}_{}", i, j)))
+                .collect()
+        })
+        .collect();
+    let var_refs: Vec<Vec<_>> =
+        vars.iter().map(|vars_i| vars_i.iter().collect()).collect();
+    assert_eq!(vars.len(), n);
+    assert_eq!(var_refs.len(), n);
+
+    // Variables conform to matchable constraints
+    for i in 0..n {
+        for j in 0..n {
+            if !matchable[i][j] {
+                solver.assert(&var_refs[i][j].not());
+            }
+        }
+    }
+
+    // At least one match per row
+    for var_row in &var_refs {
+        solver.assert(&Bool::or(&ctx, var_row));
+    }
+
+    // At most one match per column
+    #[allow(clippy::needless_range_loop)]
+    for j in 0..n {
+        for i1 in 0..n {
+            for i2 in (i1 + 1)..n {
+                let both_i1_i2 =
+                    Bool::and(&ctx, &[var_refs[i1][j], var_refs[i2][j]]);
+                solver.assert(&both_i1_i2.not());
+            }
+        }
+    }
+
+    // Solve
+    debug!("Solver: {}", solver);
+    match solver.check() {
+        SatResult::Sat => {
+            let model = solver.get_model().unwrap();
+            debug!("Model: {:?}", model);
+            vars.iter()
+                .map(|var_row| {
+                    let matches: Vec<_> = var_row
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_i, var)| {
+                            model.eval(var).unwrap().as_bool().unwrap()
+                        })
+                        .map(|(i, _var)| i)
+                        .collect();
+                    assert_eq!(matches.len(), 1);
+                    matches[0]
+                })
+                .collect()
+        }
+        SatResult::Unsat => {
+            let unsat_core = solver.get_unsat_core();
+            info!("Unsat core: {:?}", unsat_core);
+            panic!("Constraints were unsatisfiable");
+        }
+        SatResult::Unknown => {
+            panic!("Z3 failed to solve constraints");
+        }
+    }
+}
+
+/*
+    Part 1
+*/
+fn merge_constraints(fields: &[(String, Ranges)]) -> Ranges {
+    fields
+        .iter()
+        .map(|(_field_name, r)| r)
+        .fold(Ranges::new_empty(), |r1, r2| r1.union(r2))
+}
+fn invalid_fields(ticket: &[usize], constraints: &Ranges) -> Vec<usize> {
+    ticket.iter().filter(|&&n| !constraints.contains(n)).cloned().collect()
+}
+/// Sum of all ticket values which are not valid for any field.
+pub fn solve_part1(
+    fields: &[(String, Ranges)],
+    tickets: &[Vec<usize>],
+) -> usize {
+    let constraints = merge_constraints(fields);
+    tickets.iter().flat_map(|ticket| invalid_fields(ticket, &constraints)).sum()
+}
+
+/*
+    Part 2
+*/
+fn field_matches(
+    valid_tickets: &[Vec<usize>],
+    index: usize,
+    constraints: &Ranges,
+) -> bool {
+    for ticket in valid_tickets {
+        if !constraints.contains(ticket[index]) {
+            return false;
+        }
+    }
+    true
+}
+/// Product of the six "departure" fields on your ticket.
+pub fn solve_part2(
+    fields: &[(String, Ranges)],
+    tickets: &[Vec<usize>],
+    your_ticket: &[usize],
+) -> usize {
+    let constraints = merge_constraints(fields);
+    let valid_tickets: Vec<Vec<usize>> = tickets
+        .iter()
+        .filter(|ticket| invalid_fields(ticket, &constraints).is_empty())
+        .cloned()
+        .collect();
+    let mut field_possibilities = vec![vec![]; 20];
+    for field in 0..20 {
+        for index in 0..20 {
+            field_possibilities[field].push(field_matches(
+                &valid_tickets,
+                index,
+                &fields[field].1,
+            ));
+        }
+    }
+    // Find bipartite matching
+    debug!("Matchable: {:?}", field_possibilities);
+    let matching = find_matching(&field_possibilities);
+    diagnostic(format!("matching: {:?}", matching));
+    // Find the six fields starting with "departure" and compute answer
+    let departure_fields: Vec<usize> = (0..20)
+        .filter(|&f| fields[f].0.split(' ').next().unwrap() == "departure")
+        .map(|f| matching[f])
+        .collect();
+    assert_eq!(departure_fields.len(), 6);
+    departure_fields.iter().map(|&f| your_ticket[f]).product()
+}
+
+/*
+    Parsing and entrypoint
+*/
+fn parse_value(raw: &str) -> ParseResult<usize> {
+    let n = parse_str(raw)?;
+    if n >= GLOBAL_UB {
+        let msg = format!("value too large (max {}): {}", GLOBAL_UB - 1, n);
+        return Err(ParseError::new(msg));
+    }
+    Ok(n)
+}
+fn parse_range(raw: &str) -> ParseResult<Ranges> {
+    let (low, high) = iter_to_pair(raw.split('-'))?;
+    Ok(Ranges::from_range(parse_value(low)?, parse_value(high)?))
+}
+fn parse_field(line: &str) -> ParseResult<(String, Ranges)> {
+    let (field_name, split0) = iter_to_pair(line.split(": "))?;
+    let (split1, split2) = iter_to_pair(split0.split(" or "))?;
+
+    let range1 = parse_range(split1)?;
+    let range2 = parse_range(split2)?;
+    let ranges = range1.union(&range2);
+
+    Ok((field_name.to_owned(), ranges))
+}
+fn parse_ticket(line: &str) -> ParseResult<Vec<usize>> {
+    let result: Vec<usize> =
+        line.split(',').map(parse_value).collect::<ParseResult<_>>()?;
+    if result.len() != 20 {
+        let msg = format!("expected 20 ticket values, found {}", result.len());
+        return Err(ParseError::new(msg));
+    }
+    Ok(result)
+}
+/// Fields, your ticket, and nearby tickets.
+pub type TicketNotes = (Vec<(String, Ranges)>, Vec<usize>, Vec<Vec<usize>>);
+// Parse a section of tickets, with a header line
+fn parse_tickets(
+    (start, section): (usize, &[String]),
+    header: &str,
+) -> ParseResult<Vec<Vec<usize>>> {
+    if section[0] != header {
+        let msg = format!("expected {:?}", header);
+        return Err(ParseError::new(msg).at_line(start + 1, &section[0]));
+    }
+    parse_lines_with(&section[1..], parse_ticket)
+        .map_err(|err| err.offset_lines(start + 1))
+}
+pub fn parse_input(lines: &[String]) -> ParseResult<TicketNotes> {
+    let sections: Vec<(usize, &[String])> = line_groups(lines).collect();
+    if sections.len() != 3 {
+        let msg = format!("expected 3 sections, found {}", sections.len());
+        return Err(ParseError::new(msg));
+    }
+
+    let (start, field_lines) = sections[0];
+    let fields: Vec<(String, Ranges)> =
+        parse_lines_with(field_lines, parse_field)
+            .map_err(|err| err.offset_lines(start))?;
+    if fields.len() != 20 {
+        let msg = format!("expected 20 fields, found {}", fields.len());
+        return Err(ParseError::new(msg).at_line(start + 1, &field_lines[0]));
+    }
+
+    let your_tickets = parse_tickets(sections[1], "your ticket:")?;
+    if your_tickets.len() != 1 {
+        let (start, section) = sections[1];
+        let msg = "expected exactly one ticket";
+        return Err(ParseError::new(msg).at_line(start + 1, &section[0]));
+    }
+    let your_ticket = your_tickets.into_iter().next().unwrap();
+
+    let tickets = parse_tickets(sections[2], "nearby tickets:")?;
+
+    Ok((fields, your_ticket, tickets))
+}
+
+/// Solution for day 16
+pub struct Day16;
+impl Solution for Day16 {
+    type Input = TicketNotes;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_input(lines)
+    }
+    fn part1(
+        (fields, _your_ticket, tickets): &Self::Input,
+    ) -> SolveResult<String> {
+        Ok(solve_part1(fields, tickets).to_string())
+    }
+    fn part2(
+        (fields, your_ticket, tickets): &Self::Input,
+    ) -> SolveResult<String> {
+        Ok(solve_part2(fields, tickets, your_ticket).to_string())
+    }
+}
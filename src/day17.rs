@@ -0,0 +1,95 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 17 Solution
+    2020-12-17
+
+    Start time: 2:40pm
+    Solved part 1: 3:40pm (1hr)
+    Solved part 2: 3:58pm (18 min)
+    Code cleanup: 6:00-7:25pm
+
+    Time (--release): 0m0.374s
+*/
+
+use crate::automaton::{Automaton, Lattice, Rule, SparseAutomaton};
+use crate::solution::{Solution, SolveResult};
+use crate::util::{Grid, ParseResult};
+
+/*
+    Conway cubes are the game of life on an infinite lattice, in 3 dimensions
+    for part 1 and 4 dimensions for part 2.
+
+    The puzzle states the rule as: an active cube stays active with 2 or 3
+    active neighbors, and an inactive cube becomes active with exactly 3.
+*/
+
+/// Infinite grid of active cubes, in any number of dimensions.
+pub type LifeGrid = SparseAutomaton<Lattice>;
+
+pub fn conway_rule() -> Rule {
+    Rule::new(&[3], &[2, 3])
+}
+
+/// Parse the initial 2D slice (`#` active, `.` inactive).
+pub fn parse_slice(lines: &[String]) -> ParseResult<Grid<bool>> {
+    Grid::parse_lines_with(lines, |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+}
+
+/// Grid with the given 2D slice active, embedded in a higher dimension.
+pub fn embed_slice(slice: &Grid<bool>, dimension: usize) -> LifeGrid {
+    assert!(dimension >= 2);
+    let active = slice.positions().filter(|&pos| slice[pos]).map(|(i, j)| {
+        let mut coord = vec![0; dimension];
+        coord[0] = i as isize;
+        coord[1] = j as isize;
+        coord
+    });
+    LifeGrid::new(Lattice::new(dimension), conway_rule(), active)
+}
+
+fn count_active_after_6(slice: &Grid<bool>, dimension: usize) -> usize {
+    let mut grid = embed_slice(slice, dimension);
+    grid.step_for(6);
+    grid.count_alive()
+}
+
+pub fn solve_part1(slice: &Grid<bool>) -> usize {
+    count_active_after_6(slice, 3)
+}
+
+pub fn solve_part2(slice: &Grid<bool>) -> usize {
+    count_active_after_6(slice, 4)
+}
+
+/// Solution for day 17
+pub struct Day17;
+impl Solution for Day17 {
+    type Input = Grid<bool>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_slice(lines)
+    }
+    fn part1(input: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(input).to_string())
+    }
+    fn part2(input: &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(input).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    #[test]
+    fn test_example() {
+        let slice = parse_slice(&str_to_vec(".#.\n..#\n###")).unwrap();
+        assert_eq!(solve_part1(&slice), 112);
+        assert_eq!(solve_part2(&slice), 848);
+    }
+}
@@ -0,0 +1,751 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 18 Solution
+    2020-12-18
+
+    Start time: 6:11pm
+    Solved part 1: 7:21pm (1 hr, 10 min)
+    Solved part 2: 8:31pm (1 hr, 10 min)
+
+    Time (--release): 0m0.051s
+*/
+
+pub mod interval;
+pub mod numeric;
+pub mod poly;
+pub mod pretty;
+
+use crate::debug;
+use crate::solution::{Solution, SolveError, SolveResult};
+use crate::util::{parse_lines_with, ParseError, ParseResult};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use numeric::Number;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/*
+    I originally solved this by defining expressions to be sequences of
+    tokens (including parentheses) and evaluating left-to-right using a
+    stack, with a different hand-written stack machine for each part. It was
+    tricky to think of the correct state to store during evaluation.
+
+    Instead, we now extract the parse tree before evaluation, using a
+    precedence-climbing (Pratt) parser: each operator has a precedence and
+    associativity given by a table, so the two parts are just two tables,
+    and evaluating the tree is the same for both.
+
+    The puzzle only uses single digits, + and *, but the expression language
+    is a bit more general:
+        Numbers of any length, including negative literals like -12
+        Binary operations + - * / % ^ (/ and % round toward zero)
+        Unary minus, which binds more tightly than any binary operation
+        (so -2 ^ 2 is 4)
+        Variables (letters, digits and underscores, starting with a letter
+        or underscore), whose values are given by an environment
+    Evaluation is generic over the Number type: checked 64-bit integers by
+    default, which report overflow, division by zero and so on as errors;
+    big integers; polynomials, leaving the variables symbolic; or
+    intervals, bounding the value over ranges of the variables (see numeric,
+    poly and interval).
+
+    The following data types define the syntax:
+        Binary operations
+        Tokens (operations, parens, numbers, or variables)
+        Expressions (parsable from strings)
+        Precedence tables (how tightly each operation binds)
+        Syntax trees (Expr)
+*/
+
+/// Errors evaluating an expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EvalError {
+    DivisionByZero,
+    Overflow,
+    NegativeExponent,
+    Unbound(String),
+    NotPolynomial(String),
+    Parse(ParseError),
+}
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "arithmetic overflow"),
+            EvalError::NegativeExponent => write!(f, "negative exponent"),
+            EvalError::Unbound(var) => write!(f, "unbound variable: {}", var),
+            EvalError::NotPolynomial(why) => {
+                write!(f, "not a polynomial: {}", why)
+            }
+            EvalError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+impl From<ParseError> for EvalError {
+    fn from(err: ParseError) -> Self {
+        EvalError::Parse(err)
+    }
+}
+pub type EvalResult<T> = Result<T, EvalError>;
+
+/// Values of variables.
+pub type Env = HashMap<String, i64>;
+
+/// Binary operations.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinOp {
+    Plus,
+    Minus,
+    Times,
+    Div,
+    Mod,
+    Pow,
+}
+impl BinOp {
+    pub const ALL: [BinOp; 6] = [
+        BinOp::Plus,
+        BinOp::Minus,
+        BinOp::Times,
+        BinOp::Div,
+        BinOp::Mod,
+        BinOp::Pow,
+    ];
+    pub fn symbol(&self) -> char {
+        match self {
+            BinOp::Plus => '+',
+            BinOp::Minus => '-',
+            BinOp::Times => '*',
+            BinOp::Div => '/',
+            BinOp::Mod => '%',
+            BinOp::Pow => '^',
+        }
+    }
+    pub fn from_symbol(ch: char) -> Option<Self> {
+        Self::ALL.iter().copied().find(|op| op.symbol() == ch)
+    }
+    pub fn apply(&self, left: i64, right: i64) -> EvalResult<i64> {
+        let result = match self {
+            BinOp::Plus => left.checked_add(right),
+            BinOp::Minus => left.checked_sub(right),
+            BinOp::Times => left.checked_mul(right),
+            BinOp::Div | BinOp::Mod if right == 0 => {
+                return Err(EvalError::DivisionByZero)
+            }
+            BinOp::Div => left.checked_div(right),
+            BinOp::Mod => left.checked_rem(right),
+            BinOp::Pow => return pow(left, right),
+        };
+        result.ok_or(EvalError::Overflow)
+    }
+}
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+fn pow(base: i64, exp: i64) -> EvalResult<i64> {
+    if exp < 0 {
+        return Err(EvalError::NegativeExponent);
+    }
+    match (u32::try_from(exp), base) {
+        (Ok(exp), _) => base.checked_pow(exp).ok_or(EvalError::Overflow),
+        // Huge exponents only fit for these bases
+        (Err(_), 0) | (Err(_), 1) => Ok(base),
+        (Err(_), -1) => Ok(if exp % 2 == 0 { 1 } else { -1 }),
+        (Err(_), _) => Err(EvalError::Overflow),
+    }
+}
+
+/// Tokens: operations, parens, numbers, or variables.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Token {
+    LParen,
+    RParen,
+    Op(BinOp),
+    Neg,
+    Num(i64),
+    Var(String),
+}
+impl Token {
+    // Whether an operand (rather than an operation) can follow
+    fn expects_operand(&self) -> bool {
+        matches!(self, Token::LParen | Token::Op(_) | Token::Neg)
+    }
+}
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Op(op) => write!(f, "{}", op),
+            Token::Neg => write!(f, "-"),
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Var(var) => write!(f, "{}", var),
+        }
+    }
+}
+
+/// Split a string into tokens, with the column (from 1) where each starts.
+/// A - is unary where an operand is expected, and is part of a negative
+/// literal if a digit follows immediately.
+pub fn lex(raw: &str) -> ParseResult<Vec<(usize, Token)>> {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut tokens: Vec<(usize, Token)> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, ch) = (i, chars[i]);
+        let expect_operand =
+            tokens.last().is_none_or(|(_, token)| token.expects_operand());
+        let is_digit =
+            |j: usize| chars.get(j).is_some_and(char::is_ascii_digit);
+        let word = |mut j: usize, pred: fn(&char) -> bool| {
+            while chars.get(j).is_some_and(pred) {
+                j += 1;
+            }
+            j
+        };
+        i += 1;
+        let token = match ch {
+            _ if ch.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '-' if expect_operand && !is_digit(i) => Token::Neg,
+            '-' if expect_operand => {
+                i = word(i, char::is_ascii_digit);
+                Token::Num(literal(&chars[start..i], start)?)
+            }
+            _ if ch.is_ascii_digit() => {
+                i = word(i, char::is_ascii_digit);
+                Token::Num(literal(&chars[start..i], start)?)
+            }
+            _ if ch.is_alphabetic() || ch == '_' => {
+                i = word(i, |&ch| ch.is_alphanumeric() || ch == '_');
+                Token::Var(chars[start..i].iter().collect())
+            }
+            _ => match BinOp::from_symbol(ch) {
+                Some(op) => Token::Op(op),
+                None => {
+                    return Err(ParseError::new(format!(
+                        "symbol not recognized: {}",
+                        ch
+                    ))
+                    .at_col(start + 1))
+                }
+            },
+        };
+        tokens.push((start + 1, token));
+    }
+    Ok(tokens)
+}
+
+fn literal(chars: &[char], start: usize) -> ParseResult<i64> {
+    let raw: String = chars.iter().collect();
+    raw.parse().map_err(|_| {
+        ParseError::new(format!("number out of range: {}", raw))
+            .at_col(start + 1)
+    })
+}
+
+/// Expression as a sequence of tokens.
+#[derive(Debug)]
+pub struct Expression {
+    tokens: Vec<Token>,
+    // Column (from 1) of each token, for errors
+    cols: Vec<usize>,
+}
+impl FromStr for Expression {
+    type Err = ParseError;
+    fn from_str(raw: &str) -> ParseResult<Self> {
+        // Check that operands and operators alternate and parens are
+        // balanced, so that evaluation can assume a well-formed expression
+        let (mut tokens, mut cols) = (Vec::new(), Vec::new());
+        let mut expect_operand = true;
+        let mut depth = 0;
+        for (col, token) in lex(raw)? {
+            let err = |msg| ParseError::new(msg).at_col(col);
+            match (&token, expect_operand) {
+                (Token::Num(_), true) | (Token::Var(_), true) => {
+                    expect_operand = false
+                }
+                (Token::Neg, true) => (),
+                (Token::LParen, true) => depth += 1,
+                (Token::RParen, false) if depth > 0 => depth -= 1,
+                (Token::Op(_), false) => expect_operand = true,
+                _ => return Err(err(format!("unexpected symbol: {}", token))),
+            }
+            tokens.push(token);
+            cols.push(col);
+        }
+        if expect_operand {
+            Err(ParseError::new("unexpected end of expression"))
+        } else if depth > 0 {
+            Err(ParseError::new("unclosed parenthesis"))
+        } else {
+            Ok(Self { tokens, cols })
+        }
+    }
+}
+
+/// Associativity of an operation: whether a + b + c groups as (a + b) + c
+/// or as a + (b + c).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// Precedence table: the precedence and associativity of each operation.
+/// Operations with higher precedence bind more tightly.
+pub type PrecTable = [(BinOp, usize, Assoc)];
+
+/// Part 1: + and * (and - / %) at the same precedence, evaluated
+/// left-to-right. ^ binds more tightly, grouping right-to-left.
+pub const PART1: &PrecTable = &[
+    (BinOp::Plus, 1, Assoc::Left),
+    (BinOp::Minus, 1, Assoc::Left),
+    (BinOp::Times, 1, Assoc::Left),
+    (BinOp::Div, 1, Assoc::Left),
+    (BinOp::Mod, 1, Assoc::Left),
+    (BinOp::Pow, 2, Assoc::Right),
+];
+
+/// Part 2: + (and -) at a higher precedence than * (and / %).
+pub const PART2: &PrecTable = &[
+    (BinOp::Plus, 2, Assoc::Left),
+    (BinOp::Minus, 2, Assoc::Left),
+    (BinOp::Times, 1, Assoc::Left),
+    (BinOp::Div, 1, Assoc::Left),
+    (BinOp::Mod, 1, Assoc::Left),
+    (BinOp::Pow, 3, Assoc::Right),
+];
+
+/// The usual precedence: ^ then * / % then + -.
+pub const STANDARD: &PrecTable = &[
+    (BinOp::Plus, 1, Assoc::Left),
+    (BinOp::Minus, 1, Assoc::Left),
+    (BinOp::Times, 2, Assoc::Left),
+    (BinOp::Div, 2, Assoc::Left),
+    (BinOp::Mod, 2, Assoc::Left),
+    (BinOp::Pow, 3, Assoc::Right),
+];
+
+fn binding(table: &PrecTable, op: BinOp) -> Option<(usize, Assoc)> {
+    table
+        .iter()
+        .find(|&&(table_op, _, _)| table_op == op)
+        .map(|&(_, prec, assoc)| (prec, assoc))
+}
+
+/// Syntax tree for an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Num(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    Op(BinOp, Box<Expr>, Box<Expr>),
+}
+impl Expr {
+    pub fn op(op: BinOp, left: Expr, right: Expr) -> Self {
+        Expr::Op(op, Box::new(left), Box::new(right))
+    }
+    /// Names of the variables in the expression.
+    pub fn variables(&self) -> BTreeSet<String> {
+        let mut vars = BTreeSet::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+    fn collect_variables(&self, vars: &mut BTreeSet<String>) {
+        match self {
+            Expr::Num(_) => (),
+            Expr::Var(var) => {
+                vars.insert(var.clone());
+            }
+            Expr::Neg(inner) => inner.collect_variables(vars),
+            Expr::Op(_, left, right) => {
+                left.collect_variables(vars);
+                right.collect_variables(vars);
+            }
+        }
+    }
+    /// Evaluate in any numeric type, with the variables in env.
+    pub fn eval_generic<N: Number>(
+        &self,
+        env: &HashMap<String, N>,
+    ) -> EvalResult<N> {
+        match self {
+            Expr::Num(n) => Ok(N::from_i64(*n)),
+            Expr::Var(var) => env
+                .get(var)
+                .cloned()
+                .ok_or_else(|| EvalError::Unbound(var.clone())),
+            Expr::Neg(inner) => inner.eval_generic(env)?.negate(),
+            Expr::Op(op, left, right) => N::apply(
+                *op,
+                &left.eval_generic(env)?,
+                &right.eval_generic(env)?,
+            ),
+        }
+    }
+    /// Evaluate with checked 64-bit arithmetic.
+    pub fn eval(&self, env: &Env) -> EvalResult<i64> {
+        self.eval_generic(env)
+    }
+}
+
+// Precedence-climbing parser over the tokens of an expression
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    table: &'a PrecTable,
+    // Position of each operation's token, in the order the operations are
+    // built: left operand, right operand, then the operation (post-order)
+    ops: Vec<usize>,
+}
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn atom(&mut self) -> ParseResult<Expr> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Var(var)) => Ok(Expr::Var(var)),
+            Some(Token::Neg) => Ok(Expr::Neg(Box::new(self.atom()?))),
+            Some(Token::LParen) => {
+                let inner = self.expr(0)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err(ParseError::new("expected closing parenthesis")),
+                }
+            }
+            _ => Err(ParseError::new("expected a number or parenthesis")),
+        }
+    }
+    // Operations of at least the given precedence
+    fn expr(&mut self, min_prec: usize) -> ParseResult<Expr> {
+        let mut left = self.atom()?;
+        while let Some(&Token::Op(op)) = self.tokens.get(self.pos) {
+            let (prec, assoc) = binding(self.table, op).ok_or_else(|| {
+                ParseError::new(format!("no precedence for {}", op))
+            })?;
+            if prec < min_prec {
+                break;
+            }
+            let pos = self.pos;
+            self.pos += 1;
+            // The right operand of a left-associative operation can't
+            // contain the same operation (outside of parentheses)
+            let right = match assoc {
+                Assoc::Left => self.expr(prec + 1)?,
+                Assoc::Right => self.expr(prec)?,
+            };
+            left = Expr::op(op, left, right);
+            self.ops.push(pos);
+        }
+        Ok(left)
+    }
+}
+
+// Evaluate a big integer expression without variables, counting the
+// operations applied (in post-order, since each operand is evaluated before
+// its operation), so that an error can be traced to its operation: the
+// operations applied before it fail are exactly those before it in
+// post-order.
+fn big_eval_counting(expr: &Expr, applied: &mut usize) -> EvalResult<BigInt> {
+    match expr {
+        Expr::Num(n) => Ok(BigInt::from(*n)),
+        Expr::Var(var) => Err(EvalError::Unbound(var.clone())),
+        Expr::Neg(inner) => Ok(-big_eval_counting(inner, applied)?),
+        Expr::Op(op, left, right) => {
+            let left = big_eval_counting(left, applied)?;
+            let right = big_eval_counting(right, applied)?;
+            let result = BigInt::apply(*op, &left, &right)?;
+            *applied += 1;
+            Ok(result)
+        }
+    }
+}
+
+impl Expression {
+    /// Syntax tree for the expression, given the precedence of each
+    /// operation. Fails only if an operation is missing from the table.
+    pub fn to_expr(&self, table: &PrecTable) -> ParseResult<Expr> {
+        self.to_expr_ops(table).map(|(expr, _)| expr)
+    }
+    // The syntax tree, and the position of each operation's token in
+    // post-order
+    fn to_expr_ops(
+        &self,
+        table: &PrecTable,
+    ) -> ParseResult<(Expr, Vec<usize>)> {
+        let tokens = &self.tokens;
+        let mut parser = Parser { tokens, pos: 0, table, ops: Vec::new() };
+        let expr = parser.expr(0)?;
+        if parser.pos < self.tokens.len() {
+            return Err(ParseError::new("unexpected closing parenthesis"));
+        }
+        debug!("Parsed: {:?}", expr);
+        Ok((expr, parser.ops))
+    }
+    /// Check that the expression has no variables and evaluates (as a big
+    /// integer) with the given precedence table, with the column of the
+    /// variable or operation at fault if not.
+    pub fn check_closed(&self, table: &PrecTable) -> ParseResult<()> {
+        for (token, &col) in self.tokens.iter().zip(&self.cols) {
+            if let Token::Var(var) = token {
+                let err = EvalError::Unbound(var.clone());
+                return Err(ParseError::new(err).at_col(col));
+            }
+        }
+        let (expr, ops) = self.to_expr_ops(table)?;
+        let mut applied = 0;
+        match big_eval_counting(&expr, &mut applied) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                Err(ParseError::new(err).at_col(self.cols[ops[applied]]))
+            }
+        }
+    }
+    /// Evaluate in any numeric type, with the given precedence table and
+    /// variables.
+    pub fn eval_as<N: Number>(
+        &self,
+        table: &PrecTable,
+        env: &HashMap<String, N>,
+    ) -> EvalResult<N> {
+        let result = self.to_expr(table)?.eval_generic(env)?;
+        debug!("Result: {}", result);
+        Ok(result)
+    }
+    /// Evaluate with the given precedence table and variables.
+    pub fn eval_with(&self, table: &PrecTable, env: &Env) -> EvalResult<i64> {
+        self.eval_as(table, env)
+    }
+    /// Evaluate left-to-right, with + and * at the same precedence.
+    pub fn eval_part1(&self) -> EvalResult<i64> {
+        self.eval_with(PART1, &Env::new())
+    }
+    /// Evaluate with + at a higher precedence than *.
+    pub fn eval_part2(&self) -> EvalResult<i64> {
+        self.eval_with(PART2, &Env::new())
+    }
+}
+
+// Sum of the expressions as big integers, so that the answers can't
+// overflow
+fn sum(input: &[Expression], table: &PrecTable) -> EvalResult<BigInt> {
+    input.iter().try_fold(BigInt::zero(), |total, expr| {
+        let value: BigInt = expr.eval_as(table, &HashMap::new())?;
+        Ok(total + value)
+    })
+}
+
+pub fn solve_part1(input: &[Expression]) -> EvalResult<BigInt> {
+    sum(input, PART1)
+}
+
+pub fn solve_part2(input: &[Expression]) -> EvalResult<BigInt> {
+    sum(input, PART2)
+}
+
+/// Solution for day 18
+pub struct Day18;
+impl Solution for Day18 {
+    type Input = Vec<Expression>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        // The puzzle has no variables, and each line must evaluate with
+        // both parts' precedence
+        parse_lines_with(lines, |raw| {
+            let expr: Expression = raw.parse()?;
+            expr.check_closed(PART1)?;
+            expr.check_closed(PART2)?;
+            Ok::<_, ParseError>(expr)
+        })
+    }
+    fn part1(input: &Self::Input) -> SolveResult<String> {
+        let total = solve_part1(input).map_err(SolveError::new)?;
+        Ok(total.to_string())
+    }
+    fn part2(input: &Self::Input) -> SolveResult<String> {
+        let total = solve_part2(input).map_err(SolveError::new)?;
+        Ok(total.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_part1(raw: &str, expected: i64) {
+        assert_eq!(
+            Expression::from_str(raw).unwrap().eval_part1(),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_part1("2 + 3", 5);
+        assert_part1("2 * 3", 6);
+        assert_part1("2 + 2 + 3", 7);
+        assert_part1("2 + 2 * 3", 12);
+        assert_part1("2 * 3 + 2", 8);
+        assert_part1("6 * (2 + 2)", 24);
+    }
+
+    fn assert_part2(raw: &str, expected: i64) {
+        assert_eq!(
+            Expression::from_str(raw).unwrap().eval_part2(),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_part2("2 + 3", 5);
+        assert_part2("2 * 3", 6);
+        assert_part2("2 + 2 + 3", 7);
+        assert_part2("2 + 2 * 3", 12);
+        assert_part2("2 * 3 + 2", 10);
+        assert_part2("6 * (2 + 2)", 24);
+    }
+
+    #[test]
+    fn test_big_answers() {
+        let big = "999999999999 * 999999999999 * 999999999";
+        let input: Vec<Expression> =
+            vec![big.parse().unwrap(), "1 + 2".parse().unwrap()];
+        assert_eq!(
+            Expression::from_str(big).unwrap().eval_part1(),
+            Err(EvalError::Overflow)
+        );
+        let expected: BigInt =
+            "999999998998000000002001000000002".parse().unwrap();
+        assert_eq!(solve_part1(&input), Ok(expected.clone()));
+        assert_eq!(solve_part2(&input), Ok(expected));
+    }
+
+    fn expr(raw: &str, table: &PrecTable) -> Expr {
+        Expression::from_str(raw).unwrap().to_expr(table).unwrap()
+    }
+
+    #[test]
+    fn test_to_expr() {
+        let (n1, n2, n3) = (Expr::Num(1), Expr::Num(2), Expr::Num(3));
+        let plus = |l, r| Expr::op(BinOp::Plus, l, r);
+        let times = |l, r| Expr::op(BinOp::Times, l, r);
+        assert_eq!(
+            expr("1 + 2 * 3", PART1),
+            times(plus(n1.clone(), n2.clone()), n3.clone())
+        );
+        assert_eq!(
+            expr("1 * 2 + 3", PART2),
+            times(n1.clone(), plus(n2.clone(), n3.clone()))
+        );
+        assert_eq!(expr("(1 + 2) + 3", PART2), expr("1 + 2 + 3", PART2));
+        let right: &PrecTable = &[(BinOp::Plus, 1, Assoc::Right)];
+        assert_eq!(expr("1 + 2 + 3", right), plus(n1, plus(n2, n3)));
+    }
+
+    #[test]
+    fn test_other_tables() {
+        // The usual precedence, with * binding more tightly
+        let usual: &PrecTable =
+            &[(BinOp::Plus, 1, Assoc::Left), (BinOp::Times, 2, Assoc::Left)];
+        let parsed = Expression::from_str("2 + 3 * 4 + 5").unwrap();
+        assert_eq!(parsed.eval_with(usual, &Env::new()), Ok(19));
+        let plus_only: &PrecTable = &[(BinOp::Plus, 1, Assoc::Left)];
+        assert!(parsed.eval_with(plus_only, &Env::new()).is_err());
+    }
+
+    #[test]
+    fn test_lex() {
+        let tokens: Vec<Token> =
+            lex("12-(-3)- -x_1").unwrap().into_iter().map(|(_, t)| t).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Num(12),
+                Token::Op(BinOp::Minus),
+                Token::LParen,
+                Token::Num(-3),
+                Token::RParen,
+                Token::Op(BinOp::Minus),
+                Token::Neg,
+                Token::Var("x_1".to_owned()),
+            ]
+        );
+        assert_eq!(lex("1 $ 2").unwrap_err().col, Some(3));
+        assert!(lex("99999999999999999999").is_err());
+        assert!(Expression::from_str("2 3").is_err());
+        assert!(Expression::from_str("2 * * 3").is_err());
+        assert!(Expression::from_str("(2 -)").is_err());
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_part1("100 - 30 / 7", 10);
+        assert_part2("100 - 30 / 7", 10);
+        assert_part2("2 * 3 - 1", 4);
+        assert_part1("-7 / 2", -3);
+        assert_part1("-7 % 2", -1);
+        assert_part1("2 ^ 3 ^ 2", 512);
+        assert_part1("-2 ^ 2", 4);
+        assert_part1("- (2 ^ 2)", -4);
+        assert_part1("--5", 5);
+        assert_part1("-9223372036854775808", i64::MIN);
+        assert_part1("1 ^ 10000000000", 1);
+    }
+
+    #[test]
+    fn test_variables() {
+        let parsed = Expression::from_str("x * (y + 1)").unwrap();
+        let mut env = Env::new();
+        env.insert("x".to_owned(), 3);
+        assert_eq!(
+            parsed.eval_with(PART2, &env),
+            Err(EvalError::Unbound("y".to_owned()))
+        );
+        env.insert("y".to_owned(), -5);
+        assert_eq!(parsed.eval_with(PART2, &env), Ok(-12));
+    }
+
+    #[test]
+    fn test_errors() {
+        let eval = |raw| Expression::from_str(raw).unwrap().eval_part1();
+        assert_eq!(eval("1 / (2 - 2)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("1 % 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("9223372036854775807 + 1"), Err(EvalError::Overflow));
+        assert_eq!(eval("-(-9223372036854775808)"), Err(EvalError::Overflow));
+        assert_eq!(eval("2 ^ 64"), Err(EvalError::Overflow));
+        assert_eq!(eval("2 ^ -1"), Err(EvalError::NegativeExponent));
+        assert_eq!(eval("1 / 0").unwrap_err().to_string(), "division by zero");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |raw: &str| Day18::parse(&[raw.to_owned()]).unwrap_err();
+        let unbound = err("garbage");
+        assert_eq!((unbound.line, unbound.col), (Some(1), Some(1)));
+        assert_eq!(unbound.cause, "unbound variable: garbage");
+        let div = err("1 + 2 * (3 / (1 - 1))");
+        assert_eq!(
+            (div.col, div.cause.as_str()),
+            (Some(12), "division by zero")
+        );
+        // The exponent is -6 with part 2's precedence (2 with part 1's)
+        let pow = err("2 ^ (3 * 1 - 2 * 2)");
+        assert_eq!(pow.col, Some(3));
+        let pow = err("2 ^ (1 - 2 * 3)");
+        assert_eq!(
+            (pow.col, pow.cause.as_str()),
+            (Some(3), "negative exponent")
+        );
+        assert!(Day18::parse(&["2 * 3 + (4 * 5)".to_owned()]).is_ok());
+    }
+}
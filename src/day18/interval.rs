@@ -0,0 +1,241 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Interval evaluation of day 18 expressions
+
+    Evaluating with each variable given a range of values, rather than a
+    single value, gives a range containing every value the expression can
+    take. The range is exact for a single operation, but can be wider than
+    necessary for a whole expression, since each operation forgets how its
+    operands depend on the variables (x - x is [-1, 1] for x in [0, 1]).
+
+    Each operation takes its extremes at a few candidate points:
+        + - * and / are monotone in each operand once the divisor's sign
+        is fixed, so only the corners matter
+        ^ also needs 0 as a base (0^2 is below (-1)^2 and 1^2), and both
+        parities of exponent at each end (the sign of a negative base
+        depends on them)
+        % is bounded by the divisor and the dividend, and is exact when
+        the quotient is the same throughout
+    An error for some values in the ranges -- dividing by a range
+    containing 0, or raising to a possibly negative power -- is an error
+    for the whole range.
+*/
+
+use super::numeric::{big_pow, Number};
+use super::{BinOp, EvalError, EvalResult, Expression, PrecTable};
+
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A nonempty range of integers, including both ends.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interval {
+    lo: BigInt,
+    hi: BigInt,
+}
+impl Interval {
+    pub fn new(lo: BigInt, hi: BigInt) -> Self {
+        assert!(lo <= hi, "empty interval: [{}, {}]", lo, hi);
+        Self { lo, hi }
+    }
+    pub fn point(n: BigInt) -> Self {
+        Self::new(n.clone(), n)
+    }
+    pub fn lo(&self) -> &BigInt {
+        &self.lo
+    }
+    pub fn hi(&self) -> &BigInt {
+        &self.hi
+    }
+    pub fn contains(&self, n: &BigInt) -> bool {
+        &self.lo <= n && n <= &self.hi
+    }
+    // Smallest interval containing the values
+    fn hull(values: Vec<BigInt>) -> Self {
+        let lo = values.iter().min().unwrap().clone();
+        let hi = values.iter().max().unwrap().clone();
+        Self::new(lo, hi)
+    }
+    // Interval of op over the candidate values of each side
+    fn corners(
+        op: BinOp,
+        left: &[&BigInt],
+        right: &[&BigInt],
+    ) -> EvalResult<Self> {
+        let mut values = Vec::new();
+        for &l in left {
+            for &r in right {
+                values.push(BigInt::apply(op, l, r)?);
+            }
+        }
+        Ok(Self::hull(values))
+    }
+
+    /*
+        Arithmetic
+    */
+    fn div(&self, other: &Self) -> EvalResult<Self> {
+        if other.contains(&BigInt::zero()) {
+            return Err(EvalError::DivisionByZero);
+        }
+        let (left, right) = ([&self.lo, &self.hi], [&other.lo, &other.hi]);
+        Self::corners(BinOp::Div, &left, &right)
+    }
+    fn rem(&self, other: &Self) -> EvalResult<Self> {
+        let quotients = self.div(other)?;
+        if other.lo == other.hi && quotients.lo == quotients.hi {
+            // self - q * d is increasing in self
+            let (lo, hi) = (&self.lo % &other.lo, &self.hi % &other.lo);
+            return Ok(Self::new(lo, hi));
+        }
+        // The remainder has the sign of the dividend, and is smaller in
+        // absolute value than both the dividend and the divisor
+        let bound = other.lo.abs().max(other.hi.abs()) - 1;
+        let lo = self.lo.clone().max(-&bound).min(BigInt::zero());
+        let hi = self.hi.clone().min(bound).max(BigInt::zero());
+        Ok(Self::new(lo, hi))
+    }
+    fn pow(&self, exp: &Self) -> EvalResult<Self> {
+        if exp.lo.is_negative() {
+            return Err(EvalError::NegativeExponent);
+        }
+        let zero = BigInt::zero();
+        let mut bases = vec![&self.lo, &self.hi];
+        if self.contains(&zero) {
+            bases.push(&zero);
+        }
+        let (lo_next, hi_prev) = (&exp.lo + 1, &exp.hi - 1);
+        let exps: Vec<&BigInt> = vec![&exp.lo, &lo_next, &hi_prev, &exp.hi]
+            .into_iter()
+            .filter(|&e| exp.contains(e))
+            .collect();
+        let mut values = Vec::new();
+        for &base in &bases {
+            for &e in &exps {
+                values.push(big_pow(base, e)?);
+            }
+        }
+        Ok(Self::hull(values))
+    }
+}
+
+impl Number for Interval {
+    fn from_i64(n: i64) -> Self {
+        Self::point(BigInt::from(n))
+    }
+    fn negate(&self) -> EvalResult<Self> {
+        Ok(Self::new(-&self.hi, -&self.lo))
+    }
+    fn apply(op: BinOp, left: &Self, right: &Self) -> EvalResult<Self> {
+        match op {
+            BinOp::Plus => {
+                Ok(Self::new(&left.lo + &right.lo, &left.hi + &right.hi))
+            }
+            BinOp::Minus => {
+                Ok(Self::new(&left.lo - &right.hi, &left.hi - &right.lo))
+            }
+            BinOp::Times => {
+                let (l, r) = ([&left.lo, &left.hi], [&right.lo, &right.hi]);
+                Self::corners(op, &l, &r)
+            }
+            BinOp::Div => left.div(right),
+            BinOp::Mod => left.rem(right),
+            BinOp::Pow => left.pow(right),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+impl Expression {
+    /// A range containing every value of the expression, given the
+    /// precedence of each operation and a range for each variable.
+    pub fn bounds(
+        &self,
+        table: &PrecTable,
+        env: &HashMap<String, Interval>,
+    ) -> EvalResult<Interval> {
+        self.eval_as(table, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day18::{PART1, PART2, STANDARD};
+    use std::str::FromStr;
+
+    fn interval(lo: i64, hi: i64) -> Interval {
+        Interval::new(BigInt::from(lo), BigInt::from(hi))
+    }
+
+    fn bounds(raw: &str, x: (i64, i64)) -> EvalResult<Interval> {
+        let mut env = HashMap::new();
+        env.insert("x".to_owned(), interval(x.0, x.1));
+        Expression::from_str(raw).unwrap().bounds(STANDARD, &env)
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(bounds("x * x", (-2, 3)), Ok(interval(-6, 9)));
+        assert_eq!(bounds("x ^ 2", (-2, 3)), Ok(interval(0, 9)));
+        assert_eq!(bounds("x ^ 3 - 1", (-2, 3)), Ok(interval(-9, 26)));
+        assert_eq!(bounds("x - x", (0, 1)), Ok(interval(-1, 1)));
+        assert_eq!(bounds("x / 2", (-5, 7)), Ok(interval(-2, 3)));
+        assert_eq!(bounds("x % 10", (12, 17)), Ok(interval(2, 7)));
+        assert_eq!(bounds("x % 10", (-3, 17)), Ok(interval(-3, 9)));
+        assert_eq!(bounds("7 % x", (2, 4)), Ok(interval(0, 3)));
+        assert_eq!(bounds("2 ^ x", (0, 10)), Ok(interval(1, 1024)));
+        assert_eq!(bounds("(-2) ^ x", (0, 3)), Ok(interval(-8, 4)));
+        assert_eq!(bounds("1 / x", (-1, 1)), Err(EvalError::DivisionByZero));
+        assert_eq!(bounds("2 ^ x", (-1, 1)), Err(EvalError::NegativeExponent));
+        assert_eq!(interval(-1, 4).to_string(), "[-1, 4]");
+    }
+
+    #[test]
+    fn test_points() {
+        let parsed = Expression::from_str("2 * 3 + (4 * 5) - 7 % 3").unwrap();
+        for &table in &[PART1, PART2] {
+            let value: BigInt = parsed.eval_as(table, &HashMap::new()).unwrap();
+            assert_eq!(
+                parsed.bounds(table, &HashMap::new()),
+                Ok(Interval::point(value))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sound() {
+        // Every value for x in the range is within the bounds
+        let exprs = [
+            "x * (x - 3) + 2",
+            "(x - 1) ^ (x + 3) - x ^ 3",
+            "100 / (x + 10) % (x - 8)",
+            "(x * -7) % 5 ^ (x + 3)",
+            "-x ^ 2 * 3 / (x + 12)",
+        ];
+        for raw in &exprs {
+            let parsed = Expression::from_str(raw).unwrap();
+            for lo in -3..=3 {
+                for hi in lo..=4 {
+                    let range = bounds(raw, (lo, hi)).unwrap();
+                    for x in lo..=hi {
+                        let env: HashMap<String, BigInt> =
+                            vec![("x".to_owned(), BigInt::from(x))]
+                                .into_iter()
+                                .collect();
+                        let value = parsed.eval_as(STANDARD, &env).unwrap();
+                        assert!(range.contains(&value), "{}: {}", raw, x);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Numeric backends for evaluating day 18 expressions
+
+    Expressions can be evaluated in any type implementing Number:
+        i64: checked 64-bit arithmetic; overflow is an error
+        BigInt: arbitrary precision, so only division by zero, negative
+        exponents and powers too large to store (over MAX_POW_BITS) are
+        errors
+        Poly: polynomials in the variables, for symbolic evaluation (see
+        day18::poly)
+        Interval: ranges of big integers, bounding the value over a range
+        for each variable (see day18::interval)
+
+    Division and remainder round toward zero for every backend.
+*/
+
+use super::{BinOp, EvalError, EvalResult};
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::fmt;
+
+/// Values that expressions can be evaluated to.
+pub trait Number: Clone + fmt::Debug + fmt::Display + PartialEq {
+    fn from_i64(n: i64) -> Self;
+    /// Unary minus
+    fn negate(&self) -> EvalResult<Self>;
+    fn apply(op: BinOp, left: &Self, right: &Self) -> EvalResult<Self>;
+}
+
+impl Number for i64 {
+    fn from_i64(n: i64) -> Self {
+        n
+    }
+    fn negate(&self) -> EvalResult<Self> {
+        self.checked_neg().ok_or(EvalError::Overflow)
+    }
+    fn apply(op: BinOp, left: &Self, right: &Self) -> EvalResult<Self> {
+        op.apply(*left, *right)
+    }
+}
+
+impl Number for BigInt {
+    fn from_i64(n: i64) -> Self {
+        BigInt::from(n)
+    }
+    fn negate(&self) -> EvalResult<Self> {
+        Ok(-self)
+    }
+    fn apply(op: BinOp, left: &Self, right: &Self) -> EvalResult<Self> {
+        match op {
+            BinOp::Plus => Ok(left + right),
+            BinOp::Minus => Ok(left - right),
+            BinOp::Times => Ok(left * right),
+            BinOp::Div | BinOp::Mod if right.is_zero() => {
+                Err(EvalError::DivisionByZero)
+            }
+            BinOp::Div => Ok(left / right),
+            BinOp::Mod => Ok(left % right),
+            BinOp::Pow => big_pow(left, right),
+        }
+    }
+}
+
+/// Largest power, in bits, that big_pow computes (2 MiB); bigger results
+/// are an Overflow error instead of an attempt to allocate them.
+pub const MAX_POW_BITS: u64 = 1 << 24;
+
+/// Power of a big integer, if it fits in MAX_POW_BITS.
+pub fn big_pow(base: &BigInt, exp: &BigInt) -> EvalResult<BigInt> {
+    if exp.is_negative() {
+        return Err(EvalError::NegativeExponent);
+    }
+    if base.is_zero() || base.is_one() {
+        return Ok(base.clone());
+    }
+    if (-base).is_one() {
+        let even = (exp % 2u32).is_zero();
+        return Ok(if even { BigInt::one() } else { base.clone() });
+    }
+    // The result has at most exp * base.bits() bits
+    match exp.to_u32() {
+        Some(exp)
+            if u64::from(exp).saturating_mul(base.bits()) <= MAX_POW_BITS =>
+        {
+            Ok(base.pow(exp))
+        }
+        _ => Err(EvalError::Overflow),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day18::{Env, Expression, PART1, PART2};
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    fn eval_big(raw: &str) -> EvalResult<BigInt> {
+        let parsed = Expression::from_str(raw).unwrap();
+        parsed.eval_as(PART1, &HashMap::new())
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        for raw in &["2 * 3 + (4 * 5)", "-7 / 2 % 3 - 1", "(-3) ^ 3 * x"] {
+            let parsed = Expression::from_str(raw).unwrap();
+            let mut env = Env::new();
+            env.insert("x".to_owned(), 5);
+            let big_env: HashMap<String, BigInt> =
+                vec![("x".to_owned(), BigInt::from(5))].into_iter().collect();
+            for &table in &[PART1, PART2] {
+                let small = parsed.eval_with(table, &env).unwrap();
+                let big: BigInt = parsed.eval_as(table, &big_env).unwrap();
+                assert_eq!(big, BigInt::from(small));
+            }
+        }
+    }
+
+    #[test]
+    fn test_big() {
+        let raw = "9999999999 * 9999999999 * 9999999999 + 1";
+        let parsed = Expression::from_str(raw).unwrap();
+        assert_eq!(parsed.eval_part1(), Err(EvalError::Overflow));
+        // (10^10 - 1)^3 + 1
+        assert_eq!(
+            eval_big(raw).unwrap().to_string(),
+            "999999999700000000030000000000"
+        );
+        assert_eq!(eval_big("2 ^ 100").unwrap(), BigInt::one() << 100);
+        assert_eq!(eval_big("(-1) ^ 9999999999"), Ok(BigInt::from(-1)));
+        assert_eq!(eval_big("2 ^ 9999999999"), Err(EvalError::Overflow));
+        assert_eq!(eval_big("2 ^ 4000000000"), Err(EvalError::Overflow));
+        assert_eq!(eval_big("(-2) ^ 16777216"), Err(EvalError::Overflow));
+        assert_eq!(eval_big("2 ^ 100000"), Ok(BigInt::one() << 100000));
+        assert_eq!(eval_big("0 ^ 4000000000"), Ok(BigInt::zero()));
+        assert_eq!(eval_big("1 % (1 - 1)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval_big("-7 / 2"), Ok(BigInt::from(-3)));
+    }
+}
@@ -0,0 +1,346 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Symbolic evaluation of day 18 expressions
+
+    Evaluating an expression with its variables left symbolic gives a
+    polynomial with integer coefficients, stored as a map from monomials
+    (variables with exponents) to nonzero coefficients. Each polynomial has
+    exactly one representation, so two expressions are equivalent -- equal
+    for all integer values of their variables -- iff their polynomials are
+    equal. (Equivalence is over unbounded integers: one of the expressions
+    may still overflow 64 bits where the other doesn't.)
+
+    + - * and unary minus work on any polynomials. ^ needs a constant
+    exponent, and / and % need both sides constant, since integer division
+    of a polynomial isn't a polynomial (x / 2 depends on whether x is odd);
+    anything else is a NotPolynomial error.
+*/
+
+use super::numeric::{big_pow, Number, MAX_POW_BITS};
+use super::{BinOp, EvalError, EvalResult, Expr, Expression, PrecTable};
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Product of variables, each with a positive exponent.
+pub type Monomial = BTreeMap<String, u32>;
+
+/// Polynomial with integer coefficients, in canonical form.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Poly {
+    // Coefficients are never zero
+    terms: BTreeMap<Monomial, BigInt>,
+}
+impl Poly {
+    pub fn constant(n: BigInt) -> Self {
+        let mut poly = Self::default();
+        poly.add_term(Monomial::new(), n);
+        poly
+    }
+    pub fn var(name: &str) -> Self {
+        let mut monomial = Monomial::new();
+        monomial.insert(name.to_owned(), 1);
+        let mut poly = Self::default();
+        poly.add_term(monomial, BigInt::one());
+        poly
+    }
+    /// The terms, as monomials with their (nonzero) coefficients.
+    pub fn terms(&self) -> impl Iterator<Item = (&Monomial, &BigInt)> {
+        self.terms.iter()
+    }
+    /// The value, if the polynomial is constant.
+    pub fn as_constant(&self) -> Option<BigInt> {
+        match self.terms.len() {
+            0 => Some(BigInt::zero()),
+            1 => self.terms.get(&Monomial::new()).cloned(),
+            _ => None,
+        }
+    }
+    /// Highest total degree of any term (0 for constants).
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().map(degree).max().unwrap_or(0)
+    }
+    fn add_term(&mut self, monomial: Monomial, coeff: BigInt) {
+        let sum = self.terms.remove(&monomial).unwrap_or_default() + coeff;
+        if !sum.is_zero() {
+            self.terms.insert(monomial, sum);
+        }
+    }
+
+    /*
+        Arithmetic
+    */
+    fn plus(&self, other: &Self) -> Self {
+        let mut sum = self.clone();
+        for (monomial, coeff) in &other.terms {
+            sum.add_term(monomial.clone(), coeff.clone());
+        }
+        sum
+    }
+    fn times(&self, other: &Self) -> EvalResult<Self> {
+        let mut product = Self::default();
+        for (m1, c1) in &self.terms {
+            for (m2, c2) in &other.terms {
+                let mut monomial = m1.clone();
+                for (var, exp) in m2 {
+                    let total = monomial.entry(var.clone()).or_insert(0);
+                    *total =
+                        total.checked_add(*exp).ok_or(EvalError::Overflow)?;
+                }
+                product.add_term(monomial, c1 * c2);
+            }
+        }
+        Ok(product)
+    }
+    fn pow(&self, exp: &Self) -> EvalResult<Self> {
+        let exp = exp.as_constant().ok_or_else(|| {
+            EvalError::NotPolynomial("non-constant exponent".to_owned())
+        })?;
+        if let Some(base) = self.as_constant() {
+            return Ok(Self::constant(big_pow(&base, &exp)?));
+        }
+        if exp.is_negative() {
+            return Err(EvalError::NegativeExponent);
+        }
+        let mut exp = exp.to_u32().ok_or(EvalError::Overflow)?;
+        if self.terms.len() == 1 {
+            let (monomial, coeff) = self.terms.iter().next().unwrap();
+            // A single term stays a single term, so only the coefficient
+            // can get big
+            let mut power = Monomial::new();
+            for (var, &n) in monomial.iter() {
+                let n = n.checked_mul(exp).ok_or(EvalError::Overflow)?;
+                power.insert(var.clone(), n);
+            }
+            let mut result = Self::default();
+            result.add_term(power, big_pow(coeff, &BigInt::from(exp))?);
+            return Ok(result);
+        }
+        // The coefficients are at most the sum of absolute values of the
+        // coefficients to the power exp, so this bounds the result's size
+        let norm: BigInt = self.terms.values().map(Signed::abs).sum();
+        let size = u64::from(exp)
+            .saturating_mul(norm.bits())
+            .saturating_mul(max_terms(self.terms.len(), exp));
+        if size > MAX_POW_BITS {
+            return Err(EvalError::Overflow);
+        }
+        // Repeated squaring
+        let (mut result, mut square) =
+            (Self::constant(BigInt::one()), self.clone());
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result.times(&square)?;
+            }
+            exp /= 2;
+            if exp > 0 {
+                square = square.times(&square)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn degree(monomial: &Monomial) -> u32 {
+    monomial.values().sum()
+}
+
+// Most terms that a power of a polynomial with the given number of terms
+// can have: the number of monomials of degree exp in that many unknowns,
+// (exp + terms - 1) choose (terms - 1), saturating
+fn max_terms(terms: usize, exp: u32) -> u64 {
+    let mut count: u128 = 1;
+    for i in 1..terms as u128 {
+        count = count * (u128::from(exp) + i) / i;
+        if count > u128::from(u64::MAX) {
+            return u64::MAX;
+        }
+    }
+    count as u64
+}
+
+impl Number for Poly {
+    fn from_i64(n: i64) -> Self {
+        Self::constant(BigInt::from(n))
+    }
+    fn negate(&self) -> EvalResult<Self> {
+        let terms = self.terms.iter().map(|(m, c)| (m.clone(), -c)).collect();
+        Ok(Self { terms })
+    }
+    fn apply(op: BinOp, left: &Self, right: &Self) -> EvalResult<Self> {
+        match op {
+            BinOp::Plus => Ok(left.plus(right)),
+            BinOp::Minus => Ok(left.plus(&right.negate()?)),
+            BinOp::Times => left.times(right),
+            BinOp::Pow => left.pow(right),
+            BinOp::Div | BinOp::Mod => {
+                match (left.as_constant(), right.as_constant()) {
+                    (Some(l), Some(r)) => {
+                        Ok(Self::constant(BigInt::apply(op, &l, &r)?))
+                    }
+                    _ => Err(EvalError::NotPolynomial(format!(
+                        "{} of non-constant polynomials",
+                        op
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+/// Prints terms from highest to lowest degree, like 3*x^2*y - x + 1.
+impl fmt::Display for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        let mut terms: Vec<_> = self.terms.iter().collect();
+        terms.sort_by_key(|(monomial, _)| Reverse(degree(monomial)));
+        for (i, (monomial, coeff)) in terms.into_iter().enumerate() {
+            let sign = if coeff.is_negative() { "-" } else { "+" };
+            match (i, sign) {
+                (0, "+") => (),
+                (0, _) => write!(f, "-")?,
+                _ => write!(f, " {} ", sign)?,
+            }
+            let mut factors = Vec::new();
+            if !coeff.abs().is_one() || monomial.is_empty() {
+                factors.push(coeff.abs().to_string());
+            }
+            for (var, &exp) in monomial {
+                factors.push(match exp {
+                    1 => var.clone(),
+                    _ => format!("{}^{}", var, exp),
+                });
+            }
+            write!(f, "{}", factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Expr {
+    /// The expression as a polynomial in its variables.
+    pub fn to_poly(&self) -> EvalResult<Poly> {
+        let env: HashMap<String, Poly> = self
+            .variables()
+            .into_iter()
+            .map(|var| {
+                let poly = Poly::var(&var);
+                (var, poly)
+            })
+            .collect();
+        self.eval_generic(&env)
+    }
+    /// Whether the two expressions are equal for all values of their
+    /// variables.
+    pub fn equivalent(&self, other: &Expr) -> EvalResult<bool> {
+        Ok(self.to_poly()? == other.to_poly()?)
+    }
+}
+
+impl Expression {
+    /// The expression as a polynomial, given the precedence of each
+    /// operation.
+    pub fn to_poly(&self, table: &PrecTable) -> EvalResult<Poly> {
+        self.to_expr(table)?.to_poly()
+    }
+}
+
+/// Whether two expressions, each parsed with its own precedence table, are
+/// equal for all values of their variables.
+pub fn equivalent(
+    left: (&Expression, &PrecTable),
+    right: (&Expression, &PrecTable),
+) -> EvalResult<bool> {
+    Ok(left.0.to_poly(left.1)? == right.0.to_poly(right.1)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day18::{PART1, PART2, STANDARD};
+    use std::str::FromStr;
+
+    fn poly(raw: &str, table: &PrecTable) -> EvalResult<Poly> {
+        Expression::from_str(raw).unwrap().to_poly(table)
+    }
+
+    #[test]
+    fn test_canonical() {
+        let square = poly("(x + 1) * (x + 1)", STANDARD).unwrap();
+        assert_eq!(square, poly("x ^ 2 + 2 * x + 1", STANDARD).unwrap());
+        assert_eq!(square, poly("(1 + x) ^ 2", STANDARD).unwrap());
+        assert_eq!(square.to_string(), "x^2 + 2*x + 1");
+        assert_eq!(square.degree(), 2);
+        let mixed = poly("y * x * -3 * x - y + 0 * z + 7 / 2", STANDARD);
+        assert_eq!(mixed.unwrap().to_string(), "-3*x^2*y - y + 3");
+        assert_eq!(poly("x - x", STANDARD).unwrap().to_string(), "0");
+        assert_eq!(
+            poly("2 ^ 10 - 1", STANDARD).unwrap().as_constant(),
+            Some(BigInt::from(1023))
+        );
+    }
+
+    #[test]
+    fn test_equivalent() {
+        let parse = |raw| Expression::from_str(raw).unwrap();
+        let (a, b) = (parse("x + y * z"), parse("(y * z) + x"));
+        assert_eq!(equivalent((&a, PART2), (&b, PART2)), Ok(false));
+        assert_eq!(equivalent((&a, STANDARD), (&b, STANDARD)), Ok(true));
+        assert_eq!(equivalent((&a, STANDARD), (&b, PART1)), Ok(true));
+        let c = parse("(x + y) * z");
+        assert_eq!(equivalent((&a, PART1), (&c, STANDARD)), Ok(true));
+        let d = parse("x * z + y * z");
+        assert_eq!(equivalent((&c, STANDARD), (&d, STANDARD)), Ok(true));
+    }
+
+    #[test]
+    fn test_not_polynomial() {
+        assert!(matches!(
+            poly("x / 2", STANDARD),
+            Err(EvalError::NotPolynomial(_))
+        ));
+        assert!(matches!(
+            poly("2 ^ x", STANDARD),
+            Err(EvalError::NotPolynomial(_))
+        ));
+        assert_eq!(poly("x ^ -1", STANDARD), Err(EvalError::NegativeExponent));
+    }
+
+    #[test]
+    fn test_big_powers() {
+        let huge = poly("(x * y) ^ 4000000000", STANDARD).unwrap();
+        assert_eq!(huge.to_string(), "x^4000000000*y^4000000000");
+        assert_eq!(
+            poly("x ^ 3000000000 * x ^ 3000000000", STANDARD),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(
+            poly("(x ^ 2) ^ 3000000000", STANDARD),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(
+            poly("(2 * x) ^ 4000000000", STANDARD),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(
+            poly("(x + 1) ^ 4000000000", STANDARD),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(
+            poly("(x + y + 1) ^ 100000", STANDARD),
+            Err(EvalError::Overflow)
+        );
+        let cube = poly("(x - 1) ^ 3", STANDARD).unwrap();
+        assert_eq!(cube.to_string(), "x^3 - 3*x^2 + 3*x - 1");
+        assert_eq!(
+            poly("x / 0 ^ 1", PART1).unwrap_err().to_string(),
+            "not a polynomial: / of non-constant polynomials"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Printing day 18 expressions
+
+    An Expression prints back as its tokens, and a syntax tree (Expr) prints
+    in one of two forms:
+        Fully parenthesized (Display): every operation in parentheses, so
+        it reads the same under any precedence table, like ((1 + 2) * 3)
+        Minimal (pretty): only the parentheses needed to parse back to the
+        same tree under a given precedence table
+
+    Printing the tree from one table minimally under another rewrites an
+    expression from one set of precedence rules to the other; e.g.
+    to_part1 shows how part 2 reads an expression, in terms of part 1's
+    left-to-right rule.
+
+    An operand needs parentheses when the parser would otherwise stop or
+    continue in the wrong place (see Parser::expr): the left operand of an
+    operation at precedence p if it is an operation that binds less tightly
+    (precedence below p, or p and right-associative), and the right operand
+    if the operation reads its right side only up to something looser
+    (precedence below p + 1 if left-associative, below p if right).
+*/
+
+use super::{binding, Assoc, Expr, Expression, PrecTable, Token, PART1, PART2};
+use crate::util::{ParseError, ParseResult};
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut prev: Option<&Token> = None;
+        for token in &self.tokens {
+            // A space between tokens, except inside parens and after a
+            // unary minus (unless that would make a negative literal)
+            let space = match (prev, token) {
+                (None, _) | (_, Token::RParen) => false,
+                (Some(Token::LParen), _) => false,
+                (Some(Token::Neg), Token::Num(_)) => true,
+                (Some(Token::Neg), _) => false,
+                _ => true,
+            };
+            if space {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", token)?;
+            prev = Some(token);
+        }
+        Ok(())
+    }
+}
+
+// Unary minus, followed by a space before a number so that it doesn't
+// become a negative literal
+fn write_neg(inner: &Expr, out: &mut String) {
+    match inner {
+        Expr::Num(_) => out.push_str("- "),
+        _ => out.push('-'),
+    }
+}
+
+/// Prints fully parenthesized.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Var(var) => write!(f, "{}", var),
+            Expr::Neg(inner) => {
+                let mut neg = String::new();
+                write_neg(inner, &mut neg);
+                write!(f, "{}{}", neg, inner)
+            }
+            Expr::Op(op, left, right) => {
+                write!(f, "({} {} {})", left, op, right)
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Every operation in parentheses.
+    pub fn fully_parenthesized(&self) -> String {
+        self.to_string()
+    }
+    /// Fewest parentheses such that parsing with the given table gives
+    /// back the same tree. Fails if an operation is missing from the table.
+    pub fn pretty(&self, table: &PrecTable) -> ParseResult<String> {
+        let mut out = String::new();
+        self.write_minimal(table, &mut out)?;
+        Ok(out)
+    }
+    fn write_minimal(
+        &self,
+        table: &PrecTable,
+        out: &mut String,
+    ) -> ParseResult<()> {
+        let lookup = |op| {
+            binding(table, op).ok_or_else(|| {
+                ParseError::new(format!("no precedence for {}", op))
+            })
+        };
+        match self {
+            Expr::Num(n) => write!(out, "{}", n).unwrap(),
+            Expr::Var(var) => out.push_str(var),
+            Expr::Neg(inner) => {
+                write_neg(inner, out);
+                let parens = matches!(**inner, Expr::Op(..));
+                write_operand(inner, parens, table, out)?;
+            }
+            Expr::Op(op, left, right) => {
+                let (prec, assoc) = lookup(*op)?;
+                let left_parens = match **left {
+                    Expr::Op(inner, _, _) => match lookup(inner)? {
+                        (inner_prec, Assoc::Left) => inner_prec < prec,
+                        (inner_prec, Assoc::Right) => inner_prec <= prec,
+                    },
+                    _ => false,
+                };
+                let right_parens = match **right {
+                    Expr::Op(inner, _, _) => {
+                        let inner_prec = lookup(inner)?.0;
+                        match assoc {
+                            Assoc::Left => inner_prec <= prec,
+                            Assoc::Right => inner_prec < prec,
+                        }
+                    }
+                    _ => false,
+                };
+                write_operand(left, left_parens, table, out)?;
+                write!(out, " {} ", op).unwrap();
+                write_operand(right, right_parens, table, out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_operand(
+    expr: &Expr,
+    parens: bool,
+    table: &PrecTable,
+    out: &mut String,
+) -> ParseResult<()> {
+    if parens {
+        out.push('(');
+    }
+    expr.write_minimal(table, out)?;
+    if parens {
+        out.push(')');
+    }
+    Ok(())
+}
+
+impl Expression {
+    /// Rewrite an expression read with one precedence table into one that
+    /// means the same when read with another, with as few parentheses as
+    /// possible.
+    pub fn convert(
+        &self,
+        from: &PrecTable,
+        to: &PrecTable,
+    ) -> ParseResult<Expression> {
+        Expression::from_str(&self.to_expr(from)?.pretty(to)?)
+    }
+    /// The expression as part 2 reads it, parenthesized for part 1.
+    pub fn to_part1(&self) -> Expression {
+        self.convert(PART2, PART1).expect("part tables have every operation")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day18::STANDARD;
+
+    fn parse(raw: &str) -> Expression {
+        Expression::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn test_display() {
+        let raw = "2 * (3 + -4) - - 5 ^ -(x % y_1)";
+        assert_eq!(parse(raw).to_string(), raw);
+        assert_eq!(parse("(( 1+2 ) )*3").to_string(), "((1 + 2)) * 3");
+    }
+
+    #[test]
+    fn test_fully_parenthesized() {
+        let expr = parse("1 + 2 * 3 + 4").to_expr(PART2).unwrap();
+        assert_eq!(expr.fully_parenthesized(), "((1 + 2) * (3 + 4))");
+        let expr = parse("-(2 ^ 3 ^ 2) * - 1").to_expr(STANDARD).unwrap();
+        assert_eq!(expr.to_string(), "(-(2 ^ (3 ^ 2)) * - 1)");
+    }
+
+    #[test]
+    fn test_pretty() {
+        let pretty = |raw, from, to| {
+            parse(raw).to_expr(from).unwrap().pretty(to).unwrap()
+        };
+        assert_eq!(pretty("((1 + 2)) * 3", PART1, PART1), "1 + 2 * 3");
+        assert_eq!(pretty("1 + 2 * 3", PART1, STANDARD), "(1 + 2) * 3");
+        assert_eq!(pretty("1 - (2 - 3)", STANDARD, STANDARD), "1 - (2 - 3)");
+        assert_eq!(pretty("(1 - 2) - 3", STANDARD, STANDARD), "1 - 2 - 3");
+        assert_eq!(pretty("(2 ^ 3) ^ 2", STANDARD, STANDARD), "(2 ^ 3) ^ 2");
+        assert_eq!(pretty("2 ^ (3 ^ 2)", STANDARD, STANDARD), "2 ^ 3 ^ 2");
+        assert_eq!(pretty("-(x) * -(1 + y)", PART2, PART2), "-x * -(1 + y)");
+        let expr = parse("1 ^ 2").to_expr(PART1).unwrap();
+        assert!(expr.pretty(&[]).is_err());
+    }
+
+    #[test]
+    fn test_to_part1() {
+        let converted = parse("2 * 3 + (4 * 5)").to_part1();
+        assert_eq!(converted.to_string(), "2 * (3 + (4 * 5))");
+        assert_eq!(converted.eval_part1(), Ok(46));
+        let converted = parse("5 + (8 * 3 + 9 + 3 * 4 * 3)").to_part1();
+        assert_eq!(converted.to_string(), "5 + (8 * (3 + 9 + 3) * 4 * 3)");
+        assert_eq!(converted.eval_part1(), Ok(1445));
+    }
+}
@@ -0,0 +1,667 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 19 Solution
+    2020-12-19 to 2020-12-20
+
+    Time (--release): 5m16.695s
+    Time (--release, DFA and Earley): 0m0.044s
+*/
+
+pub mod earley;
+pub mod regular;
+pub mod rules;
+
+use crate::debug;
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::util::{parse_str, ParseError, ParseResult};
+use regex::Regex;
+use rules::RuleExpr;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/*
+    SmartRegexMatcher
+
+    Specialized matcher designed for matching small strings against a set of
+    inter-defined regexes (rules): each inter-defined regex can be a union or
+    a concat of two other regexes in the set. (Rules in the input can be
+    longer; see rules for how they are split up.)
+
+    This matcher is not very efficient (on its own it took 5 minutes on
+    the puzzle input, and with --engine smart it still takes minutes,
+    whereas the day as a whole now runs in well under a second with a DFA
+    and the Earley parser; see below), but it works.
+
+    The assumption here is that it would be inefficient to simply expand out
+    regex 0 into a single regex recursively, as the expression tree might
+    contain the same regex many times (this is true even without loops);
+    similar to how a Boolean formula can be exponentially larger than a Boolean
+    circuit, in the worst case we might get a regex of size 2^m if we started
+    from m inter-defined regex rules.
+
+    # Part 1 solution and complexity
+    Our idea is to heavily rely on caching of match results.
+    For each string we are asked to match, we recursively call match on
+    substrings as appropriate, but as we do so we keep a memoization cache
+    of the match results for each (regex, start index, end index) triple.
+    As a result we are guaranteed to recurse only once for each such tuple,
+    which bounds the number of cache misses by O(n^2 m), and since each cache
+    miss does O(n) work (for the Concat case, recursing on all O(n) splits),
+    the worst-case time complexity is given by
+        O(n^3 m),
+    where n is the length of the string and m is the number of regexes (rules).
+
+    # Part 2 solution and complexity
+    For part 2, to deal with loops, we just need to additionally track (as we
+    recurse on regexes and substrings) the call stack (as a set) of which
+    (regex, start index, end index) triples we have seen. If we attempt to
+    recurse on a triple that is already in the call stack set, we know that
+    this is a loop and there is no need to explore it. Basically, each string
+    which matches must have a match that does not contain any loops in the match
+    tree. Keeping this additional information doesn't add any time overhead
+    beyond O(1) for each recursive call (to update the call stack before/after),
+    so the complexity is still
+        O(n^3 m).
+
+    One catch: a result of false that depends on cutting a loop is only
+    valid for the current call stack. (With 0: 0* | "a" 0, matching "a"
+    cuts the loop back to 0 on "a" inside 0*, before trying "a" 0.) So,
+    like lowlinks in Tarjan's algorithm, each call reports the shallowest
+    call on the stack that it cut a loop to, and a result is only cached if
+    it is true or doesn't depend on a loop cut above the call itself.
+    Uncached results may be recomputed, so this can be slower than the
+    bound above in the worst case, though not on part 2's rules.
+    Always cutting loops also means rules that recurse without repetition
+    (like 0: "a" | 0) can't recurse forever.
+
+    # Concrete time complexity
+    With the worst-case of a string of length 100 and 130 rules, this gives
+        130,000,000
+    operations per match.
+
+    # Space complexity
+    Since we reset the cache after each string match, the memory complexity
+    (cache size) is O(n^2 m) for part 1. For part 2, there is no a priori bound
+    on the size of the call stack but in practice it seems to be low enough.
+
+    # Regular rules
+    When the rules are regular (no recursion, or only in tail position, as
+    in part 1), we don't need any of this: count_matches compiles them to a
+    DFA (see regular), which matches each message in linear time.
+
+    # Other engines
+    The loop-cutting above is specific to how part 2's rules recurse. For
+    any context-free rules, earley has a general parser, which also gives
+    the parse trees for a message and how many there are. count_matches
+    uses it for rules that are genuinely context-free (like part 2's rule
+    11), where it takes well under a second; see Engine to choose one.
+*/
+
+pub type RegexId = u32;
+
+/// Definition of a single rule in terms of other rules.
+#[derive(Clone, Copy, Debug)]
+pub enum RegexCases {
+    Union(RegexId, RegexId),
+    Concat(RegexId, RegexId),
+    Noop(RegexId),
+    Char(char),
+    /// Matches only the empty string
+    Empty,
+}
+
+/// Memoizing matcher for a set of inter-defined rules (see above).
+#[derive(Clone, Default)]
+pub struct SmartRegexMatcher {
+    regex_defs: HashMap<RegexId, RegexCases>,
+    // Helper rules added by add_rule, and the rule each is part of
+    helpers: HashMap<RegexId, RegexId>,
+    // State related to the current string to match
+    match_cache: HashMap<(RegexId, usize, usize), bool>,
+    // Depth of each call on the stack
+    call_stack: HashMap<(RegexId, usize, usize), usize>,
+    // Debug information
+    cache_hits: usize,
+    cache_misses: usize,
+    loops_seen: usize,
+}
+impl SmartRegexMatcher {
+    /* Initialization */
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Define (or redefine) a rule.
+    pub fn add_regex(&mut self, id: RegexId, re: RegexCases) {
+        self.regex_defs.insert(id, re);
+    }
+    /// Whether a rule is defined.
+    pub fn is_defined(&self, id: RegexId) -> bool {
+        self.regex_defs.contains_key(&id)
+    }
+    /// A rule used in the definition of id, but not itself defined, if any.
+    pub fn undefined_ref(&self, id: RegexId) -> Option<RegexId> {
+        let refs = match self.regex_defs.get(&id)? {
+            &RegexCases::Union(id1, id2) => vec![id1, id2],
+            &RegexCases::Concat(id1, id2) => vec![id1, id2],
+            &RegexCases::Noop(id1) => vec![id1],
+            RegexCases::Char(_) | RegexCases::Empty => vec![],
+        };
+        refs.into_iter().find(|&id| !self.is_defined(id))
+    }
+
+    /* Debug info (printed with -vv) */
+    fn reset_debug_info(&mut self) {
+        self.cache_hits = 0;
+        self.cache_misses = 0;
+        self.loops_seen = 0;
+    }
+    fn cache_hit(&mut self) {
+        self.cache_hits += 1;
+    }
+    fn cache_miss(&mut self) {
+        self.cache_misses += 1;
+    }
+    fn loop_seen(&mut self) {
+        self.loops_seen += 1;
+    }
+    fn print_debug_info(&self) {
+        debug!("Cache hits: {}", self.cache_hits);
+        debug!("Cache misses: {}", self.cache_misses);
+        debug!("Loops seen: {}", self.loops_seen);
+        debug!("Cache size: {}", self.match_cache.len());
+    }
+
+    /*
+        Functionality
+        get_regex and eval_rec are for internal use.
+        The only exposed method is eval, which matches a regex against a string.
+
+        An assumption we make is that there are no multiple-byte chars.
+    */
+    fn get_regex(&mut self, id: RegexId) -> RegexCases {
+        *self.regex_defs.get(&id).unwrap()
+    }
+    // Whether rule id matches s, the part of the message from i to j. Loops
+    // are cut, and cut is lowered to the shallowest call stack depth of any
+    // loop cut that the result depends on.
+    fn eval_rec(
+        &mut self,
+        id: RegexId,
+        s: &str,
+        (i, j): (usize, usize),
+        cut: &mut usize,
+    ) -> bool {
+        if let Some(&result) = self.match_cache.get(&(id, i, j)) {
+            self.cache_hit();
+            result
+        } else if let Some(&depth) = self.call_stack.get(&(id, i, j)) {
+            // Loop found
+            self.loop_seen();
+            *cut = (*cut).min(depth);
+            false
+        } else {
+            self.cache_miss();
+            let depth = self.call_stack.len();
+            self.call_stack.insert((id, i, j), depth);
+            let mut low = usize::MAX;
+            let result = match self.get_regex(id) {
+                RegexCases::Union(id1, id2) => {
+                    self.eval_rec(id1, s, (i, j), &mut low)
+                        || self.eval_rec(id2, s, (i, j), &mut low)
+                }
+                RegexCases::Concat(id1, id2) => {
+                    // Either side may be empty
+                    let mut result = false;
+                    for split_point in 0..=s.len() {
+                        let (s1, s2) = s.split_at(split_point);
+                        let k = i + split_point;
+                        let b1 = self.eval_rec(id1, s1, (i, k), &mut low);
+                        let b2 = self.eval_rec(id2, s2, (k, j), &mut low);
+                        if b1 & b2 {
+                            result = true;
+                            break;
+                        }
+                    }
+                    result
+                }
+                RegexCases::Noop(id1) => {
+                    self.eval_rec(id1, s, (i, j), &mut low)
+                }
+                RegexCases::Char(ch) => s == ch.to_string(),
+                RegexCases::Empty => s.is_empty(),
+            };
+            self.call_stack.remove(&(id, i, j));
+            if result || low >= depth {
+                // Not a false negative from cutting a loop that is still
+                // on the call stack, so the result holds in any context
+                self.match_cache.insert((id, i, j), result);
+            } else {
+                *cut = (*cut).min(low);
+            }
+            result
+        }
+    }
+    /// Whether rule id matches the whole string s.
+    pub fn eval(&mut self, id: RegexId, s: &str) -> bool {
+        debug!("Matching: {}", s);
+        debug!("String len: {}", s.len());
+        let mut cut = usize::MAX;
+        let result = self.eval_rec(id, s, (0, s.len()), &mut cut);
+        debug!("Result: {}", result);
+        self.print_debug_info();
+        self.reset_debug_info();
+        // Reset caches and return
+        self.match_cache = HashMap::new();
+        self.call_stack = HashMap::new();
+        result
+    }
+
+    /* Answer */
+    /// Number of messages which match rule 0.
+    pub fn count_regex0_matches(&mut self, msgs: &[String]) -> usize {
+        msgs.iter().map(|s| self.eval(0, s)).filter(|&s| s).count()
+    }
+}
+
+/*
+    Input parsing and parts 1+2 solutions
+*/
+
+/// Parse rules and messages (separated by a blank line). See rules for
+/// the syntax of a rule.
+pub fn parse_input(
+    input_lines: &[String],
+) -> ParseResult<(SmartRegexMatcher, Vec<String>)> {
+    let rule = Regex::new(r"^(\d*): (.*)$").unwrap();
+    let msg = Regex::new(r"^([[:graph:]]*)$").unwrap();
+
+    // Collect lines into rules and a list of messages
+    let mut rules: Vec<(RegexId, RuleExpr, usize)> = Vec::new();
+    let mut msgs: Vec<String> = Vec::new();
+    let mut first_part = true;
+    // Line number where each rule was defined
+    let mut rule_lines: HashMap<RegexId, usize> = HashMap::new();
+    for (i, line) in input_lines.iter().enumerate() {
+        let at_line = |err: ParseError| err.at_line(i + 1, line);
+        if let Some(caps) = rule.captures(line) {
+            if !first_part {
+                return Err(at_line("rule after the messages".into()));
+            }
+            let id: RegexId = parse_str(&caps[1]).map_err(at_line)?;
+            if rule_lines.insert(id, i).is_some() {
+                let msg = format!("rule {} is defined twice", id);
+                return Err(at_line(msg.into()));
+            }
+            let expr = caps[2].parse().map_err(|err: ParseError| {
+                at_line(err.offset_cols(caps[1].len() + 2))
+            })?;
+            rules.push((id, expr, i));
+        } else if line.is_empty() {
+            if !first_part {
+                return Err(at_line("more than one blank line".into()));
+            }
+            first_part = false;
+        } else if msg.is_match(line) {
+            if first_part {
+                return Err(at_line("message before the blank line".into()));
+            }
+            msgs.push(line.to_string());
+        } else {
+            return Err(at_line("not a rule or message".into()));
+        }
+    }
+
+    // Check that every rule used is defined
+    if !rule_lines.contains_key(&0) {
+        return Err(ParseError::new("rule 0 is not defined"));
+    }
+    let mut matcher = SmartRegexMatcher::new();
+    for (id, expr, i) in &rules {
+        if let Some(undef) =
+            expr.refs().into_iter().find(|id| !rule_lines.contains_key(id))
+        {
+            let msg = format!("rule {} is not defined", undef);
+            return Err(ParseError::new(msg).at_line(i + 1, &input_lines[*i]));
+        }
+        matcher.add_rule(*id, expr);
+    }
+    // Helper rules are numbered down from RegexId::MAX
+    let clash = rule_lines.keys().filter(|&&id| matcher.is_helper(id)).min();
+    if let Some(id) = clash {
+        return Err(ParseError::new(format!("rule ID too large: {}", id)));
+    }
+
+    debug!("Rules: {:?}", matcher.regex_defs);
+    debug!("Messages: {:?}", msgs);
+
+    Ok((matcher, msgs))
+}
+
+/// Engine for matching messages against the rules.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Engine {
+    /// A DFA if the rules are regular, and otherwise the Earley parser
+    Auto,
+    Smart,
+    Earley,
+}
+impl FromStr for Engine {
+    type Err = ParseError;
+    fn from_str(s: &str) -> ParseResult<Self> {
+        match s {
+            "auto" => Ok(Engine::Auto),
+            "smart" => Ok(Engine::Smart),
+            "earley" => Ok(Engine::Earley),
+            _ => Err(ParseError::new(format!(
+                "Not a valid engine (auto, smart or earley): {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Number of messages which match rule 0: with a DFA if the rules are
+/// regular, and otherwise with the Earley parser.
+pub fn count_matches(matcher: &SmartRegexMatcher, msgs: &[String]) -> usize {
+    count_matches_with(Engine::Auto, matcher, msgs)
+}
+
+/// Number of messages which match rule 0, using the given engine.
+pub fn count_matches_with(
+    engine: Engine,
+    matcher: &SmartRegexMatcher,
+    msgs: &[String],
+) -> usize {
+    match engine {
+        Engine::Auto => match regular::Dfa::compile(matcher, 0) {
+            Ok(dfa) => {
+                diagnostic(format!(
+                    "matched with a {}-state DFA",
+                    dfa.num_states()
+                ));
+                msgs.iter().filter(|msg| dfa.is_match(msg)).count()
+            }
+            Err(err) => {
+                diagnostic(format!("not compiled to a DFA: {}", err));
+                count_matches_with(Engine::Earley, matcher, msgs)
+            }
+        },
+        Engine::Smart => matcher.clone().count_regex0_matches(msgs),
+        Engine::Earley => {
+            let parser = earley::EarleyParser::new(matcher);
+            msgs.iter().filter(|msg| parser.parse(0, msg).is_match()).count()
+        }
+    }
+}
+
+pub fn solve_part1(matcher: &SmartRegexMatcher, msgs: &[String]) -> usize {
+    count_matches(matcher, msgs)
+}
+
+/// Part 2's rules: rules 8 and 11 replaced by loops.
+pub fn part2_rules(matcher: &SmartRegexMatcher) -> SmartRegexMatcher {
+    let mut matcher = matcher.clone();
+
+    // Additional rules
+    for &(id, def) in &[(8, "42 | 42 8"), (11, "42 31 | 42 11 31")] {
+        let expr: RuleExpr = def.parse().expect("part 2 rules are valid");
+        matcher.add_rule(id, &expr);
+    }
+    matcher
+}
+
+/// Part 2: same as part 1, but with rules 8 and 11 replaced by loops.
+pub fn solve_part2(matcher: &SmartRegexMatcher, msgs: &[String]) -> usize {
+    count_matches(&part2_rules(matcher), msgs)
+}
+
+/*
+    Solution and tests
+*/
+
+/// Solution for day 19
+pub struct Day19;
+impl Solution for Day19 {
+    type Input = (SmartRegexMatcher, Vec<String>);
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_input(lines)
+    }
+    fn part1((matcher, msgs): &Self::Input) -> SolveResult<String> {
+        Ok(solve_part1(matcher, msgs).to_string())
+    }
+    fn part2((matcher, msgs): &Self::Input) -> SolveResult<String> {
+        Ok(solve_part2(matcher, msgs).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::once;
+
+    struct Example {
+        rules: &'static [&'static str],
+        msgs: &'static [&'static str],
+        expect: usize,
+    }
+    impl Example {
+        fn check(&self) {
+            let lines: Vec<_> = self
+                .rules
+                .iter()
+                .chain(once(&""))
+                .chain(self.msgs.iter())
+                .map(|s| s.to_string())
+                .collect();
+            let (mut matcher, msgs) = parse_input(&lines).unwrap();
+            let ans = matcher.count_regex0_matches(&msgs);
+            assert_eq!(ans, self.expect)
+        }
+    }
+
+    const EX1: Example = Example {
+        rules: &["0: 1 2", r#"1: "a""#, "2: 1 3 | 3 1", r#"3: "b""#],
+        msgs: &["aab", "aba", "bba", "bab", "bbb", "aab"],
+        expect: 3,
+    };
+    const EX2: Example = Example {
+        rules: &[
+            "0: 4 10",
+            "10: 1 5",
+            "1: 2 3 | 3 2",
+            "2: 4 4 | 5 5",
+            "3: 4 5 | 5 4",
+            r#"4: "a""#,
+            r#"5: "b""#,
+        ],
+        msgs: &["ababbb", "bababa", "abbbab", "aaabbb", "aaaabbb"],
+        expect: 2,
+    };
+    const EX3: Example = Example {
+        rules: &[
+            "42: 9 14 | 10 1",
+            "9: 14 27 | 1 26",
+            "10: 23 14 | 28 1",
+            r#"1: "a""#,
+            "11: 42 31",
+            "5: 1 14 | 15 1",
+            "19: 14 1 | 14 14",
+            "12: 24 14 | 19 1",
+            "16: 15 1 | 14 14",
+            "31: 14 17 | 1 13",
+            "6: 14 14 | 1 14",
+            "2: 1 24 | 14 4",
+            "0: 8 11",
+            "13: 14 3 | 1 12",
+            "15: 1 | 14",
+            "17: 14 2 | 1 7",
+            "23: 25 1 | 22 14",
+            "28: 16 1",
+            "4: 1 1",
+            "20: 14 14 | 1 15",
+            "3: 5 14 | 16 1",
+            "27: 1 6 | 14 18",
+            r#"14: "b""#,
+            "21: 14 1 | 1 14",
+            "25: 1 1 | 1 14",
+            "22: 14 14",
+            "8: 42",
+            "26: 14 22 | 1 20",
+            "18: 15 15",
+            "7: 14 5 | 1 21",
+            "24: 14 1",
+        ],
+        msgs: &[
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa",
+            "bbabbbbaabaabba",
+            "babbbbaabbbbbabbbbbbaabaaabaaa",
+            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa",
+            "bbbbbbbaaaabbbbaaabbabaaa",
+            "bbbababbbbaaaaaaaabbababaaababaabab",
+            "ababaaaaaabaaab",
+            "ababaaaaabbbaba",
+            "baabbaaaabbaaaababbaababb",
+            "abbbbabbbbaaaababbbbbbaaaababb",
+            "aaaaabbaabaaaaababaa",
+            "aaaabbaaaabbaaa",
+            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa",
+            "babaaabbbaaabaababbaabababaaab",
+            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba",
+        ],
+        expect: 3,
+    };
+
+    #[test]
+    fn test_part1() {
+        EX1.check();
+        EX2.check();
+        EX3.check();
+    }
+
+    const EX4: Example = Example {
+        rules: &[
+            "8: 42 | 108",
+            "108: 42 8",
+            "11: 42 31 | 42 111",
+            "111: 11 31",
+            "42: 9 14 | 10 1",
+            "9: 14 27 | 1 26",
+            "10: 23 14 | 28 1",
+            r#"1: "a""#,
+            "5: 1 14 | 15 1",
+            "19: 14 1 | 14 14",
+            "12: 24 14 | 19 1",
+            "16: 15 1 | 14 14",
+            "31: 14 17 | 1 13",
+            "6: 14 14 | 1 14",
+            "2: 1 24 | 14 4",
+            "0: 8 11",
+            "13: 14 3 | 1 12",
+            "15: 1 | 14",
+            "17: 14 2 | 1 7",
+            "23: 25 1 | 22 14",
+            "28: 16 1",
+            "4: 1 1",
+            "20: 14 14 | 1 15",
+            "3: 5 14 | 16 1",
+            "27: 1 6 | 14 18",
+            r#"14: "b""#,
+            "21: 14 1 | 1 14",
+            "25: 1 1 | 1 14",
+            "22: 14 14",
+            "26: 14 22 | 1 20",
+            "18: 15 15",
+            "7: 14 5 | 1 21",
+            "24: 14 1",
+        ],
+        msgs: &[
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa",
+            "bbabbbbaabaabba",
+            "babbbbaabbbbbabbbbbbaabaaabaaa",
+            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa",
+            "bbbbbbbaaaabbbbaaabbabaaa",
+            "bbbababbbbaaaaaaaabbababaaababaabab",
+            "ababaaaaaabaaab",
+            "ababaaaaabbbaba",
+            "baabbaaaabbaaaababbaababb",
+            "abbbbabbbbaaaababbbbbbaaaababb",
+            "aaaaabbaabaaaaababaa",
+            "aaaabbaaaabbaaa",
+            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa",
+            "babaaabbbaaabaababbaabababaaab",
+            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba",
+        ],
+        expect: 12,
+    };
+
+    #[test]
+    fn test_part2() {
+        EX4.check();
+    }
+
+    #[test]
+    fn test_recursive_rules() {
+        // Loops through rules that match the empty string
+        let (mut matcher, _) = parse("0: 0* | \"a\" 0\n\na").unwrap();
+        assert!(matcher.eval(0, "a"));
+        assert!(matcher.eval(0, "aaa"));
+        assert!(!matcher.eval(0, "b"));
+        let (mut matcher, _) = parse("0: 1 0 | \"b\"\n1: \"a\"?\n\nb").unwrap();
+        assert!(matcher.eval(0, "b"));
+        assert!(matcher.eval(0, "aab"));
+        assert!(!matcher.eval(0, "aa"));
+        // Recursion without repetition
+        let (mut matcher, msgs) = parse("0: \"a\" \"a\" | 0\n\naa\na").unwrap();
+        assert_eq!(matcher.count_regex0_matches(&msgs), 1);
+    }
+
+    fn parse(raw: &str) -> ParseResult<(SmartRegexMatcher, Vec<String>)> {
+        parse_input(&crate::util::str_to_vec(raw))
+    }
+
+    #[test]
+    fn test_rule_syntax() {
+        let raw = "0: 1+ (\"xy\" | 2 3 4)? [0-9]*\n1: \"a\" | \"b\" | \"c\"\n\
+                   2: 1 1\n3: 2\n4: \"--\"\n\n\
+                   abc\nxy\naxy42\nbaabcc--\nbaabcc-7\n";
+        let (matcher, msgs) = parse(raw).unwrap();
+        let engines = [Engine::Auto, Engine::Smart, Engine::Earley];
+        for &engine in &engines {
+            assert_eq!(count_matches_with(engine, &matcher, &msgs), 3);
+        }
+        let dfa = regular::Dfa::compile(&matcher, 0).unwrap();
+        let matches: Vec<&String> =
+            msgs.iter().filter(|msg| dfa.is_match(msg)).collect();
+        assert_eq!(matches, vec!["abc", "axy42", "baabcc--"]);
+        // IDs are no longer capped
+        let (matcher, _) =
+            parse("0: 1000 200\n1000: \"a\"\n200: \"b\"\n\nab").unwrap();
+        assert!(matcher.is_defined(1000));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |raw| match parse(raw) {
+            Ok(_) => panic!("parsed: {}", raw),
+            Err(err) => (err.line, err.col, err.cause),
+        };
+        let expected = |line, col: Option<usize>, cause: &str| {
+            (Some(line), col, cause.to_owned())
+        };
+        assert_eq!(
+            err("0: 1 (2\n1: \"a\"\n2: \"b\"\n\nab"),
+            expected(1, Some(8), "expected )")
+        );
+        assert_eq!(
+            err("0: 1\n1: 2 | \"a\"\n\nab"),
+            expected(2, None, "rule 2 is not defined")
+        );
+        assert_eq!(
+            err("0: 1\n1: 0\n0: \"a\"\n\na"),
+            expected(3, None, "rule 0 is defined twice")
+        );
+        let (_, _, cause) = err("4294967295: \"a\"\n0: 1 1 1\n1: \"a\"\n\na");
+        assert_eq!(cause, "rule ID too large: 4294967295");
+    }
+}
@@ -0,0 +1,509 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Earley parser for day 19 rule sets
+
+    SmartRegexMatcher handles part 2's recursive rules by cutting loops on
+    the call stack, which works for these rules but isn't a general method,
+    and only answers yes or no. This is a general context-free parser:
+    Earley's algorithm, which handles any grammar, including left recursion
+    (8: 8 42 | 42), right recursion (8: 42 | 42 8) and ambiguity, in O(n^3)
+    time in the worst case and O(n^2) for unambiguous grammars.
+
+    Each rule is a nonterminal, with one production per alternative:
+        Union(a, b)  =>  a | b
+        Concat(a, b) =>  a b
+        Noop(a)      =>  a
+        Char(c)      =>  "c" (a terminal)
+        Empty        =>  (nothing)
+
+    # Recognition
+    The chart has a set of items for each position j in the message: an
+    item (production, dot, origin) means that the first `dot` symbols of
+    the production match the message from origin to j. Items are predicted
+    (a rule is expected at j), scanned (the next character matches), and
+    completed (a rule matched from origin to j, so items waiting for it at
+    origin advance). Empty productions are handled by also advancing past
+    nullable rules when predicting them (Aycock and Horspool).
+
+    # Parse trees
+    Every completed item gives a span (rule, i, j) that the rule matches
+    and that can be part of a parse; from these, the parse trees are
+    rebuilt top-down by trying each way to split a span among the symbols
+    of each production. Counting them is the same recursion, memoized on
+    spans; a span which derives itself (through a cycle like 1: 2, 2: 1 |
+    "a") has infinitely many parse trees.
+*/
+
+use super::{RegexCases, RegexId, SmartRegexMatcher};
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Symbol on the right side of a production.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Symbol {
+    Rule(RegexId),
+    Char(char),
+}
+
+#[derive(Clone, Debug)]
+struct Production {
+    lhs: RegexId,
+    rhs: Vec<Symbol>,
+}
+
+/// Earley parser for a set of rules.
+#[derive(Clone, Debug)]
+pub struct EarleyParser {
+    productions: Vec<Production>,
+    by_lhs: HashMap<RegexId, Vec<usize>>,
+    nullable: HashSet<RegexId>,
+    helpers: HashSet<RegexId>,
+}
+impl EarleyParser {
+    pub fn new(matcher: &SmartRegexMatcher) -> Self {
+        let mut productions = Vec::new();
+        for (&lhs, &def) in &matcher.regex_defs {
+            let rhs_list = match def {
+                RegexCases::Union(id1, id2) => {
+                    vec![vec![Symbol::Rule(id1)], vec![Symbol::Rule(id2)]]
+                }
+                RegexCases::Concat(id1, id2) => {
+                    vec![vec![Symbol::Rule(id1), Symbol::Rule(id2)]]
+                }
+                RegexCases::Noop(id1) => vec![vec![Symbol::Rule(id1)]],
+                RegexCases::Char(ch) => vec![vec![Symbol::Char(ch)]],
+                RegexCases::Empty => vec![vec![]],
+            };
+            for rhs in rhs_list {
+                productions.push(Production { lhs, rhs });
+            }
+        }
+        let mut by_lhs: HashMap<RegexId, Vec<usize>> = HashMap::new();
+        for (i, prod) in productions.iter().enumerate() {
+            by_lhs.entry(prod.lhs).or_default().push(i);
+        }
+        let nullable = nullable_rules(&productions);
+        let helpers = matcher.helpers.keys().copied().collect();
+        Self { productions, by_lhs, nullable, helpers }
+    }
+
+    /// Parse a message as rule start.
+    pub fn parse(&self, start: RegexId, msg: &str) -> Parse<'_> {
+        let chars: Vec<char> = msg.chars().collect();
+        let n = chars.len();
+        // Items (production, dot, origin) at each position
+        let mut chart: Vec<Vec<(usize, usize, usize)>> = vec![vec![]; n + 1];
+        let mut seen: Vec<HashSet<(usize, usize, usize)>> =
+            vec![HashSet::new(); n + 1];
+        let mut completed = HashSet::new();
+        for &prod in self.by_lhs.get(&start).into_iter().flatten() {
+            if seen[0].insert((prod, 0, 0)) {
+                chart[0].push((prod, 0, 0));
+            }
+        }
+        for j in 0..=n {
+            // The chart at j grows as we go
+            let mut k = 0;
+            while k < chart[j].len() {
+                let (prod, dot, origin) = chart[j][k];
+                k += 1;
+                let rhs = &self.productions[prod].rhs;
+                let mut add = |j: usize, item, chart: &mut Vec<Vec<_>>| {
+                    if seen[j].insert(item) {
+                        chart[j].push(item);
+                    }
+                };
+                match rhs.get(dot) {
+                    // Predict
+                    Some(&Symbol::Rule(id)) => {
+                        for &next in self.by_lhs.get(&id).into_iter().flatten()
+                        {
+                            add(j, (next, 0, j), &mut chart);
+                        }
+                        if self.nullable.contains(&id) {
+                            add(j, (prod, dot + 1, origin), &mut chart);
+                        }
+                    }
+                    // Scan
+                    Some(&Symbol::Char(ch)) => {
+                        if j < n && chars[j] == ch {
+                            add(j + 1, (prod, dot + 1, origin), &mut chart);
+                        }
+                    }
+                    // Complete
+                    None => {
+                        let lhs = self.productions[prod].lhs;
+                        completed.insert((lhs, origin, j));
+                        let waiting: Vec<_> = chart[origin]
+                            .iter()
+                            .filter(|&&(p, d, _)| {
+                                self.productions[p].rhs.get(d)
+                                    == Some(&Symbol::Rule(lhs))
+                            })
+                            .map(|&(p, d, o)| (p, d + 1, o))
+                            .collect();
+                        for item in waiting {
+                            add(j, item, &mut chart);
+                        }
+                    }
+                }
+            }
+        }
+        Parse { parser: self, start, chars, completed }
+    }
+}
+
+// Rules which can match the empty string
+fn nullable_rules(productions: &[Production]) -> HashSet<RegexId> {
+    let mut nullable = HashSet::new();
+    loop {
+        let before = nullable.len();
+        for prod in productions {
+            let empty = prod.rhs.iter().all(|sym| match sym {
+                Symbol::Rule(id) => nullable.contains(id),
+                Symbol::Char(_) => false,
+            });
+            if empty {
+                nullable.insert(prod.lhs);
+            }
+        }
+        if nullable.len() == before {
+            return nullable;
+        }
+    }
+}
+
+/// Parse tree: a rule with the trees for each symbol it matched, or a
+/// character.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseTree {
+    Rule(RegexId, Vec<ParseTree>),
+    Char(char),
+}
+/// Prints as an s-expression, like (0 (1 a) (2 b)).
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTree::Char(ch) => write!(f, "{}", ch),
+            ParseTree::Rule(id, children) => {
+                write!(f, "({}", id)?;
+                for child in children {
+                    write!(f, " {}", child)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+// Replace the helper rules for parts of a rule by their children
+fn splice(tree: ParseTree, helpers: &HashSet<RegexId>) -> Vec<ParseTree> {
+    match tree {
+        ParseTree::Rule(id, children) => {
+            let children = children
+                .into_iter()
+                .flat_map(|child| splice(child, helpers))
+                .collect();
+            if helpers.contains(&id) {
+                children
+            } else {
+                vec![ParseTree::Rule(id, children)]
+            }
+        }
+        ParseTree::Char(ch) => vec![ParseTree::Char(ch)],
+    }
+}
+
+/// Result of parsing one message.
+pub struct Parse<'a> {
+    parser: &'a EarleyParser,
+    start: RegexId,
+    chars: Vec<char>,
+    // Spans (rule, i, j) where the rule matches and could be part of a parse
+    completed: HashSet<(RegexId, usize, usize)>,
+}
+impl Parse<'_> {
+    /// Whether the message matches the rule.
+    pub fn is_match(&self) -> bool {
+        self.completed.contains(&(self.start, 0, self.chars.len()))
+    }
+    fn symbol_matches(&self, sym: Symbol, i: usize, j: usize) -> bool {
+        match sym {
+            Symbol::Rule(id) => self.completed.contains(&(id, i, j)),
+            Symbol::Char(ch) => j == i + 1 && self.chars[i] == ch,
+        }
+    }
+    // Ways to split i..j among the symbols of rhs, as the end of each
+    // symbol (only the first symbol's end, then recursively)
+    fn splits(&self, rhs: &[Symbol], i: usize, j: usize) -> Vec<usize> {
+        match rhs.split_first() {
+            None => vec![],
+            Some((&sym, rest)) => (i..=j)
+                .filter(|&k| {
+                    self.symbol_matches(sym, i, k)
+                        && self.seq_matches(rest, k, j)
+                })
+                .collect(),
+        }
+    }
+    fn seq_matches(&self, rhs: &[Symbol], i: usize, j: usize) -> bool {
+        match rhs.split_first() {
+            None => i == j,
+            Some(_) => !self.splits(rhs, i, j).is_empty(),
+        }
+    }
+
+    /// Number of parse trees, or None if there are infinitely many.
+    /// Zero if the message doesn't match; more than one if it is ambiguous.
+    pub fn count(&self) -> Option<BigUint> {
+        if !self.is_match() {
+            return Some(BigUint::zero());
+        }
+        let mut counter = Counter { parse: self, memo: HashMap::new() };
+        counter.rule(self.start, 0, self.chars.len())
+    }
+
+    /// Up to limit parse trees (leaving out any that go around a cycle).
+    pub fn trees(&self, limit: usize) -> Vec<ParseTree> {
+        if !self.is_match() {
+            return vec![];
+        }
+        let mut in_progress = HashSet::new();
+        self.rule_trees(
+            self.start,
+            0,
+            self.chars.len(),
+            limit,
+            &mut in_progress,
+        )
+        .into_iter()
+        .flat_map(|tree| splice(tree, &self.parser.helpers))
+        .collect()
+    }
+    fn rule_trees(
+        &self,
+        id: RegexId,
+        i: usize,
+        j: usize,
+        limit: usize,
+        in_progress: &mut HashSet<(RegexId, usize, usize)>,
+    ) -> Vec<ParseTree> {
+        if !in_progress.insert((id, i, j)) {
+            return vec![];
+        }
+        let mut trees = Vec::new();
+        for &prod in &self.parser.by_lhs[&id] {
+            let rhs = &self.parser.productions[prod].rhs;
+            let left = limit - trees.len();
+            for children in self.seq_trees(rhs, i, j, left, in_progress) {
+                trees.push(ParseTree::Rule(id, children));
+            }
+            if trees.len() >= limit {
+                break;
+            }
+        }
+        in_progress.remove(&(id, i, j));
+        trees
+    }
+    fn seq_trees(
+        &self,
+        rhs: &[Symbol],
+        i: usize,
+        j: usize,
+        limit: usize,
+        in_progress: &mut HashSet<(RegexId, usize, usize)>,
+    ) -> Vec<Vec<ParseTree>> {
+        let (&sym, rest) = match rhs.split_first() {
+            _ if limit == 0 => return vec![],
+            None if i == j => return vec![vec![]],
+            None => return vec![],
+            Some(split) => split,
+        };
+        let mut seqs = Vec::new();
+        for k in self.splits(rhs, i, j) {
+            let firsts = match sym {
+                Symbol::Rule(id) => {
+                    self.rule_trees(id, i, k, limit, in_progress)
+                }
+                Symbol::Char(ch) => vec![ParseTree::Char(ch)],
+            };
+            if firsts.is_empty() {
+                continue;
+            }
+            for tail in self.seq_trees(rest, k, j, limit, in_progress) {
+                for first in &firsts {
+                    let mut seq = vec![first.clone()];
+                    seq.extend(tail.iter().cloned());
+                    seqs.push(seq);
+                    if seqs.len() >= limit {
+                        return seqs;
+                    }
+                }
+            }
+        }
+        seqs
+    }
+}
+
+// Memoized count of parse trees for each span; None means infinite.
+// Only spans that can be part of a parse are visited (see Parse::splits),
+// so reaching a span again while counting it is a real cycle.
+struct Counter<'a, 'b> {
+    parse: &'a Parse<'b>,
+    memo: HashMap<(RegexId, usize, usize), Option<Option<BigUint>>>,
+}
+impl Counter<'_, '_> {
+    fn rule(&mut self, id: RegexId, i: usize, j: usize) -> Option<BigUint> {
+        match self.memo.get(&(id, i, j)) {
+            // In progress: a cycle
+            Some(None) => return None,
+            Some(Some(count)) => return count.clone(),
+            None => (),
+        }
+        self.memo.insert((id, i, j), None);
+        let parser = self.parse.parser;
+        let mut total = Some(BigUint::zero());
+        for &prod in &parser.by_lhs[&id] {
+            let count = self.seq(&parser.productions[prod].rhs, i, j);
+            total = total.and_then(|total| count.map(|count| total + count));
+        }
+        self.memo.insert((id, i, j), Some(total.clone()));
+        total
+    }
+    fn seq(&mut self, rhs: &[Symbol], i: usize, j: usize) -> Option<BigUint> {
+        let (&sym, rest) = match rhs.split_first() {
+            None if i == j => return Some(BigUint::one()),
+            None => return Some(BigUint::zero()),
+            Some(split) => split,
+        };
+        let mut total = Some(BigUint::zero());
+        for k in self.parse.splits(rhs, i, j) {
+            let first = match sym {
+                Symbol::Rule(id) => self.rule(id, i, k),
+                Symbol::Char(_) => Some(BigUint::one()),
+            };
+            let rest = self.seq(rest, k, j);
+            total = match (total, first, rest) {
+                (Some(total), Some(first), Some(rest)) => {
+                    Some(total + first * rest)
+                }
+                _ => None,
+            };
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day19::parse_input;
+    use crate::util::str_to_vec;
+
+    fn earley(rules: &str) -> EarleyParser {
+        let lines = str_to_vec(&format!("{}\n\nab", rules));
+        let (matcher, _) = parse_input(&lines).unwrap();
+        EarleyParser::new(&matcher)
+    }
+
+    #[test]
+    fn test_match() {
+        let parser = earley("0: 1 2\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
+        let matches: Vec<&str> = ["aab", "aba", "bba", "bab", "bbb"]
+            .iter()
+            .copied()
+            .filter(|msg| parser.parse(0, msg).is_match())
+            .collect();
+        assert_eq!(matches, vec!["aab", "aba"]);
+        let parse = parser.parse(0, "aba");
+        assert_eq!(parse.count(), Some(BigUint::one()));
+        let trees: Vec<String> =
+            parse.trees(10).iter().map(|t| t.to_string()).collect();
+        assert_eq!(trees, vec!["(0 (1 a) (2 (3 b) (1 a)))"]);
+    }
+
+    #[test]
+    fn test_helpers_spliced() {
+        // Helper rules for the parts of a rule don't appear in the tree
+        let parser = earley("0: \"ab\" 1+ [xy]?\n1: \"c\" | \"d\"");
+        let parse = parser.parse(0, "abcdx");
+        let trees: Vec<String> =
+            parse.trees(10).iter().map(|t| t.to_string()).collect();
+        assert_eq!(trees, vec!["(0 a b (1 c) (1 d) x)"]);
+        // An optional empty string: infinitely ambiguous under *
+        let parser = earley("0: 1*\n1: \"a\"?");
+        assert!(parser.parse(0, "aa").is_match());
+        assert_eq!(parser.parse(0, "aa").count(), None);
+    }
+
+    #[test]
+    fn test_recursion() {
+        // Left recursion, right recursion, and a^k b^k
+        let left = earley("0: 1 | 2\n2: 0 1\n1: \"a\"");
+        assert!(left.parse(0, "aaaa").is_match());
+        assert!(!left.parse(0, "").is_match());
+        let right = earley("0: 1 | 2\n2: 1 0\n1: \"a\"");
+        assert!(right.parse(0, "aaaa").is_match());
+        let balanced =
+            earley("0: 5 | 3\n5: 1 2\n3: 1 4\n4: 0 2\n1: \"a\"\n2: \"b\"");
+        assert!(balanced.parse(0, "aaabbb").is_match());
+        assert!(!balanced.parse(0, "aaabb").is_match());
+        assert_eq!(balanced.parse(0, "aabb").count(), Some(BigUint::one()));
+    }
+
+    #[test]
+    fn test_empty() {
+        // The empty message has a tree only if rule 0 is nullable
+        let parser = earley("0: 1 2\n1: \"a\"?\n2: \"b\"");
+        assert_eq!(parser.parse(0, "").count(), Some(BigUint::zero()));
+        assert!(parser.parse(0, "").trees(10).is_empty());
+        let parser = earley("0: 1 2\n1: \"a\"?\n2: \"b\"?");
+        assert_eq!(parser.parse(0, "").count(), Some(BigUint::one()));
+        let trees: Vec<String> = parser
+            .parse(0, "")
+            .trees(10)
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(trees, vec!["(0 (1) (2))"]);
+        // No trees at all with a limit of 0
+        assert!(parser.parse(0, "").trees(0).is_empty());
+        assert!(parser.parse(0, "ab").trees(0).is_empty());
+    }
+
+    #[test]
+    fn test_ambiguity() {
+        // 0 is a+ in any grouping: Catalan-many trees
+        let parser = earley("0: 1 | 2\n2: 0 0\n1: \"a\"");
+        let counts: Vec<BigUint> = (1..=5)
+            .map(|n| parser.parse(0, &"a".repeat(n)).count().unwrap())
+            .collect();
+        assert_eq!(
+            counts,
+            vec![1u32, 1, 2, 5, 14]
+                .into_iter()
+                .map(BigUint::from)
+                .collect::<Vec<_>>()
+        );
+        let parse = parser.parse(0, "aaa");
+        let trees: Vec<String> =
+            parse.trees(10).iter().map(|t| t.to_string()).collect();
+        assert_eq!(
+            trees,
+            vec![
+                "(0 (2 (0 (1 a)) (0 (2 (0 (1 a)) (0 (1 a))))))",
+                "(0 (2 (0 (2 (0 (1 a)) (0 (1 a)))) (0 (1 a))))"
+            ]
+        );
+        assert_eq!(parse.trees(1).len(), 1);
+        // A cycle: infinitely many trees
+        let cyclic = earley("0: 1 | 2\n2: 0\n1: \"a\"");
+        assert!(cyclic.parse(0, "a").is_match());
+        assert_eq!(cyclic.parse(0, "a").count(), None);
+        assert_eq!(cyclic.parse(0, "b").count(), Some(BigUint::zero()));
+    }
+}
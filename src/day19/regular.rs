@@ -0,0 +1,330 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Compiling regular rule sets to automata
+
+    SmartRegexMatcher and the Earley parser work for any rules, but take up
+    to cubic time per message. Most rule sets -- including all of part 1 --
+    describe regular languages, and can be compiled once to a DFA which then
+    matches each message in O(n) time.
+
+    # When the rules are regular
+    Rules that don't refer to themselves (even indirectly) are regular, and
+    so are rules that only recurse in tail position: the last part of a
+    concatenation, like part 2's 8: 42 | 42 8. A rule recursing anywhere
+    else, like part 2's 11: 42 31 | 42 11 31, may not be regular (11 matches
+    42^k 31^k), so compilation fails and we fall back to the Earley parser.
+    Precisely: within each strongly connected component of the rule graph,
+    the first half of a Concat may not be in the same component.
+
+    # Construction
+    First an NFA with epsilon moves, built in continuation-passing style:
+    compiling rule r with continuation k gives a state that matches r and
+    then continues at k, so Concat(a, b) is a followed by (b followed by k).
+    Memoizing on (r, k) shares states between uses of the same rule, and
+    ties the knot for tail recursion, where the continuation stays the same.
+    Then the DFA is the usual subset construction over the NFA states.
+
+    Both steps can blow up exponentially in the worst case, so each is
+    capped at MAX_STATES states.
+*/
+
+use super::{RegexCases, RegexId, SmartRegexMatcher};
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Limit on the number of NFA or DFA states.
+pub const MAX_STATES: usize = 100_000;
+
+/// Why a rule set couldn't be compiled to a DFA.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CompileError {
+    /// The rule refers to itself other than in tail position.
+    NonTailRecursion(RegexId),
+    /// The automaton would have more than MAX_STATES states.
+    TooManyStates,
+}
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompileError::NonTailRecursion(id) => {
+                write!(f, "rule {} is recursive, not in tail position", id)
+            }
+            CompileError::TooManyStates => {
+                write!(f, "more than {} states", MAX_STATES)
+            }
+        }
+    }
+}
+
+/*
+    Regularity check
+*/
+
+fn refs(def: RegexCases) -> Vec<RegexId> {
+    match def {
+        RegexCases::Union(id1, id2) | RegexCases::Concat(id1, id2) => {
+            vec![id1, id2]
+        }
+        RegexCases::Noop(id1) => vec![id1],
+        RegexCases::Char(_) | RegexCases::Empty => vec![],
+    }
+}
+
+// Rules reachable from each rule reachable from start (including itself
+// only if it is recursive)
+fn reachability(
+    defs: &HashMap<RegexId, RegexCases>,
+    start: RegexId,
+) -> HashMap<RegexId, HashSet<RegexId>> {
+    let mut reach: HashMap<RegexId, HashSet<RegexId>> = HashMap::new();
+    let mut todo = vec![start];
+    while let Some(id) = todo.pop() {
+        if reach.contains_key(&id) {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        let mut stack = refs(defs[&id]);
+        while let Some(next) = stack.pop() {
+            if seen.insert(next) {
+                stack.extend(refs(defs[&next]));
+            }
+        }
+        todo.extend(seen.iter().copied());
+        reach.insert(id, seen);
+    }
+    reach
+}
+
+fn check_regular(
+    matcher: &SmartRegexMatcher,
+    start: RegexId,
+) -> Result<(), CompileError> {
+    let defs = &matcher.regex_defs;
+    let reach = reachability(defs, start);
+    // Sorted, for a deterministic error
+    let ids: BTreeSet<RegexId> = reach.keys().copied().collect();
+    for id in ids {
+        if let RegexCases::Concat(head, _) = defs[&id] {
+            if reach[&head].contains(&id) {
+                let rule = matcher.owner(id);
+                return Err(CompileError::NonTailRecursion(rule));
+            }
+        }
+    }
+    Ok(())
+}
+
+/*
+    NFA
+*/
+
+#[derive(Clone, Debug)]
+enum NfaState {
+    Split(Vec<usize>),
+    Char(char, usize),
+    Accept,
+}
+
+struct NfaBuilder<'a> {
+    defs: &'a HashMap<RegexId, RegexCases>,
+    states: Vec<NfaState>,
+    memo: HashMap<(RegexId, usize), usize>,
+}
+impl NfaBuilder<'_> {
+    // State matching rule id and then continuing at state k
+    fn compile(
+        &mut self,
+        id: RegexId,
+        k: usize,
+    ) -> Result<usize, CompileError> {
+        if let Some(&state) = self.memo.get(&(id, k)) {
+            return Ok(state);
+        }
+        if self.states.len() >= MAX_STATES {
+            return Err(CompileError::TooManyStates);
+        }
+        let state = self.states.len();
+        self.states.push(NfaState::Split(vec![]));
+        self.memo.insert((id, k), state);
+        self.states[state] = match self.defs[&id] {
+            RegexCases::Char(ch) => NfaState::Char(ch, k),
+            RegexCases::Union(id1, id2) => {
+                let state1 = self.compile(id1, k)?;
+                let state2 = self.compile(id2, k)?;
+                NfaState::Split(vec![state1, state2])
+            }
+            RegexCases::Concat(id1, id2) => {
+                let rest = self.compile(id2, k)?;
+                NfaState::Split(vec![self.compile(id1, rest)?])
+            }
+            RegexCases::Noop(id1) => {
+                NfaState::Split(vec![self.compile(id1, k)?])
+            }
+            RegexCases::Empty => NfaState::Split(vec![k]),
+        };
+        Ok(state)
+    }
+    // States reachable by epsilon moves (only Char and Accept states)
+    fn closure<I: IntoIterator<Item = usize>>(&self, states: I) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<usize> = states.into_iter().collect();
+        let mut closure = BTreeSet::new();
+        while let Some(state) = stack.pop() {
+            if !seen.insert(state) {
+                continue;
+            }
+            match &self.states[state] {
+                NfaState::Split(next) => stack.extend(next.iter().copied()),
+                _ => {
+                    closure.insert(state);
+                }
+            }
+        }
+        closure.into_iter().collect()
+    }
+}
+
+/*
+    DFA
+*/
+
+/// Deterministic automaton matching a rule, for linear-time matching.
+#[derive(Clone, Debug)]
+pub struct Dfa {
+    // State 0 is the start; a missing transition rejects
+    transitions: Vec<HashMap<char, usize>>,
+    accepting: Vec<bool>,
+}
+impl Dfa {
+    /// Compile rule id, if the rules it uses are regular (see above).
+    pub fn compile(
+        matcher: &SmartRegexMatcher,
+        id: RegexId,
+    ) -> Result<Self, CompileError> {
+        let defs = &matcher.regex_defs;
+        check_regular(matcher, id)?;
+        let mut nfa = NfaBuilder {
+            defs,
+            states: vec![NfaState::Accept],
+            memo: HashMap::new(),
+        };
+        let start = nfa.compile(id, 0)?;
+
+        // Subset construction
+        let mut dfa = Self { transitions: vec![], accepting: vec![] };
+        let start = nfa.closure(Some(start));
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::new();
+        ids.insert(start.clone(), 0);
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(subset) = queue.pop_front() {
+            let mut moves: HashMap<char, Vec<usize>> = HashMap::new();
+            let mut accepting = false;
+            for &state in &subset {
+                match nfa.states[state] {
+                    NfaState::Char(ch, next) => {
+                        moves.entry(ch).or_default().push(next)
+                    }
+                    NfaState::Accept => accepting = true,
+                    NfaState::Split(_) => unreachable!(),
+                }
+            }
+            let mut transitions = HashMap::new();
+            for (ch, next) in moves {
+                let next = nfa.closure(next);
+                let num_ids = ids.len();
+                let next_id = *ids.entry(next.clone()).or_insert_with(|| {
+                    queue.push_back(next);
+                    num_ids
+                });
+                transitions.insert(ch, next_id);
+            }
+            if ids.len() > MAX_STATES {
+                return Err(CompileError::TooManyStates);
+            }
+            dfa.transitions.push(transitions);
+            dfa.accepting.push(accepting);
+        }
+        Ok(dfa)
+    }
+    pub fn num_states(&self) -> usize {
+        self.transitions.len()
+    }
+    /// Whether the rule matches the whole string s.
+    pub fn is_match(&self, s: &str) -> bool {
+        let mut state = 0;
+        for ch in s.chars() {
+            match self.transitions[state].get(&ch) {
+                Some(&next) => state = next,
+                None => return false,
+            }
+        }
+        self.accepting[state]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day19::parse_input;
+    use crate::util::str_to_vec;
+
+    fn dfa(rules: &str) -> Result<Dfa, CompileError> {
+        let lines = str_to_vec(&format!("{}\n\nab", rules));
+        let (matcher, _) = parse_input(&lines).unwrap();
+        Dfa::compile(&matcher, 0)
+    }
+
+    #[test]
+    fn test_regular() {
+        let dfa = dfa("0: 4 6\n6: 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n\
+                       3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"")
+        .unwrap();
+        let matches: Vec<&str> = ["ababbb", "bababa", "abbbab", "aaabbb"]
+            .iter()
+            .copied()
+            .filter(|msg| dfa.is_match(msg))
+            .collect();
+        assert_eq!(matches, vec!["ababbb", "abbbab"]);
+        assert!(!dfa.is_match(""));
+        assert!(!dfa.is_match("ababbbb"));
+    }
+
+    #[test]
+    fn test_tail_recursion() {
+        // a+ b
+        let dfa = dfa("0: 1 2\n1: 3 | 4\n4: 3 1\n2: \"b\"\n3: \"a\"").unwrap();
+        assert!(dfa.is_match("ab"));
+        assert!(dfa.is_match("aaaaaaab"));
+        assert!(!dfa.is_match("b"));
+        assert!(!dfa.is_match("aaba"));
+        assert!(dfa.num_states() <= 3);
+    }
+
+    #[test]
+    fn test_repetition() {
+        let err = dfa("0: 1 2 | (0 \"a\")+\n1: \"a\"\n2: \"b\"").unwrap_err();
+        assert_eq!(err, CompileError::NonTailRecursion(0));
+        let dfa = dfa("0: (1 | \"ab\")+ [0-9]? 1*\n1: \"c\"").unwrap();
+        let matches: Vec<&str> = ["c", "abcab7", "ab", "7", "abc77", "ccc"]
+            .iter()
+            .copied()
+            .filter(|msg| dfa.is_match(msg))
+            .collect();
+        assert_eq!(matches, vec!["c", "abcab7", "ab", "ccc"]);
+    }
+
+    #[test]
+    fn test_not_regular() {
+        // a^k b^k, and the left-recursive a+
+        let err = dfa("0: 1 2 | 1 3\n3: 0 2\n1: \"a\"\n2: \"b\"").unwrap_err();
+        assert_eq!(err, CompileError::NonTailRecursion(3));
+        let err = dfa("0: 1 | 2\n2: 0 1\n1: \"a\"").unwrap_err();
+        assert_eq!(err, CompileError::NonTailRecursion(2));
+        assert_eq!(
+            err.to_string(),
+            "rule 2 is recursive, not in tail position"
+        );
+    }
+}
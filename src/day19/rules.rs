@@ -0,0 +1,475 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Grammar for day 19 rule files
+
+    The puzzle input only uses a few shapes of rule (one or two rules in
+    sequence, a union of those, or a letter in quotes), but rule files can
+    use a fuller syntax: the right side of a rule is a regular expression
+    over other rules,
+        alt  := seq ('|' seq)*
+        seq  := item item*
+        item := atom ('?' | '*' | '+')*
+        atom := id | "literal" | [class] | '(' alt ')'
+    with spaces between items optional, except between two IDs. A literal
+    matches its characters in order ("" matches the empty string), and a
+    class matches any one of its characters, which can include ranges like
+    [a-z] (a '-' at the start or end of the class is itself). Literals and
+    classes can't contain '"' or ']'.
+
+    # Lowering
+    The matchers work with RegexCases, which are binary: so each rule is
+    lowered into a RegexCases for the rule itself, plus helper rules for
+    any parts that aren't just a reference to another rule. For example
+        0: 1 2 3 | "ab"
+    becomes 0: h1 | h2, h1: 1 h3, h3: 2 3, h2: h4 h5, h4: "a", h5: "b".
+    Repetition becomes (tail) recursion: x* is h: h' | "", h': x h. Helper
+    rules are numbered down from RegexId::MAX so that they don't clash with
+    the rules in the file, and each one remembers the rule it is part of,
+    for error messages and parse trees.
+*/
+
+use super::{RegexCases, RegexId, SmartRegexMatcher};
+use crate::util::{ParseError, ParseResult};
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Right side of a rule.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RuleExpr {
+    Ref(RegexId),
+    Literal(String),
+    /// Sorted, without duplicates
+    Class(Vec<char>),
+    Seq(Vec<RuleExpr>),
+    Alt(Vec<RuleExpr>),
+    Optional(Box<RuleExpr>),
+    Star(Box<RuleExpr>),
+    Plus(Box<RuleExpr>),
+}
+impl RuleExpr {
+    /// The rules this refers to.
+    pub fn refs(&self) -> BTreeSet<RegexId> {
+        let mut refs = BTreeSet::new();
+        self.add_refs(&mut refs);
+        refs
+    }
+    fn add_refs(&self, refs: &mut BTreeSet<RegexId>) {
+        match self {
+            RuleExpr::Ref(id) => {
+                refs.insert(*id);
+            }
+            RuleExpr::Literal(_) | RuleExpr::Class(_) => (),
+            RuleExpr::Seq(exprs) | RuleExpr::Alt(exprs) => {
+                for expr in exprs {
+                    expr.add_refs(refs);
+                }
+            }
+            RuleExpr::Optional(expr)
+            | RuleExpr::Star(expr)
+            | RuleExpr::Plus(expr) => expr.add_refs(refs),
+        }
+    }
+}
+
+/*
+    Parsing
+*/
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+impl Parser {
+    fn error<D: fmt::Display>(&self, msg: D) -> ParseError {
+        ParseError::new(msg).at_col(self.pos + 1)
+    }
+    // Next character other than a space
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos) == Some(&' ') {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+    // Characters up to (and past) the closing delimiter
+    fn until(&mut self, close: char, what: &str) -> ParseResult<Vec<char>> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|&ch| ch != close) {
+            self.pos += 1;
+        }
+        if self.pos == self.chars.len() {
+            self.pos = start - 1;
+            return Err(self.error(format!("unterminated {}", what)));
+        }
+        self.pos += 1;
+        Ok(self.chars[start..self.pos - 1].to_vec())
+    }
+    fn alt(&mut self) -> ParseResult<RuleExpr> {
+        let mut alts = vec![self.seq()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alts.push(self.seq()?);
+        }
+        Ok(if alts.len() == 1 {
+            alts.pop().unwrap()
+        } else {
+            RuleExpr::Alt(alts)
+        })
+    }
+    fn seq(&mut self) -> ParseResult<RuleExpr> {
+        let mut items = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            items.push(self.item()?);
+        }
+        match items.len() {
+            0 => Err(self.error("expected a rule")),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(RuleExpr::Seq(items)),
+        }
+    }
+    fn item(&mut self) -> ParseResult<RuleExpr> {
+        let mut item = self.atom()?;
+        loop {
+            item = match self.peek() {
+                Some('?') => RuleExpr::Optional(Box::new(item)),
+                Some('*') => RuleExpr::Star(Box::new(item)),
+                Some('+') => RuleExpr::Plus(Box::new(item)),
+                _ => return Ok(item),
+            };
+            self.pos += 1;
+        }
+    }
+    fn atom(&mut self) -> ParseResult<RuleExpr> {
+        let start = self.pos;
+        let ch = self.chars[start];
+        self.pos += 1;
+        match ch {
+            _ if ch.is_ascii_digit() => {
+                while self.chars.get(self.pos).is_some_and(char::is_ascii_digit)
+                {
+                    self.pos += 1;
+                }
+                let raw: String = self.chars[start..self.pos].iter().collect();
+                raw.parse().map(RuleExpr::Ref).map_err(|_| {
+                    ParseError::new(format!("rule ID out of range: {}", raw))
+                        .at_col(start + 1)
+                })
+            }
+            '"' => {
+                let chars = self.until('"', "literal")?;
+                Ok(RuleExpr::Literal(chars.into_iter().collect()))
+            }
+            '[' => {
+                let chars = self.until(']', "character class")?;
+                class(&chars).map_err(|err| err.at_col(start + 1))
+            }
+            '(' => {
+                let inner = self.alt()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("expected )"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            _ => {
+                self.pos = start;
+                Err(self.error(format!("unexpected {}", ch)))
+            }
+        }
+    }
+}
+
+// The characters in a class, expanding ranges
+fn class(chars: &[char]) -> ParseResult<RuleExpr> {
+    let mut members = BTreeSet::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' {
+            let (lo, hi) = (chars[i], chars[i + 2]);
+            if lo > hi {
+                let msg =
+                    format!("empty range in character class: {}-{}", lo, hi);
+                return Err(ParseError::new(msg));
+            }
+            members.extend(lo..=hi);
+            i += 3;
+        } else {
+            members.insert(chars[i]);
+            i += 1;
+        }
+    }
+    if members.is_empty() {
+        return Err(ParseError::new("empty character class"));
+    }
+    Ok(RuleExpr::Class(members.into_iter().collect()))
+}
+
+impl FromStr for RuleExpr {
+    type Err = ParseError;
+    fn from_str(s: &str) -> ParseResult<Self> {
+        let mut parser = Parser { chars: s.chars().collect(), pos: 0 };
+        let expr = parser.alt()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(ch) => Err(parser.error(format!("unexpected {}", ch))),
+        }
+    }
+}
+
+/// Prints in the syntax above, with parentheses only where needed.
+impl fmt::Display for RuleExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleExpr::Ref(id) => write!(f, "{}", id),
+            RuleExpr::Literal(s) => write!(f, "\"{}\"", s),
+            RuleExpr::Class(chars) => {
+                // A '-' goes first, so it isn't read as a range
+                let dash = if chars.contains(&'-') { "-" } else { "" };
+                let rest: String =
+                    chars.iter().filter(|&&ch| ch != '-').collect();
+                write!(f, "[{}{}]", dash, rest)
+            }
+            RuleExpr::Seq(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| match item {
+                        RuleExpr::Alt(_) => format!("({})", item),
+                        _ => item.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", items.join(" "))
+            }
+            RuleExpr::Alt(alts) => {
+                let alts: Vec<String> =
+                    alts.iter().map(|alt| alt.to_string()).collect();
+                write!(f, "{}", alts.join(" | "))
+            }
+            RuleExpr::Optional(inner)
+            | RuleExpr::Star(inner)
+            | RuleExpr::Plus(inner) => {
+                match **inner {
+                    RuleExpr::Seq(_) | RuleExpr::Alt(_) => {
+                        write!(f, "({})", inner)?
+                    }
+                    _ => write!(f, "{}", inner)?,
+                }
+                match self {
+                    RuleExpr::Optional(_) => write!(f, "?"),
+                    RuleExpr::Star(_) => write!(f, "*"),
+                    _ => write!(f, "+"),
+                }
+            }
+        }
+    }
+}
+
+/*
+    Lowering to RegexCases
+*/
+
+// Single-character literals
+fn singles<I: Iterator<Item = char>>(chars: I) -> Vec<RuleExpr> {
+    chars.map(|ch| RuleExpr::Literal(ch.to_string())).collect()
+}
+
+impl SmartRegexMatcher {
+    /// Define (or redefine) a rule, adding helper rules for its parts.
+    pub fn add_rule(&mut self, id: RegexId, expr: &RuleExpr) {
+        self.define(id, id, expr);
+    }
+    /// Whether id is a helper rule added by add_rule.
+    pub fn is_helper(&self, id: RegexId) -> bool {
+        self.helpers.contains_key(&id)
+    }
+    /// The rule that id is part of: itself, unless it is a helper rule.
+    pub fn owner(&self, id: RegexId) -> RegexId {
+        self.helpers.get(&id).copied().unwrap_or(id)
+    }
+    fn new_helper(&mut self, owner: RegexId) -> RegexId {
+        let id = RegexId::MAX - self.helpers.len() as RegexId;
+        self.helpers.insert(id, owner);
+        id
+    }
+    // A rule matching expr, as part of rule owner
+    fn operand(&mut self, owner: RegexId, expr: &RuleExpr) -> RegexId {
+        match expr {
+            RuleExpr::Ref(id) => *id,
+            _ => {
+                let id = self.new_helper(owner);
+                self.define(id, owner, expr);
+                id
+            }
+        }
+    }
+    // Define id as one of exprs, or all of them in sequence
+    fn define_list(
+        &mut self,
+        id: RegexId,
+        owner: RegexId,
+        exprs: &[RuleExpr],
+        join: fn(RegexId, RegexId) -> RegexCases,
+    ) {
+        match exprs {
+            [] => self.add_regex(id, RegexCases::Empty),
+            [expr] => self.define(id, owner, expr),
+            [first, rest @ ..] => {
+                let first = self.operand(owner, first);
+                let rest = match rest {
+                    [last] => self.operand(owner, last),
+                    _ => {
+                        let rest_id = self.new_helper(owner);
+                        self.define_list(rest_id, owner, rest, join);
+                        rest_id
+                    }
+                };
+                self.add_regex(id, join(first, rest));
+            }
+        }
+    }
+    fn define(&mut self, id: RegexId, owner: RegexId, expr: &RuleExpr) {
+        match expr {
+            RuleExpr::Ref(id1) => self.add_regex(id, RegexCases::Noop(*id1)),
+            RuleExpr::Literal(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => {
+                        self.add_regex(id, RegexCases::Char(ch))
+                    }
+                    _ => {
+                        let parts = singles(s.chars());
+                        self.define_list(id, owner, &parts, RegexCases::Concat)
+                    }
+                }
+            }
+            RuleExpr::Class(members) => {
+                let alts = singles(members.iter().copied());
+                self.define_list(id, owner, &alts, RegexCases::Union)
+            }
+            RuleExpr::Seq(items) => {
+                self.define_list(id, owner, items, RegexCases::Concat)
+            }
+            RuleExpr::Alt(alts) => {
+                self.define_list(id, owner, alts, RegexCases::Union)
+            }
+            RuleExpr::Optional(inner) => {
+                let inner = self.operand(owner, inner);
+                let empty = self.new_helper(owner);
+                self.add_regex(empty, RegexCases::Empty);
+                self.add_regex(id, RegexCases::Union(inner, empty));
+            }
+            RuleExpr::Star(inner) | RuleExpr::Plus(inner) => {
+                // Plus: x | x id; star: x id | ""
+                let inner = self.operand(owner, inner);
+                let more = self.new_helper(owner);
+                self.add_regex(more, RegexCases::Concat(inner, id));
+                let other = match expr {
+                    RuleExpr::Plus(_) => inner,
+                    _ => {
+                        let empty = self.new_helper(owner);
+                        self.add_regex(empty, RegexCases::Empty);
+                        empty
+                    }
+                };
+                self.add_regex(id, RegexCases::Union(more, other));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day19::earley::EarleyParser;
+    use crate::day19::regular::Dfa;
+
+    fn parse(raw: &str) -> RuleExpr {
+        RuleExpr::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let lit = |s: &str| RuleExpr::Literal(s.to_owned());
+        assert_eq!(parse("12"), RuleExpr::Ref(12));
+        assert_eq!(
+            parse("1 2 3 | \"ab\""),
+            RuleExpr::Alt(vec![
+                RuleExpr::Seq(vec![
+                    RuleExpr::Ref(1),
+                    RuleExpr::Ref(2),
+                    RuleExpr::Ref(3)
+                ]),
+                lit("ab"),
+            ])
+        );
+        assert_eq!(
+            parse("[c-ea-] (4 5)?+"),
+            RuleExpr::Seq(vec![
+                RuleExpr::Class(vec!['-', 'a', 'c', 'd', 'e']),
+                RuleExpr::Plus(Box::new(RuleExpr::Optional(Box::new(
+                    RuleExpr::Seq(vec![RuleExpr::Ref(4), RuleExpr::Ref(5)])
+                )))),
+            ])
+        );
+        assert_eq!(parse("\"\"*"), RuleExpr::Star(Box::new(lit(""))));
+        assert_eq!(
+            parse("100000 | 2").refs(),
+            vec![2, 100000].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for raw in &[
+            "1 2 3 | \"ab\"",
+            "[-ac] (4 5 | 6)?+ 7*",
+            "(1 | 2) 3",
+            "((1 | 2)* 3)+",
+        ] {
+            assert_eq!(parse(raw).to_string(), *raw);
+        }
+        assert_eq!(parse("1\"a\" ( (2) )").to_string(), "1 \"a\" 2");
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |raw: &str| RuleExpr::from_str(raw).unwrap_err();
+        assert_eq!(err("").cause, "expected a rule");
+        assert_eq!(err("1 | | 2").col, Some(5));
+        assert_eq!(err("1 \"ab").cause, "unterminated literal");
+        assert_eq!(err("1 \"ab").col, Some(3));
+        assert_eq!(err("[]").cause, "empty character class");
+        assert_eq!(err("[z-a]").col, Some(1));
+        assert_eq!(err("(1 2").cause, "expected )");
+        assert_eq!(err("1 2)").cause, "unexpected )");
+        assert_eq!(err("1 & 2").col, Some(3));
+        assert_eq!(
+            err("99999999999").cause,
+            "rule ID out of range: 99999999999"
+        );
+    }
+
+    #[test]
+    fn test_lowering() {
+        let mut matcher = SmartRegexMatcher::new();
+        matcher.add_rule(0, &parse("\"ab\"+ [xy]? 1*"));
+        matcher.add_rule(1, &parse("\"c\""));
+        let dfa = Dfa::compile(&matcher, 0).unwrap();
+        let parser = EarleyParser::new(&matcher);
+        let msgs = ["ab", "ababy", "abxccc", "abyx", "", "c", "abab"];
+        let matches: Vec<&str> =
+            msgs.iter().copied().filter(|msg| dfa.is_match(msg)).collect();
+        assert_eq!(matches, vec!["ab", "ababy", "abxccc", "abab"]);
+        for msg in &msgs {
+            assert_eq!(parser.parse(0, msg).is_match(), dfa.is_match(msg));
+        }
+        assert!(matcher.is_helper(RegexId::MAX));
+        assert_eq!(matcher.owner(RegexId::MAX), 0);
+        assert!(!matcher.is_helper(1));
+        assert_eq!(matcher.owner(1), 1);
+    }
+}
@@ -0,0 +1,671 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 20 Solution
+    2020-12-20 -- 2020-12-22
+
+    Time (--release): 0m0.055s
+*/
+
+use crate::solution::{diagnostic, Solution, SolveError, SolveResult};
+use crate::trace;
+use crate::util::{
+    parse_groups_with, parse_str, Grid, ParseError, ParseResult,
+};
+use std::collections::HashMap;
+
+/*
+    Tiles are stored as Boolean grids. They support the following:
+
+    - Naming edges:
+      EdgeInfo can be used to give unique names to tile edges, considering them
+      to be either oriented or unoriented. The unique unoriented value is just
+      the min of the oriented value and its reverse.
+      Collecting all of the unoriented values is enough for part 1, as this
+      allows detecting which tiles are corners.
+
+    - Rotation and reflection:
+      This is needed in part 2 to assemble all the tiles in the puzzle together.
+      We can iterate over all 8 rotations and reflections by repeatedly
+      calling .reorient().
+
+    - Assembling:
+      Check if the tile fits together with another tile along a given direction.
+*/
+
+// Utility: read bools as binary (at most usize::BITS of them)
+fn bools_to_int<I: Iterator<Item = bool>>(v: I) -> usize {
+    v.fold(0, |n, b| (n << 1) | usize::from(b))
+}
+
+/// Identifiers for a tile edge, read in either direction.
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeInfo {
+    fwd_id: usize,
+    bck_id: usize,
+}
+impl EdgeInfo {
+    fn from_bools<I>(bools: I) -> Self
+    where
+        I: Clone + DoubleEndedIterator<Item = bool>,
+    {
+        let bools_rev = bools.clone().rev();
+        let fwd_id = bools_to_int(bools);
+        let bck_id = bools_to_int(bools_rev);
+        Self { fwd_id, bck_id }
+    }
+
+    pub fn oriented_id(&self) -> usize {
+        self.fwd_id
+    }
+    pub fn unoriented_id(&self) -> usize {
+        self.fwd_id.min(self.bck_id)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+use Direction::{East, North, South, West};
+
+/// Square tile of pixels, with an ID.
+#[derive(Clone, Debug)]
+pub struct Tile {
+    id: usize,
+    len: usize,
+    grid: Grid<bool>, // len x len grid
+    times_reoriented: usize,
+}
+const TILE_DISPLAY_MAX_ROWS: usize = 7;
+const TILE_DISPLAY_MAX_COLS: usize = 50;
+impl Tile {
+    /// A tile from a square grid, with edges short enough to identify
+    /// (from 1 to usize::BITS pixels).
+    pub fn new(id: usize, grid: Grid<bool>) -> ParseResult<Self> {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        if rows != cols || rows == 0 || rows > usize::BITS as usize {
+            let msg = format!("tile is {} x {} pixels", rows, cols);
+            return Err(ParseError::new(msg));
+        }
+        Ok(Self::from_grid(id, grid))
+    }
+    // Any square grid, for the assembled image (whose edges aren't used)
+    fn from_grid(id: usize, grid: Grid<bool>) -> Self {
+        let len = grid.rows();
+        assert_eq!(grid.cols(), len);
+        let times_reoriented = 0;
+        Tile { id, len, grid, times_reoriented }
+    }
+
+    /* Edge getters */
+    fn edge_from_coords<I>(&self, coords: I) -> EdgeInfo
+    where
+        I: Clone + DoubleEndedIterator<Item = (usize, usize)>,
+    {
+        EdgeInfo::from_bools(coords.map(|pos| self.grid[pos]))
+    }
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    pub fn get_edge(&self, dir: Direction) -> EdgeInfo {
+        let n = self.len;
+        match dir {
+            North => self.edge_from_coords((0..n).map(|j| (0, j))),
+            East => self.edge_from_coords((0..n).map(|i| (i, n - 1))),
+            South => self.edge_from_coords((0..n).map(|j| (n - 1, j))),
+            West => self.edge_from_coords((0..n).map(|i| (i, 0))),
+        }
+    }
+    pub fn get_edges(&self) -> [EdgeInfo; 4] {
+        [
+            self.get_edge(North),
+            self.get_edge(East),
+            self.get_edge(South),
+            self.get_edge(West),
+        ]
+    }
+
+    /* Rotation and reflection */
+    /// Rotate clockwise by 90 degrees.
+    pub fn rotate(&mut self) {
+        self.grid = self.grid.rotate();
+    }
+    /// Reflect along the main diagonal.
+    pub fn reflect(&mut self) {
+        self.grid = self.grid.reflect();
+    }
+    /// Move to the next of the 8 orientations (rotations and reflections).
+    pub fn reorient(&mut self) {
+        self.rotate();
+        self.times_reoriented += 1;
+        if self.times_reoriented.is_multiple_of(4) {
+            self.reflect();
+        }
+    }
+
+    /* Check fitting together with another tile */
+    fn fits_core(
+        &self,
+        other: &Self,
+        dir1: Direction,
+        dir2: Direction,
+    ) -> bool {
+        self.get_edge(dir1).oriented_id() == other.get_edge(dir2).oriented_id()
+    }
+    pub fn fits_south(&self, other: &Self) -> bool {
+        self.fits_core(other, South, North)
+    }
+    pub fn fits_east(&self, other: &Self) -> bool {
+        self.fits_core(other, East, West)
+    }
+
+    // Printing
+    pub fn print(&self) {
+        for row in self.grid.iter_rows().take(TILE_DISPLAY_MAX_ROWS) {
+            for pixel in row.iter().take(TILE_DISPLAY_MAX_COLS) {
+                match pixel {
+                    true => eprint!("#"),
+                    false => eprint!("."),
+                };
+            }
+            if self.len > TILE_DISPLAY_MAX_COLS {
+                eprint!(" …");
+            }
+            eprintln!();
+        }
+        if self.len > TILE_DISPLAY_MAX_ROWS {
+            eprintln!("         …  …  …");
+        }
+    }
+}
+
+/*
+    Puzzle types for sorting and assembling the puzzle
+
+    I previously had a single monolithic Puzzle struct for all stages of
+    assembly, but this seems like bad design: the data model is completely
+    different before tiles are sorted in, after tiles are assembled into
+    a puzzle, and after the puzzle is assembled into an image.
+    Therefore, we instead adopt a more functional style with types:
+        - UnsortedPuzzle: a collection of tiles
+        - SortedPuzzle: tiles sorted into useful categories
+          (Part 1 is solved at this stage)
+        - AssembledPuzzle: tiles assembled into the correct grid
+        - AssembledImage: the image extracted from the assembled puzzle
+          (Part 2 is solved at this stage)
+*/
+
+/// Collection of tiles, indexed by their edges.
+#[derive(Debug, Default)]
+pub struct UnsortedPuzzle {
+    tiles: HashMap<usize, Tile>,       // tile ID -> tile
+    edges: HashMap<usize, Vec<usize>>, // *unoriented* edge ID -> tile IDs
+    tile_len: usize,
+}
+/// Tiles sorted into corner, edge and inside tiles.
+#[derive(Debug, Default)]
+pub struct SortedPuzzle {
+    corner_tiles: Vec<Tile>,
+    edge_tiles: Vec<Tile>,
+    inside_tiles: Vec<Tile>,
+    puzzle_len: usize,
+}
+/// Tiles assembled (and oriented) into the correct grid.
+#[derive(Debug)]
+pub struct AssembledPuzzle {
+    grid: Vec<Vec<Tile>>,
+    tile_len: usize,
+    puzzle_len: usize,
+}
+/// Image extracted from the assembled puzzle (tile borders removed).
+#[derive(Clone, Debug)]
+pub struct AssembledImage(Tile);
+
+/*
+    Collecting the puzzle tiles to store the set of corresponding edges
+*/
+impl UnsortedPuzzle {
+    pub fn new(tile_list: &[Tile]) -> Self {
+        let mut puzzle: Self = Default::default();
+        for tile in tile_list {
+            // All puzzle tiles should be the same length
+            if puzzle.tile_len == 0 {
+                puzzle.tile_len = tile.len;
+            } else {
+                assert_eq!(puzzle.tile_len, tile.len);
+            }
+            // Store tile and its edges
+            puzzle.tiles.insert(tile.id, tile.clone());
+            for &info in &tile.get_edges() {
+                let entry = puzzle.edges.entry(info.unoriented_id());
+                entry.or_default().push(tile.id);
+            }
+        }
+        puzzle
+    }
+
+    fn is_puzzle_edge(&self, edge: &EdgeInfo) -> bool {
+        let edge_count = self.edges[&edge.unoriented_id()].len();
+        // Important check: each unoriented ID uniquely implies either
+        // a single tile or a pair of tiles
+        assert!(edge_count == 1 || edge_count == 2);
+        edge_count == 1
+    }
+    fn get_other_at_edge(&self, tile: &Tile, edge: &EdgeInfo) -> Tile {
+        assert!(!self.is_puzzle_edge(edge));
+        let possibilities = &self.edges[&edge.unoriented_id()];
+        if possibilities[0] == tile.id {
+            self.tiles[&possibilities[1]].clone()
+        } else {
+            self.tiles[&possibilities[0]].clone()
+        }
+    }
+
+    pub fn print_tile_counts(&self) {
+        eprintln!("Total tiles: {}", self.tiles.len());
+        eprintln!("Unique tile edge patterns: {}", self.edges.len());
+    }
+}
+
+/*
+    Sorting the tiles
+*/
+impl SortedPuzzle {
+    pub fn new(unsorted: &UnsortedPuzzle) -> ParseResult<Self> {
+        let mut puzzle: Self = Default::default();
+        if unsorted.edges.values().any(|ids| ids.len() > 2) {
+            return Err(ParseError::new(
+                "found an edge shared by more than two tiles",
+            ));
+        }
+        for tile in unsorted.tiles.values() {
+            let mut unique_edges = 0;
+            for info in &tile.get_edges() {
+                if unsorted.is_puzzle_edge(info) {
+                    unique_edges += 1;
+                }
+            }
+            match unique_edges {
+                2 => puzzle.corner_tiles.push(tile.clone()),
+                1 => puzzle.edge_tiles.push(tile.clone()),
+                0 => puzzle.inside_tiles.push(tile.clone()),
+                _ => {
+                    return Err(ParseError::new(format!(
+                        "found tile {} with {} unmatched edges",
+                        tile.id, unique_edges
+                    )))
+                }
+            }
+        }
+        // Calculate puzzle dimensions (assume a square)
+        puzzle.puzzle_len = (puzzle.edge_tiles.len() / 4) + 2;
+        puzzle.check_tile_counts(unsorted)?;
+        Ok(puzzle)
+    }
+
+    fn check_tile_counts(&self, unsorted: &UnsortedPuzzle) -> ParseResult<()> {
+        let n = self.puzzle_len;
+        if self.corner_tiles.len() != 4
+            || self.edge_tiles.len() != 4 * (n - 2)
+            || self.inside_tiles.len() != (n - 2) * (n - 2)
+            || unsorted.tiles.len() != n * n
+            || unsorted.edges.len() != 2 * n * (n + 1)
+        {
+            return Err(ParseError::new(format!(
+                "tiles do not form a square puzzle ({} corner, {} edge, {} \
+                 inside)",
+                self.corner_tiles.len(),
+                self.edge_tiles.len(),
+                self.inside_tiles.len()
+            )));
+        }
+        Ok(())
+    }
+    pub fn print_tile_counts(&self) {
+        eprintln!("Corner tiles: {}", self.corner_tiles.len());
+        eprintln!("Edge tiles: {}", self.edge_tiles.len());
+        eprintln!("Inside tiles: {}", self.inside_tiles.len());
+    }
+}
+
+/*
+    Assembling the tiles (in top-to-bottom, left-to-right order)
+*/
+fn fits_southeast(
+    unsorted: &UnsortedPuzzle,
+    above: Option<&Tile>,
+    left: Option<&Tile>,
+    tile: &Tile,
+) -> bool {
+    (above.is_some() || unsorted.is_puzzle_edge(&tile.get_edge(North)))
+        && (above.is_none() || above.unwrap().fits_south(tile))
+        && (left.is_some() || unsorted.is_puzzle_edge(&tile.get_edge(West)))
+        && (left.is_none() || left.unwrap().fits_east(tile))
+}
+fn assemble_tile(
+    unsorted: &UnsortedPuzzle,
+    above: Option<&Tile>,
+    left: Option<&Tile>,
+    tile: &mut Tile,
+) {
+    // Precondition: there exists a unique orientation that fits
+    while !fits_southeast(unsorted, above, left, tile) {
+        tile.reorient();
+    }
+}
+impl AssembledPuzzle {
+    pub fn new(unsorted: &UnsortedPuzzle, sorted: &SortedPuzzle) -> Self {
+        let tile_len = unsorted.tile_len;
+        let puzzle_len = sorted.puzzle_len;
+        let mut grid: Vec<Vec<Tile>> = Vec::new(); // n x n grid
+        for i in 0..puzzle_len {
+            grid.push(Vec::new());
+            for j in 0..puzzle_len {
+                // Assemble tile (i, j)
+                if i == 0 && j == 0 {
+                    let mut new_tile = sorted.corner_tiles[0].clone();
+                    assemble_tile(unsorted, None, None, &mut new_tile);
+                    grid[i].push(new_tile);
+                } else if j == 0 {
+                    // i > 0, j == 0
+                    let above = &grid[i - 1][j];
+                    let edge = above.get_edge(South);
+                    let mut new_tile = unsorted.get_other_at_edge(above, &edge);
+                    assemble_tile(unsorted, Some(above), None, &mut new_tile);
+                    grid[i].push(new_tile);
+                } else {
+                    // j > 0
+                    let left = &grid[i][j - 1];
+                    let edge = left.get_edge(East);
+                    let mut new_tile = unsorted.get_other_at_edge(left, &edge);
+                    let above =
+                        if i == 0 { None } else { Some(&grid[i - 1][j]) };
+                    assemble_tile(unsorted, above, Some(left), &mut new_tile);
+                    grid[i].push(new_tile);
+                }
+                debug_assert_eq!(grid[i][j].len, tile_len);
+            }
+            debug_assert_eq!(grid[i].len(), puzzle_len);
+        }
+        debug_assert_eq!(grid.len(), puzzle_len);
+        Self { grid, tile_len, puzzle_len }
+    }
+    pub fn print_ids(&self) {
+        for row in &self.grid {
+            for tile in row {
+                eprint!("{} ", tile.id);
+            }
+            eprintln!();
+        }
+    }
+}
+
+/*
+    Assembling the image
+*/
+impl AssembledImage {
+    pub fn new(assembled: &AssembledPuzzle) -> Self {
+        let canvas_step = assembled.tile_len - 2;
+        let tile_last = assembled.tile_len - 1;
+        let puzzle_len = assembled.puzzle_len;
+        let canvas_size = canvas_step * puzzle_len;
+        let mut canvas = Grid::new(canvas_size, canvas_size, false);
+        for row in 0..puzzle_len {
+            for col in 0..puzzle_len {
+                let tile = &assembled.grid[row][col];
+                // Sanity check: verify that the pixels line up
+                if row > 0 {
+                    let prev = &assembled.grid[row - 1][col];
+                    for j in 0..canvas_step {
+                        assert_eq!(
+                            tile.grid[(0, j)],
+                            prev.grid[(tile_last, j)]
+                        );
+                    }
+                }
+                if col > 0 {
+                    let prev = &assembled.grid[row][col - 1];
+                    for i in 0..canvas_step {
+                        assert_eq!(
+                            tile.grid[(i, 0)],
+                            prev.grid[(i, tile_last)]
+                        );
+                    }
+                }
+                // Copy over other pixels
+                for i in 0..canvas_step {
+                    for j in 0..canvas_step {
+                        let x = canvas_step * row + i;
+                        let y = canvas_step * col + j;
+                        let pixel = tile.grid[(i + 1, j + 1)];
+                        canvas[(x, y)] = pixel;
+                    }
+                }
+            }
+        }
+        Self(Tile::from_grid(0, canvas))
+    }
+}
+
+/*
+    Detecting sea monsters
+
+    Sea monster image:
+    ----------------------
+    |                  # |
+    |#    ##    ##    ###|
+    | #  #  #  #  #  #   |
+    ----------------------
+    3 x 20
+*/
+
+const SEAMONSTER_COORDS: &[(usize, usize)] = &[
+    (1, 0),
+    (2, 1),
+    (2, 4),
+    (1, 5),
+    (1, 6),
+    (2, 7),
+    (2, 10),
+    (1, 11),
+    (1, 12),
+    (2, 13),
+    (2, 16),
+    (1, 17),
+    (1, 18),
+    (0, 18),
+    (1, 19),
+];
+
+impl AssembledImage {
+    fn seamonster_at(&self, i: usize, j: usize) -> bool {
+        if i + 2 >= self.0.len || j + 19 >= self.0.len {
+            return false;
+        }
+        for (di, dj) in SEAMONSTER_COORDS {
+            if !self.0.grid[(i + di, j + dj)] {
+                return false;
+            }
+        }
+        true
+    }
+    pub fn count_seamonsters(&self) -> usize {
+        let mut count = 0;
+        for i in 0..self.0.len {
+            for j in 0..self.0.len {
+                if self.seamonster_at(i, j) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    /// Reorient the image so that sea monsters are visible.
+    pub fn find_seamonster_orientation(&mut self) {
+        while self.count_seamonsters() == 0 {
+            self.0.reorient();
+        }
+        let seamonsters = self.count_seamonsters();
+        self.0.reorient();
+        for _ in 0..7 {
+            assert_eq!(self.count_seamonsters(), 0);
+            self.0.reorient();
+        }
+        assert_eq!(seamonsters, self.count_seamonsters());
+    }
+
+    fn erase_seamonster_at(&mut self, i: usize, j: usize) {
+        for (di, dj) in SEAMONSTER_COORDS {
+            self.0.grid[(i + di, j + dj)] = false;
+        }
+    }
+    pub fn erase_all_seamonsters(&self) -> Self {
+        let mut other = self.clone();
+        for i in 0..self.0.len {
+            for j in 0..self.0.len {
+                if self.seamonster_at(i, j) {
+                    other.erase_seamonster_at(i, j);
+                }
+            }
+        }
+        other
+    }
+
+    pub fn print(&self) {
+        self.0.print();
+        eprintln!("Seamonsters found: {}", self.count_seamonsters());
+    }
+}
+
+/*
+    Answers, input parsing, and entrypoint
+*/
+
+/// Product of the corner tile IDs.
+pub fn part1_answer(sorted: &SortedPuzzle) -> usize {
+    sorted.corner_tiles.iter().map(|c| c.id).product()
+}
+
+/// Number of pixels which are not part of a sea monster.
+pub fn part2_answer(clean: &AssembledImage) -> usize {
+    clean.0.grid.count(|&p| p)
+}
+
+/// Parse a tile of the form `Tile 1234:` followed by 10 rows.
+pub fn parse_tile(lines: &[String]) -> ParseResult<Tile> {
+    let err = |i: usize, msg: &str| {
+        let line = lines.get(i).map_or("", |s| s.as_str());
+        Err(ParseError::new(msg).at_line(i + 1, line))
+    };
+    let tile_id = match lines[0]
+        .strip_prefix("Tile ")
+        .and_then(|rest| rest.strip_suffix(':'))
+    {
+        Some(raw) => {
+            parse_str(raw).map_err(|err| err.at_line(1, &lines[0]).at_col(6))?
+        }
+        None => return err(0, "expected `Tile N:`"),
+    };
+    let parse_pixel = |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    };
+    let grid = Grid::parse_lines_with(&lines[1..], parse_pixel)
+        .map_err(|err| err.offset_lines(1))?;
+    if grid.rows() != 10 || grid.cols() != 10 {
+        return err(0, "expected 10 x 10 pixels after the tile ID");
+    }
+    Tile::new(tile_id, grid)
+}
+/// Parse tiles, separated by blank lines.
+pub fn parse_input(lines: &[String]) -> ParseResult<Vec<Tile>> {
+    parse_groups_with(lines, parse_tile)
+}
+
+/// Solution for day 20
+pub struct Day20;
+impl Solution for Day20 {
+    type Input = Vec<Tile>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let tile_list = parse_input(lines)?;
+        SortedPuzzle::new(&UnsortedPuzzle::new(&tile_list))?;
+        Ok(tile_list)
+    }
+    fn part1(tile_list: &Self::Input) -> SolveResult<String> {
+        let verbose = trace::enabled(trace::INFO);
+        let unsorted = UnsortedPuzzle::new(tile_list);
+        if verbose {
+            eprintln!("=== Unsorted puzzle ===");
+            unsorted.print_tile_counts();
+        }
+
+        let sorted = SortedPuzzle::new(&unsorted).map_err(SolveError::new)?;
+        if verbose {
+            eprintln!("=== Sorted puzzle ===");
+            sorted.print_tile_counts();
+        }
+
+        Ok(part1_answer(&sorted).to_string())
+    }
+    fn part2(tile_list: &Self::Input) -> SolveResult<String> {
+        let verbose = trace::enabled(trace::INFO);
+        let unsorted = UnsortedPuzzle::new(tile_list);
+        let sorted = SortedPuzzle::new(&unsorted).map_err(SolveError::new)?;
+
+        let assembled = AssembledPuzzle::new(&unsorted, &sorted);
+        if verbose {
+            eprintln!("=== Solved puzzle ===");
+            assembled.print_ids();
+        }
+
+        let mut image = AssembledImage::new(&assembled);
+        image.find_seamonster_orientation();
+        if verbose {
+            eprintln!("=== Assembled image (oriented) ===");
+            image.print();
+        }
+        let seamonsters = image.count_seamonsters();
+        diagnostic(format!("seamonsters found: {}", seamonsters));
+
+        let clean = image.erase_all_seamonsters();
+        if verbose {
+            eprintln!("=== Seamonster-free image ===");
+            clean.print();
+        }
+
+        Ok(part2_answer(&clean).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_sizes() {
+        let mut grid = Grid::new(3, 3, false);
+        grid[(0, 0)] = true;
+        grid[(0, 2)] = true;
+        let tile = Tile::new(7, grid).unwrap();
+        assert_eq!(tile.get_edge(North).oriented_id(), 0b101);
+        assert_eq!(tile.get_edge(West).unoriented_id(), 0b001);
+        assert!(Tile::new(7, Grid::new(0, 0, false)).is_err());
+        assert!(Tile::new(7, Grid::new(2, 3, false)).is_err());
+        assert!(Tile::new(7, Grid::new(65, 65, false)).is_err());
+        assert!(Tile::new(7, Grid::new(64, 64, true)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use crate::util::str_to_vec;
+        assert!(Day20::parse(&[]).is_err());
+        let mut raw = "Tile 1:\n".to_owned();
+        raw.push_str(&"#.........\n".repeat(10));
+        assert!(Day20::parse(&str_to_vec(&raw)).is_err());
+    }
+}
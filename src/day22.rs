@@ -0,0 +1,299 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 22 Solution
+    2020-12-22
+
+    Time (--release): 0m0.506s
+    Time (--debug): 0m9.072s
+*/
+
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::trace;
+use crate::util::{iter_to_pair, parse_groups_with, parse_lines};
+use crate::util::{unique, unique_1_to_n};
+use crate::util::{ParseError, ParseResult};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/*
+    Basic types
+
+    Card is a simple wrapper around usize.
+    However, we deliberately do not derive Copy or Clone.
+    This has the nice guarantee that we know cards won't be duplicated during
+    the game, which matches the reality of physical cards and ensures we
+    don't make a mistake like pushing a card onto both player's decks.
+*/
+#[derive(Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Card(pub usize);
+#[derive(Clone, Copy, Debug)]
+pub enum Player {
+    One,
+    Two,
+}
+
+/*
+    The SpaceCards game state, implementing both part 1 and part 2 logic.
+*/
+/// State of a game of (recursive) Combat.
+#[derive(Debug)]
+pub struct SpaceCards {
+    deck1: VecDeque<Card>,
+    deck2: VecDeque<Card>,
+    winner: Option<Player>,
+    seen_hashes: HashSet<u64>,
+}
+impl SpaceCards {
+    /*
+        Constructor for a new game.
+        In debug mode, this validates that the deck cards provided are unique,
+        and optionally further that they are equal to 1 through n for some n.
+    */
+    fn debug_checks(
+        start_deck1: &[usize],
+        start_deck2: &[usize],
+        verify_all_cards_present: bool,
+    ) -> bool {
+        let all_cards: Vec<usize> =
+            start_deck1.iter().chain(start_deck2.iter()).copied().collect();
+        if verify_all_cards_present {
+            unique_1_to_n(all_cards.iter())
+        } else {
+            unique(all_cards.iter())
+        }
+    }
+    pub fn new(
+        start_deck1: &[usize],
+        start_deck2: &[usize],
+        verify_all_cards_present: bool,
+    ) -> Self {
+        let deck1 = start_deck1.iter().map(|&i| Card(i)).collect();
+        let deck2 = start_deck2.iter().map(|&i| Card(i)).collect();
+        debug_assert!(Self::debug_checks(
+            start_deck1,
+            start_deck2,
+            verify_all_cards_present
+        ));
+        let winner = None;
+        let seen_hashes = HashSet::new();
+        Self { deck1, deck2, winner, seen_hashes }
+    }
+
+    /*
+        Game score and printing functionality.
+    */
+    pub fn deck_score(deck: &VecDeque<Card>) -> usize {
+        deck.iter().rev().enumerate().map(|(i, Card(j))| (i + 1) * j).sum()
+    }
+    pub fn print_state(&self) {
+        eprint!("Player 1 deck:");
+        for &Card(i) in &self.deck1 {
+            eprint!(" {}", i);
+        }
+        eprintln!();
+        eprint!("Player 2 deck:");
+        for &Card(i) in &self.deck2 {
+            eprint!(" {}", i);
+        }
+        eprintln!();
+    }
+    /// Whether the cards in both decks are still exactly 1 to n.
+    pub fn check_cards(&self) -> bool {
+        let cards = self.deck1.iter().chain(&self.deck2).map(|card| &card.0);
+        unique_1_to_n(cards)
+    }
+    /// Score of the winning deck, once the game is over.
+    pub fn winner_score(&self) -> usize {
+        match self.winner {
+            Some(Player::One) => Self::deck_score(&self.deck1),
+            Some(Player::Two) => Self::deck_score(&self.deck2),
+            None => panic!("Score called on game still in progress!"),
+        }
+    }
+    pub fn print_end_state(&self) {
+        match self.winner {
+            Some(Player::One) => {
+                debug_assert!(self.deck2.is_empty());
+                diagnostic("player 1 wins");
+                self.print_state();
+                eprintln!("Score (answer): {}", Self::deck_score(&self.deck1));
+            }
+            Some(Player::Two) => {
+                debug_assert!(self.deck1.is_empty());
+                diagnostic("player 2 wins");
+                self.print_state();
+                eprintln!("Score (answer): {}", Self::deck_score(&self.deck2));
+            }
+            None => panic!("End state called on game still in progress!"),
+        }
+    }
+
+    /*
+        Part 1 Rules
+    */
+    fn part1_step(&mut self) -> bool {
+        if self.deck1.is_empty() {
+            self.winner = Some(Player::Two);
+            false
+        } else if self.deck2.is_empty() {
+            self.winner = Some(Player::One);
+            false
+        } else {
+            if trace::enabled(trace::DEBUG) {
+                self.print_state();
+            }
+            let c1 = self.deck1.pop_front().unwrap();
+            let c2 = self.deck2.pop_front().unwrap();
+            match c1.cmp(&c2) {
+                Ordering::Less => {
+                    self.deck2.push_back(c2);
+                    self.deck2.push_back(c1);
+                }
+                Ordering::Greater => {
+                    self.deck1.push_back(c1);
+                    self.deck1.push_back(c2);
+                }
+                Ordering::Equal => unreachable!(),
+            }
+            true
+        }
+    }
+    /// Play (non-recursive) Combat to completion.
+    pub fn part1_execute(&mut self) {
+        while self.part1_step() {}
+    }
+
+    /*
+        Part 2 Rules
+
+        The one iffy thing we do is store the game state as a u64 hash
+        instead of as a truly unique value, which is mainly to avoid dealing
+        with a hashset over the entire state (VecDeque<Card>, VecDeque<Card>)
+        and a lot of associated copying/cloning.
+        Depending on how long typical games are, which I don't know, the
+        probability of a collision may be sufficiently low to justify this.
+        It at least gives the correct answer on the provided input.
+    */
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.deck1.hash(&mut hasher);
+        self.deck2.hash(&mut hasher);
+        hasher.finish()
+    }
+    fn top_cards(deck: &VecDeque<Card>, n: usize) -> Vec<usize> {
+        // Precondition: deck has at least n cards
+        let result: Vec<_> = deck.iter().take(n).map(|x| x.0).collect();
+        debug_assert_eq!(result.len(), n);
+        result
+    }
+    fn part2_step(&mut self) -> bool {
+        // Check for repeated state -- player 1 wins on repetition
+        let state = self.state_hash();
+        if self.seen_hashes.contains(&state) {
+            self.winner = Some(Player::One);
+            return false;
+        }
+        self.seen_hashes.insert(state);
+        // Check for deck empty (same as in part 1)
+        if self.deck1.is_empty() {
+            self.winner = Some(Player::Two);
+            return false;
+        } else if self.deck2.is_empty() {
+            self.winner = Some(Player::One);
+            return false;
+        }
+        if trace::enabled(trace::DEBUG) {
+            self.print_state();
+        }
+        // Draw cards
+        let c1 = self.deck1.pop_front().unwrap();
+        let c2 = self.deck2.pop_front().unwrap();
+        let round_winner =
+            if self.deck1.len() >= c1.0 && self.deck2.len() >= c2.0 {
+                // Recursive combat!!!
+                let new_deck1 = Self::top_cards(&self.deck1, c1.0);
+                let new_deck2 = Self::top_cards(&self.deck2, c2.0);
+                let mut rec_game = Self::new(&new_deck1, &new_deck2, false);
+                rec_game.part2_execute()
+            } else {
+                // Normal rules (same as in part 1)
+                match c1.cmp(&c2) {
+                    Ordering::Greater => Player::One,
+                    Ordering::Less => Player::Two,
+                    Ordering::Equal => unreachable!(),
+                }
+            };
+        // Push cards back on deck
+        match round_winner {
+            Player::One => {
+                self.deck1.push_back(c1);
+                self.deck1.push_back(c2);
+            }
+            Player::Two => {
+                self.deck2.push_back(c2);
+                self.deck2.push_back(c1);
+            }
+        }
+        true
+    }
+    /// Play Recursive Combat to completion and return the winner.
+    pub fn part2_execute(&mut self) -> Player {
+        while self.part2_step() {}
+        self.winner.unwrap()
+    }
+}
+
+/*
+    Input parsing and solution
+*/
+/// Parse the starting decks of both players.
+pub fn parse_input(lines: &[String]) -> ParseResult<(Vec<usize>, Vec<usize>)> {
+    let mut player = 0;
+    let decks = parse_groups_with(lines, |deck_lines| {
+        player += 1;
+        let header = format!("Player {}:", player);
+        if deck_lines[0] != header {
+            let msg = format!("expected {:?}", header);
+            return Err(ParseError::new(msg).at_line(1, &deck_lines[0]));
+        }
+        parse_lines(&deck_lines[1..]).map_err(|err| err.offset_lines(1))
+    })?;
+    let (deck1, deck2) = iter_to_pair(decks.into_iter())
+        .map_err(|err| ParseError::new(format!("{} (players)", err.cause)))?;
+    if !SpaceCards::debug_checks(&deck1, &deck2, true) {
+        let msg = "the cards should be 1 to n, each appearing once";
+        return Err(ParseError::new(msg));
+    }
+    Ok((deck1, deck2))
+}
+/// Solution for day 22
+pub struct Day22;
+impl Solution for Day22 {
+    type Input = (Vec<usize>, Vec<usize>);
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_input(lines)
+    }
+    fn part1(
+        (starting_deck1, starting_deck2): &Self::Input,
+    ) -> SolveResult<String> {
+        let mut game = SpaceCards::new(starting_deck1, starting_deck2, true);
+        game.part1_execute();
+        if trace::enabled(trace::INFO) {
+            game.print_end_state();
+        }
+        Ok(game.winner_score().to_string())
+    }
+    fn part2(
+        (starting_deck1, starting_deck2): &Self::Input,
+    ) -> SolveResult<String> {
+        let mut game = SpaceCards::new(starting_deck1, starting_deck2, true);
+        game.part2_execute();
+        if trace::enabled(trace::INFO) {
+            game.print_end_state();
+        }
+        Ok(game.winner_score().to_string())
+    }
+}
@@ -0,0 +1,205 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 24 Solution
+    2020-12-24
+
+    Time (--release): 0m0.255s
+*/
+
+use crate::automaton::{Automaton, HexLattice, Rule, SparseAutomaton};
+use crate::solution::{Solution, SolveResult};
+use crate::util::{parse_lines_with, ParseError, ParseResult};
+use derive_more::{Add, Sum};
+use std::collections::HashSet;
+use std::iter::FromIterator;
+
+/*
+    Hexagonal coordinates
+
+    These are just the same as rectangular coordinates with an appropriate
+    choice of basis.
+    The function agg_path aggregates the steps along a path for part 1.
+*/
+
+#[derive(Add, Clone, Debug, Eq, Hash, PartialEq, Sum)]
+pub struct HexCoord(pub isize, pub isize);
+
+pub const E: HexCoord = HexCoord(1, 0);
+pub const NE: HexCoord = HexCoord(0, 1);
+pub const NW: HexCoord = HexCoord(-1, 1);
+pub const W: HexCoord = HexCoord(-1, 0);
+pub const SW: HexCoord = HexCoord(0, -1);
+pub const SE: HexCoord = HexCoord(1, -1);
+pub const ALL_DIRS: &[HexCoord] = &[E, NE, NW, W, SW, SE];
+
+/// Tile reached by following a path from the reference tile.
+pub fn agg_path(path: &[HexCoord]) -> HexCoord {
+    path.iter().cloned().sum()
+}
+
+// HexCoord iterators
+
+/// The 6 adjacent tiles.
+pub fn neighbors(coord: &HexCoord) -> impl Iterator<Item = HexCoord> {
+    // Need to clone coord to capture and use it in a closure
+    let coord = coord.clone();
+    ALL_DIRS.iter().cloned().map(move |dir| dir + coord.clone())
+}
+
+/*
+    Hexagonal grid
+
+    For part 1: supports .toggle() to toggle tiles and FromIterator<HexCoord>
+    to toggle all tiles specified in the input.
+
+    For part 2: implements .step(), the game of life update rules, as a
+    sparse automaton on the hexagonal lattice: a black tile stays black with
+    1 or 2 black neighbors, and a white tile turns black with exactly 2.
+*/
+
+/// Set of black tiles on an infinite hexagonal grid.
+#[derive(Clone)]
+pub struct HexGrid {
+    grid: HashSet<HexCoord>,
+}
+impl HexGrid {
+    pub fn new() -> Self {
+        HexGrid { grid: HashSet::new() }
+    }
+    /// Number of black tiles
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.grid.is_empty()
+    }
+    pub fn toggle(&mut self, coord: &HexCoord) {
+        if !self.grid.remove(coord) {
+            self.grid.insert(coord.clone());
+        }
+    }
+
+    // Game logic (for part 2)
+    /// Apply one day of the flipping rules.
+    pub fn step(&mut self) {
+        self.step_for(1);
+    }
+    pub fn step_for(&mut self, iterations: usize) {
+        let black = self.grid.iter().map(|coord| (coord.0, coord.1));
+        let mut tiles = SparseAutomaton::new(HexLattice, flip_rule(), black);
+        tiles.step_for(iterations);
+        self.grid = tiles.alive().map(|&(x, y)| HexCoord(x, y)).collect();
+    }
+}
+pub fn flip_rule() -> Rule {
+    Rule::new(&[2], &[1, 2])
+}
+impl Default for HexGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl FromIterator<HexCoord> for HexGrid {
+    fn from_iter<I: IntoIterator<Item = HexCoord>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for coord in iter {
+            grid.toggle(&coord);
+        }
+        grid
+    }
+}
+
+/*
+    Input parsing
+
+    This code is more verbose than I would like.
+    I initially tried to use Regex for more concise parsing but it's not the
+    best for this use case.
+*/
+
+fn parse_dir(dir_raw: &str) -> Result<HexCoord, String> {
+    match dir_raw {
+        "e" => Ok(E),
+        "ne" => Ok(NE),
+        "nw" => Ok(NW),
+        "w" => Ok(W),
+        "sw" => Ok(SW),
+        "se" => Ok(SE),
+        _ => Err(format!("Could not parse direction: {}", dir_raw)),
+    }
+}
+/// Parse a path of directions (e, se, sw, w, nw, ne).
+pub fn parse_line(line: &str) -> ParseResult<Vec<HexCoord>> {
+    let mut char_iter = line.chars().enumerate();
+    let mut result = Vec::new();
+    while let Some((col, ch1)) = char_iter.next() {
+        let mut raw = ch1.to_string();
+        if raw != "e" && raw != "w" {
+            raw.extend(char_iter.next().map(|(_, ch2)| ch2));
+        }
+        let dir = parse_dir(&raw)
+            .map_err(|err| ParseError::new(err).at_col(col + 1))?;
+        result.push(dir);
+    }
+    Ok(result)
+}
+pub fn parse_input(lines: &[String]) -> ParseResult<Vec<Vec<HexCoord>>> {
+    parse_lines_with(lines, parse_line)
+}
+
+/*
+    Solutions
+*/
+
+/// Solution for day 24
+pub struct Day24;
+impl Solution for Day24 {
+    type Input = Vec<Vec<HexCoord>>;
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        parse_input(lines)
+    }
+    fn part1(paths: &Self::Input) -> SolveResult<String> {
+        let grid: HexGrid = paths.iter().map(|p| agg_path(p)).collect();
+        Ok(grid.len().to_string())
+    }
+    fn part2(paths: &Self::Input) -> SolveResult<String> {
+        let mut grid: HexGrid = paths.iter().map(|p| agg_path(p)).collect();
+        grid.step_for(100);
+        Ok(grid.len().to_string())
+    }
+}
+
+/*
+    Unit tests
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: HexCoord = HexCoord(0, 0);
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(E + W, ORIGIN);
+        assert_eq!(NE + SW, ORIGIN);
+        assert_eq!(NW + SE, ORIGIN);
+        assert_eq!(E + NW, NE);
+        assert_eq!(NE + W, NW);
+        assert_eq!(NW + SW, W);
+        assert_eq!(W + SE, SW);
+        assert_eq!(SW + E, SE);
+        assert_eq!(SE + NE, E);
+    }
+
+    #[test]
+    fn test_flipping() {
+        // Two adjacent black tiles: each has 1 black neighbor, and the two
+        // tiles next to both of them turn black
+        let mut grid: HexGrid = vec![ORIGIN, E].into_iter().collect();
+        grid.step();
+        assert_eq!(grid.len(), 4);
+        assert!(grid.grid.contains(&NE) && grid.grid.contains(&SE));
+    }
+}
@@ -0,0 +1,82 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Day 25 Solution
+    2020-12-26
+
+    Time (--release): 0m0.166s
+*/
+
+use crate::info;
+use crate::solution::{diagnostic, Solution, SolveResult};
+use crate::util::{iter_to_pair, parse_lines, ParseError, ParseResult};
+
+// Fixed prime number modulus for the problem
+pub const MODULUS: usize = 20201227;
+
+/// Calculate base^pow (mod MODULUS).
+pub fn encrypt(mut base: usize, mut pow: usize) -> usize {
+    // Calculate the result of base^pow (mod MODULUS).
+    // Uses repeated squaring.
+    let mut result = 1;
+    base %= MODULUS;
+    while pow > 0 {
+        if pow % 2 == 1 {
+            result *= base;
+            result %= MODULUS;
+        }
+        base = (base * base) % MODULUS;
+        pow /= 2;
+    }
+    result
+}
+
+/// Discrete logarithm: find pow such that base^pow = result (mod MODULUS).
+pub fn brute_force_attack(base: usize, result: usize) -> usize {
+    // Calculate pow such that base^pow = result (mod MODULUS).
+    // Uses a simple brute force search.
+    assert!(base > 0 && result > 0 && result < MODULUS); // preconditions
+    let mut pow = 0;
+    let mut prod = 1;
+    while prod != result {
+        prod = (prod * base) % MODULUS;
+        pow += 1;
+    }
+    pow
+}
+
+/// Solution for day 25
+pub struct Day25;
+impl Solution for Day25 {
+    type Input = (usize, usize);
+    fn parse(lines: &[String]) -> ParseResult<Self::Input> {
+        let input: Vec<usize> = parse_lines(lines)?;
+        let (&device_pub, &door_pub) = iter_to_pair(input.iter())?;
+        // Preconditions for brute_force_attack
+        for (i, &key) in input.iter().enumerate() {
+            if key == 0 || key >= MODULUS {
+                let msg = format!("public key should be 1 to {}", MODULUS - 1);
+                return Err(ParseError::new(msg).at_line(i + 1, &lines[i]));
+            }
+        }
+        Ok((device_pub, door_pub))
+    }
+    fn part1(&(device_pub, door_pub): &Self::Input) -> SolveResult<String> {
+        info!("Device public key: {}", device_pub);
+        info!("Door public key: {}", door_pub);
+        let starting_base = 7;
+        let device_pow = brute_force_attack(starting_base, device_pub);
+        let door_pow = brute_force_attack(starting_base, door_pub);
+        diagnostic(format!("device loop size: {}", device_pow));
+        diagnostic(format!("door loop size: {}", door_pow));
+        let answer1 = encrypt(starting_base, device_pow * door_pow);
+        let answer2 = encrypt(device_pub, door_pow);
+        let answer3 = encrypt(door_pub, device_pow);
+        assert_eq!(answer1, answer2);
+        assert_eq!(answer1, answer3);
+        Ok(answer1.to_string())
+    }
+    fn part2(_input: &Self::Input) -> SolveResult<String> {
+        Ok("Freebie!".to_owned())
+    }
+}
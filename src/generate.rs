@@ -0,0 +1,490 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Random input generators
+
+    Seeded generators of puzzle inputs, in the same format as the input
+    files, for property-based and stress testing (see tests/properties.rs).
+    The same seed always gives the same input, so a failing case can be
+    reproduced from its seed alone.
+
+    Rng is a small SplitMix64 generator: not cryptographic, but fast and
+    good enough to explore inputs, without depending on an external crate.
+*/
+
+use std::collections::HashMap;
+
+/// Seeded pseudo-random number generator (SplitMix64).
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// Uniform in 0..n (n > 0).
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        (self.next_u64() % n as u64) as usize
+    }
+    /// Uniform in lo..=hi.
+    pub fn range(&mut self, lo: isize, hi: isize) -> isize {
+        assert!(lo <= hi);
+        lo + self.below((hi - lo) as usize + 1) as isize
+    }
+    /// True with probability p.
+    pub fn chance(&mut self, p: f64) -> bool {
+        (self.next_u64() >> 11) as f64 / ((1u64 << 53) as f64) < p
+    }
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+    /// Shuffle in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/*
+    Day 7: bag rules
+*/
+
+const ADJECTIVES: &[&str] = &[
+    "light", "dark", "bright", "muted", "faded", "dotted", "vibrant", "dull",
+    "pale", "wavy", "plaid", "posh", "drab", "clear", "striped", "mirrored",
+];
+const COLORS: &[&str] = &[
+    "red", "orange", "white", "yellow", "gold", "olive", "plum", "blue",
+    "black", "tan", "teal", "violet", "lime", "cyan", "coral", "beige",
+];
+
+/// Rules for num_bags distinct bags (at most 256, one of them shiny gold),
+/// with no bag (transitively) containing itself.
+pub fn bag_rules(rng: &mut Rng, num_bags: usize) -> Vec<String> {
+    let mut names: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|adj| {
+            COLORS.iter().map(move |color| adj.to_string() + " " + color)
+        })
+        .filter(|name| name != "shiny gold")
+        .collect();
+    rng.shuffle(&mut names);
+    names.truncate(num_bags.max(1) - 1);
+    names.insert(rng.below(names.len() + 1), "shiny gold".to_owned());
+    // Bags only contain bags later in the list
+    (0..names.len())
+        .map(|i| {
+            let mut later: Vec<usize> = ((i + 1)..names.len()).collect();
+            rng.shuffle(&mut later);
+            later.truncate(rng.below(4));
+            let contents: Vec<String> = later
+                .iter()
+                .map(|&j| {
+                    let count = rng.range(1, 4);
+                    let bags = if count == 1 { "bag" } else { "bags" };
+                    format!("{} {} {}", count, names[j], bags)
+                })
+                .collect();
+            if contents.is_empty() {
+                format!("{} bags contain no other bags.", names[i])
+            } else {
+                format!("{} bags contain {}.", names[i], contents.join(", "))
+            }
+        })
+        .collect()
+}
+
+/*
+    Day 8: handheld programs
+*/
+
+fn instruction(op: &str, arg: isize) -> String {
+    format!("{} {:+}", op, arg)
+}
+
+/// Program of len instructions with arbitrary jumps (which may loop or jump
+/// out of the program in either direction).
+pub fn handheld_program(rng: &mut Rng, len: usize) -> Vec<String> {
+    let bound = len as isize + 1;
+    (0..len)
+        .map(|_| {
+            let op = *rng.choose(&["acc", "jmp", "nop"]);
+            instruction(op, rng.range(-bound, bound))
+        })
+        .collect()
+}
+
+/// Program of len instructions which only jumps forward, so always
+/// terminates by running off the end.
+pub fn terminating_program(rng: &mut Rng, len: usize) -> Vec<String> {
+    (0..len)
+        .map(|i| match rng.below(3) {
+            0 => instruction("acc", rng.range(-50, 50)),
+            1 => instruction("jmp", rng.range(1, (len - i) as isize)),
+            _ => instruction("nop", rng.range(-50, 50)),
+        })
+        .collect()
+}
+
+/*
+    Day 10: adapters
+*/
+
+/// num_adapters distinct joltages (in a random order) whose consecutive
+/// differences, starting from the outlet at 0, are drawn from gaps.
+pub fn adapters(
+    rng: &mut Rng,
+    num_adapters: usize,
+    gaps: &[usize],
+) -> Vec<String> {
+    let mut jolts = 0;
+    let mut adapters: Vec<usize> = (0..num_adapters)
+        .map(|_| {
+            jolts += rng.choose(gaps);
+            jolts
+        })
+        .collect();
+    rng.shuffle(&mut adapters);
+    adapters.iter().map(|jolts| jolts.to_string()).collect()
+}
+
+/*
+    Day 13: bus schedules
+*/
+
+const SMALL_PRIMES: &[usize] = &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31];
+
+/// A target time and up to max_buses distinct small prime bus IDs
+/// (so pairwise coprime), with some slots out of service.
+pub fn bus_schedule(rng: &mut Rng, max_buses: usize) -> Vec<String> {
+    let mut primes = SMALL_PRIMES.to_vec();
+    rng.shuffle(&mut primes);
+    let mut slots: Vec<String> = primes[..1 + rng.below(max_buses)]
+        .iter()
+        .map(|bus| bus.to_string())
+        .collect();
+    for _ in 0..rng.below(slots.len() + 1) {
+        slots.insert(rng.below(slots.len() + 1), "x".to_owned());
+    }
+    vec![rng.below(1000).to_string(), slots.join(",")]
+}
+
+/*
+    Day 18: expressions
+*/
+
+// Expression with the given number of digits (at least 1)
+fn expression_of_size(rng: &mut Rng, digits: usize) -> String {
+    if digits == 1 {
+        return rng.range(1, 9).to_string();
+    }
+    // Split the digits among 2 or more operands, some parenthesized
+    let mut operands = Vec::new();
+    let mut left = digits;
+    while left > 0 {
+        let size = if operands.is_empty() {
+            1 + rng.below(left - 1)
+        } else {
+            1 + rng.below(left)
+        };
+        let operand = expression_of_size(rng, size);
+        operands.push(if size > 1 {
+            format!("({})", operand)
+        } else {
+            operand
+        });
+        left -= size;
+    }
+    let mut expr = operands[0].clone();
+    for operand in &operands[1..] {
+        expr += *rng.choose(&[" + ", " * "]);
+        expr += operand;
+    }
+    expr
+}
+
+/// An expression of single digits, + and * and parentheses, with at most
+/// max_digits digits (at most 12, so that the value fits in 64 bits).
+pub fn expression(rng: &mut Rng, max_digits: usize) -> String {
+    assert!((1..=12).contains(&max_digits));
+    let digits = 1 + rng.below(max_digits);
+    expression_of_size(rng, digits)
+}
+
+/*
+    Day 19: rule grammars
+*/
+
+/// Acyclic rules 0 to num_rules - 1 in the day 19 format (num_rules at
+/// least 3), followed by num_msgs messages, about half of which are derived
+/// from rule 0 (so match).
+pub fn rule_grammar(
+    rng: &mut Rng,
+    num_rules: usize,
+    num_msgs: usize,
+) -> Vec<String> {
+    assert!(num_rules >= 3);
+    // Each rule only refers to higher rules; the last two are the letters
+    let mut defs: Vec<Vec<Vec<usize>>> = Vec::new();
+    for id in 0..(num_rules - 2) {
+        let pick = |rng: &mut Rng| id + 1 + rng.below(num_rules - id - 1);
+        let seq = |rng: &mut Rng, len| (0..len).map(|_| pick(rng)).collect();
+        let def = match rng.below(4) {
+            0 => vec![seq(rng, 1)],
+            1 => vec![seq(rng, 2)],
+            2 => vec![seq(rng, 1), seq(rng, 1)],
+            _ => vec![seq(rng, 2), seq(rng, 2)],
+        };
+        defs.push(def);
+    }
+    let mut lines: Vec<String> = defs
+        .iter()
+        .enumerate()
+        .map(|(id, def)| {
+            let alts: Vec<String> = def
+                .iter()
+                .map(|seq| {
+                    let ids: Vec<String> =
+                        seq.iter().map(|id| id.to_string()).collect();
+                    ids.join(" ")
+                })
+                .collect();
+            format!("{}: {}", id, alts.join(" | "))
+        })
+        .collect();
+    lines.push(format!("{}: \"a\"", num_rules - 2));
+    lines.push(format!("{}: \"b\"", num_rules - 1));
+    rng.shuffle(&mut lines);
+    lines.push(String::new());
+    let derive = |rng: &mut Rng| {
+        let mut msg = String::new();
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            if id == num_rules - 2 {
+                msg.push('a');
+            } else if id == num_rules - 1 {
+                msg.push('b');
+            } else {
+                stack.extend(rng.choose(&defs[id]).iter().rev());
+            }
+        }
+        msg
+    };
+    for _ in 0..num_msgs {
+        let mut msg = derive(rng);
+        if rng.chance(0.5) {
+            // Random message of a similar length (which may still match)
+            let len = msg.len() + rng.below(3);
+            msg = (0..len).map(|_| *rng.choose(&['a', 'b'])).collect();
+        }
+        lines.push(msg);
+    }
+    lines
+}
+
+// Right side of a rule in the full rule syntax (see day19::rules), with
+// groups nested at most depth deep
+fn rule_expr(rng: &mut Rng, num_rules: usize, depth: usize) -> String {
+    let seqs: Vec<String> = (0..1 + rng.below(2))
+        .map(|_| {
+            let items: Vec<String> = (0..1 + rng.below(3))
+                .map(|_| {
+                    let atom = match rng.below(if depth == 0 { 3 } else { 4 }) {
+                        0 => rng.below(num_rules).to_string(),
+                        1 => {
+                            let len = rng.below(3);
+                            let lit: String = (0..len)
+                                .map(|_| *rng.choose(&['a', 'b']))
+                                .collect();
+                            format!("\"{}\"", lit)
+                        }
+                        2 => rng.choose(&["[a]", "[ab]", "[a-b]"]).to_string(),
+                        _ => format!(
+                            "({})",
+                            rule_expr(rng, num_rules, depth - 1)
+                        ),
+                    };
+                    atom + *rng.choose(&["", "", "?", "*", "+"])
+                })
+                .collect();
+            items.join(" ")
+        })
+        .collect();
+    seqs.join(" | ")
+}
+
+/// Rules 0 to num_rules - 1 in the full rule syntax (see day19::rules),
+/// which can refer to each other in any order (so can be recursive, and
+/// can match the empty string), followed by num_msgs random messages of
+/// a's and b's.
+pub fn rule_syntax(
+    rng: &mut Rng,
+    num_rules: usize,
+    num_msgs: usize,
+) -> Vec<String> {
+    let mut lines: Vec<String> = (0..num_rules)
+        .map(|id| format!("{}: {}", id, rule_expr(rng, num_rules, 2)))
+        .collect();
+    lines.push(String::new());
+    for _ in 0..num_msgs {
+        let len = 1 + rng.below(6);
+        lines.push((0..len).map(|_| *rng.choose(&['a', 'b'])).collect());
+    }
+    lines
+}
+
+/*
+    Day 20: tile sets
+*/
+
+const TILE_LEN: usize = 10;
+
+fn tile_edges(tile: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let last = TILE_LEN - 1;
+    let col = |j: usize| tile.iter().map(|row| row[j]).collect();
+    vec![tile[0].clone(), tile[last].clone(), col(0), col(last)]
+}
+
+/// An image of side x side tiles (side at least 2), cut into 10 x 10 tiles
+/// which share their borders, randomly rotated, reflected and shuffled.
+/// Returns the input lines and the IDs of the four corner tiles.
+/// Every border pattern is unique (even reversed), so the corners are
+/// exactly the tiles with two unmatched edges.
+pub fn tile_set(rng: &mut Rng, side: usize) -> (Vec<String>, Vec<usize>) {
+    assert!(side >= 2);
+    let size = side * (TILE_LEN - 1) + 1;
+    let (image, tiles) = loop {
+        let image: Vec<Vec<bool>> = (0..size)
+            .map(|_| (0..size).map(|_| rng.chance(0.5)).collect())
+            .collect();
+        let tiles: Vec<Vec<Vec<bool>>> = (0..side * side)
+            .map(|t| {
+                let (r, c) = ((t / side) * 9, (t % side) * 9);
+                image[r..r + TILE_LEN]
+                    .iter()
+                    .map(|row| row[c..c + TILE_LEN].to_vec())
+                    .collect()
+            })
+            .collect();
+        // Each edge pattern should occur once (outer) or twice (shared)
+        let mut counts: HashMap<Vec<bool>, usize> = HashMap::new();
+        for edge in tiles.iter().flat_map(|tile| tile_edges(tile)) {
+            let rev: Vec<bool> = edge.iter().rev().copied().collect();
+            *counts.entry(edge.clone().min(rev)).or_default() += 1;
+        }
+        let outer = counts.values().filter(|&&n| n == 1).count();
+        if counts.values().all(|&n| n <= 2) && outer == 4 * side {
+            break (image, tiles);
+        }
+    };
+    debug_assert_eq!(image.len(), size);
+    let mut ids: Vec<usize> = (1000..10000).collect();
+    rng.shuffle(&mut ids);
+    let corners = [0, side - 1, side * (side - 1), side * side - 1];
+    let corner_ids = corners.iter().map(|&t| ids[t]).collect();
+    let mut order: Vec<usize> = (0..tiles.len()).collect();
+    rng.shuffle(&mut order);
+    let mut lines = Vec::new();
+    for t in order {
+        let mut tile = tiles[t].clone();
+        for _ in 0..rng.below(4) {
+            // Rotate clockwise
+            tile = (0..TILE_LEN)
+                .map(|i| {
+                    (0..TILE_LEN).map(|j| tile[TILE_LEN - 1 - j][i]).collect()
+                })
+                .collect();
+        }
+        if rng.chance(0.5) {
+            tile.reverse();
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("Tile {}:", ids[t]));
+        for row in tile {
+            lines
+                .push(row.iter().map(|&p| if p { '#' } else { '.' }).collect());
+        }
+    }
+    (lines, corner_ids)
+}
+
+/*
+    Day 22: decks
+*/
+
+/// Cards 1 to num_cards (at least 2), shuffled and split between two
+/// players (each with at least one card), in the day 22 format.
+pub fn decks(rng: &mut Rng, num_cards: usize) -> Vec<String> {
+    assert!(num_cards >= 2);
+    let mut cards: Vec<usize> = (1..=num_cards).collect();
+    rng.shuffle(&mut cards);
+    let split = 1 + rng.below(num_cards - 1);
+    let mut lines = vec!["Player 1:".to_owned()];
+    lines.extend(cards[..split].iter().map(|c| c.to_string()));
+    lines.push(String::new());
+    lines.push("Player 2:".to_owned());
+    lines.extend(cards[split..].iter().map(|c| c.to_string()));
+    lines
+}
+
+/*
+    Day 23: cup labelings
+*/
+
+/// Cups 1 to n in a random order.
+pub fn cup_labeling(rng: &mut Rng, n: usize) -> Vec<usize> {
+    let mut cups: Vec<usize> = (1..=n).collect();
+    rng.shuffle(&mut cups);
+    cups
+}
+
+/*
+    Day 24: hex paths
+*/
+
+pub const HEX_DIR_NAMES: &[&str] = &["e", "ne", "nw", "w", "sw", "se"];
+
+/// num_paths paths of up to max_len steps each.
+pub fn hex_paths(
+    rng: &mut Rng,
+    num_paths: usize,
+    max_len: usize,
+) -> Vec<String> {
+    (0..num_paths)
+        .map(|_| {
+            let len = rng.below(max_len + 1);
+            (0..len).map(|_| *rng.choose(HEX_DIR_NAMES)).collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(2020);
+        let first: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+        let mut again = Rng::new(2020);
+        assert!(first.iter().all(|&x| x == again.next_u64()));
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3, 3)));
+            assert!(rng.below(7) < 7);
+        }
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<usize>>());
+    }
+}
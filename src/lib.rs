@@ -1 +1,67 @@
+//! Advent of Code 2020 solutions.
+//!
+//! Each day is a public module implementing [`solution::Solution`], along
+//! with the data structures used to solve it. The [`solution::runner`]
+//! function dispatches to any day by number.
+
+pub mod automaton;
+pub mod bench;
+pub mod generate;
+pub mod parallel;
+pub mod reference;
+pub mod solution;
+pub mod trace;
 pub mod util;
+pub mod verify;
+pub mod vm;
+
+/// Day 1: Report Repair (pairs and triples summing to 2020)
+pub mod day01;
+/// Day 2: Password Philosophy (password policies)
+pub mod day02;
+/// Day 3: Toboggan Trajectory ([`day03::TobogganMap`])
+pub mod day03;
+/// Day 4: Passport Processing ([`day04::Passport`] validation)
+pub mod day04;
+/// Day 5: Binary Boarding (boarding pass seat IDs)
+pub mod day05;
+/// Day 6: Custom Customs (group answers)
+pub mod day06;
+/// Day 7: Handy Haversacks ([`day07::BagGraph`])
+pub mod day07;
+/// Day 8: Handheld Halting ([`day08::State`] of the game console)
+pub mod day08;
+/// Day 9: Encoding Error (XMAS cipher)
+pub mod day09;
+/// Day 10: Adapter Array (joltage arrangements)
+pub mod day10;
+/// Day 11: Seating System ([`day11::SeatMap`])
+pub mod day11;
+/// Day 12: Rain Risk ([`day12::ShipNav`])
+pub mod day12;
+/// Day 13: Shuttle Search (Chinese remainder theorem)
+pub mod day13;
+/// Day 14: Docking Data (bit masks)
+pub mod day14;
+/// Day 15: Rambunctious Recitation ([`day15::GameState`])
+pub mod day15;
+/// Day 16: Ticket Translation ([`day16::Ranges`] and bipartite matching)
+pub mod day16;
+/// Day 17: Conway Cubes ([`day17::LifeGrid`])
+pub mod day17;
+/// Day 18: Operation Order ([`day18::Expression`] evaluation)
+pub mod day18;
+/// Day 19: Monster Messages ([`day19::SmartRegexMatcher`])
+pub mod day19;
+/// Day 20: Jurassic Jigsaw ([`day20::Tile`] assembly)
+pub mod day20;
+/// Day 21: Allergen Assessment ([`day21::InjectionFinder`])
+pub mod day21;
+/// Day 22: Crab Combat ([`day22::SpaceCards`])
+pub mod day22;
+/// Day 23: Crab Cups ([`day23::CupGame`])
+pub mod day23;
+/// Day 24: Lobby Layout ([`day24::HexGrid`])
+pub mod day24;
+/// Day 25: Combo Breaker (discrete logarithm)
+pub mod day25;
@@ -0,0 +1,197 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Running many days concurrently
+
+    run_days solves a list of days on a fixed number of worker threads, which
+    take days from a shared queue. Each day is run under catch_unwind, so a
+    panic in one day is recorded as that day's status rather than bringing
+    down the whole run. Results are returned in the order the days were
+    given, and summary_table formats them for printing.
+*/
+
+use crate::solution::{solve_day, Outcome, BOTH_PARTS};
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How running a day ended.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Ok,
+    /// The input could not be read or parsed, or a part has no answer
+    Error(String),
+    /// The solver panicked, with the panic message
+    Panicked(String),
+}
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        *self == Status::Ok
+    }
+}
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Error(err) => write!(f, "error: {}", err),
+            Status::Panicked(msg) => write!(f, "panicked: {}", msg),
+        }
+    }
+}
+
+/// Result of running both parts of one day.
+#[derive(Clone, Debug)]
+pub struct DayResult {
+    pub day: usize,
+    /// Parsing and answers, if the day ran successfully
+    pub outcome: Option<Outcome>,
+    /// Wall-clock time, including reading the input
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+/// Solve both parts of a day, catching any panic.
+pub fn run_day_isolated(day: usize, path: &str) -> DayResult {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        solve_day(day, path, BOTH_PARTS)
+    }));
+    let elapsed = start.elapsed();
+    let (outcome, status) = match result {
+        Ok(Ok(outcome)) => {
+            let status = match outcome.first_error() {
+                Some((part, err)) => {
+                    Status::Error(format!("part {}: {}", part, err))
+                }
+                None => Status::Ok,
+            };
+            (Some(outcome), status)
+        }
+        Ok(Err(err)) => (None, Status::Error(err.to_string())),
+        Err(payload) => (None, Status::Panicked(panic_message(&*payload))),
+    };
+    DayResult { day, outcome, elapsed, status }
+}
+
+/// Solve the given days on a pool of threads, reading each day's input
+/// from path(day). Results are in the same order as days.
+pub fn run_days<F>(days: &[usize], threads: usize, path: F) -> Vec<DayResult>
+where
+    F: Fn(usize) -> String,
+{
+    let queue: VecDeque<(usize, usize, String)> =
+        days.iter().enumerate().map(|(i, &day)| (i, day, path(day))).collect();
+    let queue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+    let workers: Vec<_> = (0..threads.clamp(1, days.len().max(1)))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            thread::spawn(move || loop {
+                let job = queue.lock().unwrap().pop_front();
+                let (i, day, path) = match job {
+                    Some(job) => job,
+                    None => break,
+                };
+                sender.send((i, run_day_isolated(day, &path))).unwrap();
+            })
+        })
+        .collect();
+    drop(sender);
+    let mut results: Vec<Option<DayResult>> = vec![None; days.len()];
+    for (i, result) in receiver {
+        results[i] = Some(result);
+    }
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    results.into_iter().map(|result| result.unwrap()).collect()
+}
+
+/// Table with one row per day: the answers, time taken and status.
+pub fn summary_table(results: &[DayResult]) -> String {
+    let header = ["day", "part 1", "part 2", "time", "status"];
+    let mut rows = vec![header.iter().map(|s| s.to_string()).collect()];
+    for result in results {
+        let answer = |i: usize| {
+            let answer = result.outcome.as_ref().map(|o| &o.answers[i].answer);
+            match answer {
+                Some(Ok(answer)) => answer.clone(),
+                _ => "-".to_owned(),
+            }
+        };
+        rows.push(vec![
+            result.day.to_string(),
+            answer(0),
+            answer(1),
+            format!("{:.1} ms", result.elapsed.as_secs_f64() * 1000.0),
+            result.status.to_string(),
+        ]);
+    }
+    let widths: Vec<usize> = (0..header.len())
+        .map(|j| {
+            rows.iter().map(|row: &Vec<String>| row[j].len()).max().unwrap()
+        })
+        .collect();
+    let mut table = String::new();
+    for row in &rows {
+        // Right-align the day and time, left-align everything else
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(j, (cell, &width))| match j {
+                0 | 3 => format!("{:>width$}", cell, width = width),
+                _ => format!("{:<width$}", cell, width = width),
+            })
+            .collect();
+        table += cells.join("  ").trim_end();
+        table += "\n";
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_days() {
+        let results = run_days(&[6, 1, 5], 2, |day| match day {
+            5 => "input/does_not_exist.txt".to_owned(),
+            day => crate::solution::input_path(day),
+        });
+        let days: Vec<usize> = results.iter().map(|r| r.day).collect();
+        assert_eq!(days, vec![6, 1, 5]);
+        assert!(results[0].status.is_ok() && results[1].status.is_ok());
+        assert!(matches!(results[2].status, Status::Error(_)));
+        let answers = results[1].outcome.as_ref().unwrap().answer_strings();
+        assert_eq!(answers, vec!["805731", "192684960"]);
+        let table = summary_table(&results);
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.lines().nth(2).unwrap().contains("805731"));
+    }
+
+    #[test]
+    fn test_panic_isolation() {
+        // solve_day panics for a day with no solution
+        let result = run_day_isolated(26, "input/day01.txt");
+        assert!(matches!(result.status, Status::Panicked(_)));
+        assert!(result.status.to_string().contains("No solution for day 26"));
+    }
+}
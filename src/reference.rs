@@ -0,0 +1,244 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Reference solutions
+
+    Naive, obviously-correct implementations of some of the cleverer
+    solutions, to test them against (see tests/differential.rs). These are
+    exponential or otherwise slow, so only usable on small inputs:
+
+    - Day 8: flip each instruction in turn and rerun the program.
+    - Day 10: enumerate every subset of adapters.
+    - Day 13: scan timestamps one at a time.
+    - Day 18: parse each expression into an explicit syntax tree.
+    - Day 23: simulate the cups in a VecDeque, moving them around.
+*/
+
+use crate::day18::BinOp;
+use crate::vm::{Instruction, State, Status};
+
+use std::collections::VecDeque;
+
+/*
+    Day 8
+*/
+
+/// Instructions (jmp or nop) which make the program terminate normally
+/// if flipped, by running every flipped program.
+pub fn corrupted_instructions(program: &[Instruction]) -> Vec<usize> {
+    (0..program.len())
+        .filter(|&i| {
+            let flipped = match program[i].flipped() {
+                Some(flipped) => flipped,
+                None => return false,
+            };
+            let mut prog_fixed = program.to_vec();
+            prog_fixed[i] = flipped;
+            let mut st = State::new(prog_fixed);
+            st.execute();
+            st.status() == Status::HaltBottom
+        })
+        .collect()
+}
+
+/*
+    Day 10
+*/
+
+/// Number of arrangements of adapters, by trying every subset.
+/// Takes the sorted joltages including the outlet and device (as in
+/// day10::preprocess_joltages).
+pub fn adapter_arrangements(joltages: &[usize]) -> usize {
+    let (first, last) = (joltages[0], joltages[joltages.len() - 1]);
+    let middle = &joltages[1..joltages.len() - 1];
+    assert!(middle.len() < 24, "too many adapters to try every subset");
+    (0..(1usize << middle.len()))
+        .filter(|subset| {
+            let chain = middle
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| subset & (1 << i) != 0)
+                .map(|(_, &jolts)| jolts);
+            let mut prev = first;
+            for jolts in chain.chain(Some(last)) {
+                if jolts - prev > 3 {
+                    return false;
+                }
+                prev = jolts;
+            }
+            true
+        })
+        .count()
+}
+
+/*
+    Day 13
+*/
+
+/// Earliest bus at or after target, times the wait for it (ties go to the
+/// first bus in the list).
+pub fn earliest_bus(target: usize, buses: &[Option<usize>]) -> usize {
+    (target..)
+        .find_map(|time| {
+            buses
+                .iter()
+                .flatten()
+                .find(|&&bus| time % bus == 0)
+                .map(|bus| bus * (time - target))
+        })
+        .unwrap()
+}
+
+/// Earliest timestamp where each bus departs at its offset in the list.
+pub fn earliest_timestamp(buses: &[Option<usize>]) -> usize {
+    (0..)
+        .find(|time| {
+            buses.iter().enumerate().all(|(i, bus)| match bus {
+                Some(bus) => (time + i) % bus == 0,
+                None => true,
+            })
+        })
+        .unwrap()
+}
+
+/*
+    Day 18
+*/
+
+/// Syntax tree for an expression.
+#[derive(Debug)]
+pub enum Ast {
+    Num(usize),
+    Op(Box<Ast>, BinOp, Box<Ast>),
+}
+impl Ast {
+    pub fn eval(&self) -> usize {
+        match self {
+            Ast::Num(n) => *n,
+            Ast::Op(left, BinOp::Plus, right) => left.eval() + right.eval(),
+            Ast::Op(left, BinOp::Times, right) => left.eval() * right.eval(),
+            Ast::Op(_, op, _) => panic!("unsupported operation: {}", op),
+        }
+    }
+}
+
+// Recursive descent parser over the characters of an expression, where
+// precedence(op) gives the binding strength of each operator
+struct AstParser<'a, F> {
+    chars: Vec<char>,
+    pos: usize,
+    precedence: &'a F,
+}
+impl<'a, F: Fn(BinOp) -> usize> AstParser<'a, F> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn next(&mut self) -> char {
+        self.pos += 1;
+        self.chars[self.pos - 1]
+    }
+    fn op(&self) -> Option<BinOp> {
+        match self.peek() {
+            Some('+') => Some(BinOp::Plus),
+            Some('*') => Some(BinOp::Times),
+            _ => None,
+        }
+    }
+    fn atom(&mut self) -> Ast {
+        match self.next() {
+            '(' => {
+                let inner = self.expr(0);
+                assert_eq!(self.next(), ')');
+                inner
+            }
+            ch => Ast::Num(ch.to_digit(10).unwrap() as usize),
+        }
+    }
+    // Operators of at least the given precedence, left associative
+    fn expr(&mut self, min_prec: usize) -> Ast {
+        let mut left = self.atom();
+        while let Some(op) = self.op() {
+            let prec = (self.precedence)(op);
+            if prec < min_prec {
+                break;
+            }
+            self.next();
+            let right = self.expr(prec + 1);
+            left = Ast::Op(Box::new(left), op, Box::new(right));
+        }
+        left
+    }
+}
+
+/// Parse a well-formed expression into a syntax tree.
+pub fn parse_ast<F: Fn(BinOp) -> usize>(raw: &str, precedence: &F) -> Ast {
+    let chars = raw.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let mut parser = AstParser { chars, pos: 0, precedence };
+    let ast = parser.expr(0);
+    assert_eq!(parser.peek(), None);
+    ast
+}
+
+/// Evaluate with + and * at the same precedence.
+pub fn eval_part1(raw: &str) -> usize {
+    parse_ast(raw, &|_op| 0).eval()
+}
+
+/// Evaluate with + at a higher precedence than *.
+pub fn eval_part2(raw: &str) -> usize {
+    let precedence = |op| match op {
+        BinOp::Plus => 1,
+        _ => 0,
+    };
+    parse_ast(raw, &precedence).eval()
+}
+
+/*
+    Day 23
+*/
+
+/// Play the given number of moves, returning the cup labels clockwise
+/// starting from the current cup.
+pub fn play_cups(labels: &[usize], moves: usize) -> Vec<usize> {
+    let max = *labels.iter().max().unwrap();
+    // The current cup is always at the front
+    let mut cups: VecDeque<usize> = labels.iter().copied().collect();
+    for _ in 0..moves {
+        let current = cups.pop_front().unwrap();
+        let picked: Vec<usize> = cups.drain(..3).collect();
+        let mut dest = current;
+        loop {
+            dest = if dest == 1 { max } else { dest - 1 };
+            if !picked.contains(&dest) {
+                break;
+            }
+        }
+        let at = cups.iter().position(|&cup| cup == dest).unwrap() + 1;
+        for (i, &cup) in picked.iter().enumerate() {
+            cups.insert(at + i, cup);
+        }
+        cups.push_back(current);
+    }
+    cups.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        let joltages = [0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22];
+        assert_eq!(adapter_arrangements(&joltages), 8);
+        let buses = [Some(7), Some(13), None, None, Some(59), None, Some(31)];
+        assert_eq!(earliest_bus(939, &buses), 295);
+        assert_eq!(
+            earliest_timestamp(&[Some(17), None, Some(13), Some(19)]),
+            3417
+        );
+        assert_eq!(eval_part1("2 * 3 + (4 * 5)"), 26);
+        assert_eq!(eval_part2("2 * 3 + (4 * 5)"), 46);
+        let cups = play_cups(&[3, 8, 9, 1, 2, 5, 4, 6, 7], 10);
+        assert_eq!(cups, vec![8, 3, 7, 4, 1, 9, 2, 6, 5]);
+    }
+}
@@ -0,0 +1,343 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Solution trait and dispatch to each day
+*/
+
+use crate::util::{file_to_vec, ParseResult};
+use crate::*;
+
+use serde::Serialize;
+use std::cell::RefCell;
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/*
+    Each day implements Solution: the input lines are parsed once, and then
+    both parts are computed from the parsed input.
+
+    Answers are returned as strings, since they are not all numbers
+    (e.g. day 21 part 2 is a list of ingredients). Parsing checks as much
+    as it can about the input, but a part can still fail on input that
+    parses (e.g. if it has no answer), and returns a SolveError instead.
+*/
+/// Solution to one day's puzzle.
+pub trait Solution {
+    /// Parsed puzzle input
+    type Input;
+    /// Parse the input lines, or report where the input is malformed
+    fn parse(lines: &[String]) -> ParseResult<Self::Input>;
+    /// Answer to part 1
+    fn part1(input: &Self::Input) -> SolveResult<String>;
+    /// Answer to part 2
+    fn part2(input: &Self::Input) -> SolveResult<String>;
+}
+
+/// Why a part has no answer for its (parsed) input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolveError {
+    pub cause: String,
+}
+pub type SolveResult<T> = Result<T, SolveError>;
+
+impl SolveError {
+    pub fn new<D: Display>(cause: D) -> Self {
+        Self { cause: cause.to_string() }
+    }
+}
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cause)
+    }
+}
+impl Error for SolveError {}
+impl From<String> for SolveError {
+    fn from(cause: String) -> Self {
+        Self::new(cause)
+    }
+}
+impl From<&str> for SolveError {
+    fn from(cause: &str) -> Self {
+        Self::new(cause)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+pub const BOTH_PARTS: &[Part] = &[Part::One, Part::Two];
+impl FromStr for Part {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Not a valid part (should be 1 or 2): {}", s)),
+        }
+    }
+}
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/*
+    Diagnostics
+
+    Besides its answer, a part can record short diagnostic messages about
+    how it got there (e.g. how many steps a simulation took). These are
+    attached to the part's answer by run, rather than printed, so that they
+    can go to stderr or into a structured record. Diagnostics recorded
+    outside of computing a part (e.g. while parsing) are discarded.
+*/
+thread_local! {
+    static DIAGNOSTICS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+/// Record a diagnostic message for the part currently being computed.
+pub fn diagnostic<T: fmt::Display>(msg: T) {
+    DIAGNOSTICS.with(|diags| diags.borrow_mut().push(msg.to_string()));
+}
+fn take_diagnostics() -> Vec<String> {
+    DIAGNOSTICS.with(|diags| diags.borrow_mut().drain(..).collect())
+}
+
+/*
+    Type-erased dispatch
+
+    A Runner parses the input for one day and computes the requested parts,
+    returning the answers in the same order as the parts given, along with
+    how long parsing and each part took.
+*/
+pub const DAYS: usize = 25;
+pub type Runner = fn(&[String], &[Part]) -> ParseResult<Outcome>;
+
+/// Answer to one part (or why it has none), the time taken to compute it
+/// (excluding parsing), and any diagnostics recorded along the way.
+#[derive(Clone, Debug)]
+pub struct PartAnswer {
+    pub part: Part,
+    pub answer: SolveResult<String>,
+    pub elapsed: Duration,
+    pub diagnostics: Vec<String>,
+}
+impl PartAnswer {
+    pub fn is_ok(&self) -> bool {
+        self.answer.is_ok()
+    }
+    /// The answer, or the error in its place.
+    pub fn answer_string(&self) -> String {
+        match &self.answer {
+            Ok(answer) => answer.clone(),
+            Err(err) => format!("error: {}", err),
+        }
+    }
+    pub fn record(&self, day: usize) -> Record {
+        Record {
+            day,
+            part: match self.part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            answer: self.answer.as_ref().ok().cloned(),
+            error: self.answer.as_ref().err().map(|err| err.to_string()),
+            elapsed_ms: self.elapsed.as_secs_f64() * 1000.0,
+            diagnostics: self.diagnostics.clone(),
+        }
+    }
+}
+
+/// Machine-readable form of one part's answer, e.g. (as JSON):
+/// {"day":11,"part":1,"answer":"2470","elapsed_ms":21.9,
+///  "diagnostics":["reached stable after 88 steps"]}
+/// A part with no answer has an error instead, e.g.
+/// {"day":1,"part":2,"error":"no three numbers sum to 2020",...}
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record {
+    pub day: usize,
+    pub part: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub elapsed_ms: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+/// Result of a Runner: the time taken to parse, and the answers.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub parse_time: Duration,
+    pub answers: Vec<PartAnswer>,
+}
+impl Outcome {
+    pub fn answer_strings(&self) -> Vec<String> {
+        self.answers.iter().map(PartAnswer::answer_string).collect()
+    }
+    /// The first part with no answer, and why, if any.
+    pub fn first_error(&self) -> Option<(Part, &SolveError)> {
+        self.answers.iter().find_map(|answer| {
+            Some((answer.part, answer.answer.as_ref().err()?))
+        })
+    }
+}
+
+/// Parse the input and compute the given parts for a Solution.
+pub fn run<S: Solution>(
+    lines: &[String],
+    parts: &[Part],
+) -> ParseResult<Outcome> {
+    let start = Instant::now();
+    let input = S::parse(lines)?;
+    let parse_time = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            take_diagnostics();
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&input),
+                Part::Two => S::part2(&input),
+            };
+            let elapsed = start.elapsed();
+            let diagnostics = take_diagnostics();
+            PartAnswer { part, answer, elapsed, diagnostics }
+        })
+        .collect();
+    Ok(Outcome { parse_time, answers })
+}
+
+/// Runner for the given day (1 to 25), if any.
+pub fn runner(day: usize) -> Option<Runner> {
+    match day {
+        1 => Some(run::<day01::Day01>),
+        2 => Some(run::<day02::Day02>),
+        3 => Some(run::<day03::Day03>),
+        4 => Some(run::<day04::Day04>),
+        5 => Some(run::<day05::Day05>),
+        6 => Some(run::<day06::Day06>),
+        7 => Some(run::<day07::Day07>),
+        8 => Some(run::<day08::Day08>),
+        9 => Some(run::<day09::Day09>),
+        10 => Some(run::<day10::Day10>),
+        11 => Some(run::<day11::Day11>),
+        12 => Some(run::<day12::Day12>),
+        13 => Some(run::<day13::Day13>),
+        14 => Some(run::<day14::Day14>),
+        15 => Some(run::<day15::Day15>),
+        16 => Some(run::<day16::Day16>),
+        17 => Some(run::<day17::Day17>),
+        18 => Some(run::<day18::Day18>),
+        19 => Some(run::<day19::Day19>),
+        20 => Some(run::<day20::Day20>),
+        21 => Some(run::<day21::Day21>),
+        22 => Some(run::<day22::Day22>),
+        23 => Some(run::<day23::Day23>),
+        24 => Some(run::<day24::Day24>),
+        25 => Some(run::<day25::Day25>),
+        _ => None,
+    }
+}
+
+// Default location of the puzzle input for each day
+pub fn input_path(day: usize) -> String {
+    format!("input/day{:02}.txt", day)
+}
+
+// Run a day on its input file (or stdin, for "-")
+pub fn solve_day(
+    day: usize,
+    path: &str,
+    parts: &[Part],
+) -> ParseResult<Outcome> {
+    let runner = runner(day).unwrap_or_else(|| {
+        panic!("No solution for day {} (1 to {})", day, DAYS)
+    });
+    runner(&file_to_vec(path)?, parts).map_err(|err| err.in_file(path))
+}
+
+/// Print an answer for people: diagnostics to stderr, the answer to stdout
+/// (or the error, if there is no answer, to stderr).
+pub fn print_plain(answer: &PartAnswer) {
+    for diag in &answer.diagnostics {
+        eprintln!("[{}]", diag);
+    }
+    match &answer.answer {
+        Ok(ans) => println!("Part {} Answer: {}", answer.part, ans),
+        Err(err) => eprintln!("Part {} Error: {}", answer.part, err),
+    }
+}
+
+/*
+    Entrypoint for the individual dayNN binaries.
+    An input path can optionally be given as the first argument
+    ("-" to read from stdin), and -v or -vv enable debug output.
+*/
+pub fn day_main(day: usize) {
+    let mut verbosity = 0;
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        match trace::verbosity_flag(&arg) {
+            Some(level) => verbosity += level,
+            None => path = path.or(Some(arg)),
+        }
+    }
+    trace::set_verbosity(verbosity);
+    let path = path.unwrap_or_else(|| input_path(day));
+    let outcome = solve_day(day, &path, BOTH_PARTS).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    });
+    outcome.answers.iter().for_each(print_plain);
+    if outcome.first_error().is_some() {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    #[test]
+    fn test_run_records() {
+        let lines = str_to_vec("939\n7,13,x,x,59,x,31,19");
+        let outcome = run::<day13::Day13>(&lines, &[Part::One]).unwrap();
+        let record = outcome.answers[0].record(13);
+        assert_eq!((record.day, record.part), (13, 1));
+        assert_eq!(record.answer.as_deref(), Some("295"));
+        assert_eq!(record.diagnostics, vec!["best: bus 59 with delay 5"]);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.starts_with(r#"{"day":13,"part":1,"answer":"295","#));
+        // Diagnostics are not carried over to later parts
+        let outcome = run::<day13::Day13>(&lines, BOTH_PARTS).unwrap();
+        assert!(outcome.answers[1].diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_run_errors() {
+        // Buses 2 and 4 never depart a minute apart, so part 2 fails
+        let lines = str_to_vec("939\n2,4");
+        let outcome = run::<day13::Day13>(&lines, BOTH_PARTS).unwrap();
+        assert!(outcome.answers[0].is_ok());
+        let (part, err) = outcome.first_error().unwrap();
+        assert_eq!(
+            (part, err.cause.as_str()),
+            (Part::Two, "no timestamp fits every bus")
+        );
+        let record = outcome.answers[1].record(13);
+        assert_eq!(record.answer, None);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.starts_with(r#"{"day":13,"part":2,"error":"#));
+    }
+}
@@ -0,0 +1,87 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Verbosity-controlled tracing
+
+    Debug output from the solvers goes through the info! and debug! macros,
+    which print to stderr only if the verbosity is high enough:
+
+    - Level 0 (default): nothing.
+    - Level 1 (-v): summaries, e.g. game states at the start and end, cache
+      statistics, assembled puzzles.
+    - Level 2 (-vv): step-by-step traces, e.g. every instruction executed.
+
+    The verbosity is a global set once at startup by the runner, so it works
+    the same in debug and release builds. The macro arguments are not
+    evaluated unless the message is printed.
+*/
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const INFO: usize = 1;
+pub const DEBUG: usize = 2;
+
+static VERBOSITY: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_verbosity(level: usize) {
+    VERBOSITY.store(level, Ordering::Relaxed);
+}
+pub fn verbosity() -> usize {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+/// Whether messages at the given level are printed.
+pub fn enabled(level: usize) -> bool {
+    verbosity() >= level
+}
+
+/// Verbosity given by a -v (1) or -vv (2) style flag (also --verbose),
+/// or None if the argument is not one. Option values are not flags (e.g.
+/// a file named -v), so this is up to the caller, which knows which
+/// arguments are option values.
+pub fn verbosity_flag(arg: &str) -> Option<usize> {
+    match arg {
+        "--verbose" => Some(1),
+        flag if flag.len() > 1
+            && flag.starts_with('-')
+            && flag[1..].chars().all(|ch| ch == 'v') =>
+        {
+            Some(flag.len() - 1)
+        }
+        _ => None,
+    }
+}
+
+/// Print to stderr at verbosity 1 (-v) and above.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled($crate::trace::INFO) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Print to stderr at verbosity 2 (-vv) and above.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled($crate::trace::DEBUG) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_flag() {
+        assert_eq!(verbosity_flag("-v"), Some(1));
+        assert_eq!(verbosity_flag("-vv"), Some(2));
+        assert_eq!(verbosity_flag("--verbose"), Some(1));
+        assert_eq!(verbosity_flag("-"), None);
+        assert_eq!(verbosity_flag("-vx"), None);
+        assert_eq!(verbosity_flag("8"), None);
+    }
+}
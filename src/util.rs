@@ -4,35 +4,292 @@
     Utilities
 */
 
+pub mod grid;
+pub use grid::Grid;
+
 use std::collections::HashSet;
-use std::fmt::Debug;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 use std::str::FromStr;
+use std::sync::Arc;
+
+/*
+    Parse errors
+
+    ParseError records what went wrong when reading or parsing the input,
+    along with where it went wrong, as far as is known: the file path, line
+    number and column number (both 1-indexed), and the offending line.
+    Parsers for a single line create errors without a location, and the
+    location is filled in by the callers that know it (parse_lines_with,
+    file_to_vec, etc.)
+    When the error comes from another error (the FromStr::Err of a value,
+    or an I/O error), that error is kept as the source.
+*/
+
+/// Any error which can be the source of a ParseError.
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+#[derive(Clone, Debug, Default)]
+pub struct ParseError {
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+    pub text: Option<String>,
+    pub cause: String,
+    pub source: Option<Arc<dyn Error + Send + Sync>>,
+}
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl ParseError {
+    pub fn new<D: Display>(cause: D) -> Self {
+        Self { cause: cause.to_string(), ..Default::default() }
+    }
+    pub fn with_source<E: Into<BoxError>>(mut self, source: E) -> Self {
+        self.source = Some(Arc::from(source.into()));
+        self
+    }
+    // Set the location, unless it was already set more precisely
+    pub fn at_line(mut self, line: usize, text: &str) -> Self {
+        if self.line.is_none() {
+            self.line = Some(line);
+            self.text = Some(text.to_owned());
+        }
+        self
+    }
+    // Shift the line number, for errors in part of the input
+    pub fn offset_lines(mut self, offset: usize) -> Self {
+        self.line = self.line.map(|line| line + offset);
+        self
+    }
+    // Shift the column number, for errors in part of a line
+    pub fn offset_cols(mut self, offset: usize) -> Self {
+        self.col = self.col.map(|col| col + offset);
+        self
+    }
+    pub fn at_col(mut self, col: usize) -> Self {
+        if self.col.is_none() {
+            self.col = Some(col);
+        }
+        self
+    }
+    pub fn in_file(mut self, path: &str) -> Self {
+        if self.path.is_none() && path == STDIN_PATH {
+            self.path = Some("<stdin>".to_owned());
+        } else if self.path.is_none() {
+            self.path = Some(path.to_owned());
+        }
+        self
+    }
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut location = Vec::new();
+        location.extend(self.path.clone());
+        location.extend(self.line.map(|l| l.to_string()));
+        location.extend(self.col.map(|c| c.to_string()));
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(":"))?;
+        }
+        write!(f, "{}", self.cause)?;
+        if let Some(text) = &self.text {
+            write!(f, "\n    {}", text)?;
+            if let Some(col) = self.col {
+                write!(f, "\n    {:>width$}", "^", width = col)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|err| err as &(dyn Error + 'static))
+    }
+}
+// Sources are compared by their messages
+impl PartialEq for ParseError {
+    fn eq(&self, other: &Self) -> bool {
+        let source = |err: &Self| err.source.as_ref().map(|s| s.to_string());
+        (&self.path, self.line, self.col, &self.text, &self.cause)
+            == (&other.path, other.line, other.col, &other.text, &other.cause)
+            && source(self) == source(other)
+    }
+}
+impl Eq for ParseError {}
+impl From<String> for ParseError {
+    fn from(cause: String) -> Self {
+        Self::new(cause)
+    }
+}
+impl From<&str> for ParseError {
+    fn from(cause: &str) -> Self {
+        Self::new(cause)
+    }
+}
 
 /* Parsing */
 
-// Convert a file to a vector of its lines
-pub fn file_to_vec_parsed<T>(filepath: &str) -> Vec<T>
+// Parse a single string with FromStr
+pub fn parse_str<T>(raw: &str) -> ParseResult<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<BoxError>,
+{
+    raw.parse::<T>().map_err(|err| {
+        let err: BoxError = err.into();
+        ParseError::new(format!("could not parse {:?}: {}", raw, err))
+            .with_source(err)
+    })
+}
+
+// Parse each line with a custom function, recording the line number
+// (and text) of the first error
+pub fn parse_lines_with<T, E, F>(
+    lines: &[String],
+    mut f: F,
+) -> ParseResult<Vec<T>>
+where
+    E: Into<ParseError>,
+    F: FnMut(&str) -> Result<T, E>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|err| err.into().at_line(i + 1, line)))
+        .collect()
+}
+
+// Parse each line with FromStr
+pub fn parse_lines<T>(lines: &[String]) -> ParseResult<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<BoxError>,
+{
+    parse_lines_with(lines, parse_str)
+}
+
+/*
+    Reading input
+
+    Input can come from a file, from stdin (given as the path "-"), from any
+    other buffered reader, or directly from a string (e.g. examples in tests).
+    Each source has the same two variants: lines, and parsed lines.
+*/
+
+pub const STDIN_PATH: &str = "-";
+
+// Convert a buffered reader to a vector of its lines
+pub fn reader_to_vec<R: BufRead>(reader: R) -> ParseResult<Vec<String>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.map_err(|err| ParseError {
+                line: Some(i + 1),
+                ..ParseError::new(&err).with_source(err)
+            })
+        })
+        .collect()
+}
+
+// Convert a string to a vector of its lines
+pub fn str_to_vec(raw: &str) -> Vec<String> {
+    raw.lines().map(|line| line.to_owned()).collect()
+}
+
+// Convert a file (or stdin, for "-") to a vector of its lines
+pub fn file_to_vec(filepath: &str) -> ParseResult<Vec<String>> {
+    let lines = if filepath == STDIN_PATH {
+        reader_to_vec(io::stdin().lock())
+    } else {
+        File::open(filepath)
+            .map_err(|err| ParseError::new(&err).with_source(err))
+            .and_then(|file| reader_to_vec(BufReader::new(file)))
+    };
+    lines.map_err(|err| err.in_file(filepath))
+}
+
+// Parsed versions
+pub fn reader_to_vec_parsed<T, R>(reader: R) -> ParseResult<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<BoxError>,
+    R: BufRead,
+{
+    parse_lines(&reader_to_vec(reader)?)
+}
+pub fn str_to_vec_parsed<T>(raw: &str) -> ParseResult<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<BoxError>,
+{
+    parse_lines(&str_to_vec(raw))
+}
+pub fn file_to_vec_parsed<T>(filepath: &str) -> ParseResult<Vec<T>>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Into<BoxError>,
 {
-    let file = File::open(filepath).unwrap();
-    let reader = BufReader::new(file);
-    reader.lines().map(|line| line.unwrap().parse().unwrap()).collect()
+    parse_lines(&file_to_vec(filepath)?).map_err(|err| err.in_file(filepath))
+}
+
+/*
+    Groups of lines
+
+    Many inputs consist of records (groups of lines) separated by blank
+    lines. line_groups iterates over the groups, each along with the index of
+    its first line, so that errors can be reported at the right line.
+    Repeated and trailing blank lines do not produce empty groups.
+*/
+
+pub struct LineGroups<'a> {
+    lines: &'a [String],
+    pos: usize,
+}
+impl<'a> Iterator for LineGroups<'a> {
+    type Item = (usize, &'a [String]);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.lines.get(self.pos)?.is_empty() {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        while self.lines.get(self.pos).is_some_and(|line| !line.is_empty()) {
+            self.pos += 1;
+        }
+        Some((start, &self.lines[start..self.pos]))
+    }
+}
+pub fn line_groups(lines: &[String]) -> LineGroups<'_> {
+    LineGroups { lines, pos: 0 }
 }
 
-// Simple string version
-pub fn file_to_vec(filepath: &str) -> Vec<String> {
-    file_to_vec_parsed(filepath)
+// Parse each group with a custom function. Line numbers in errors are
+// relative to the group, and are converted to line numbers in the input.
+pub fn parse_groups_with<T, E, F>(
+    lines: &[String],
+    mut f: F,
+) -> ParseResult<Vec<T>>
+where
+    E: Into<ParseError>,
+    F: FnMut(&[String]) -> Result<T, E>,
+{
+    line_groups(lines)
+        .map(|(start, group)| {
+            f(group).map_err(|err| {
+                err.into().offset_lines(start).at_line(start + 1, &group[0])
+            })
+        })
+        .collect()
 }
 
-// Version that is terminated with an empty line ("")
-pub fn file_to_vec_el(filepath: &str) -> Vec<String> {
-    let mut v = file_to_vec(filepath);
-    v.push("".to_owned());
-    v
+// Parse each group with FromStr, as a single string including newlines
+pub fn parse_groups<T>(lines: &[String]) -> ParseResult<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<BoxError>,
+{
+    parse_groups_with(lines, |group| parse_str(&group.join("\n")))
 }
 
 // Separate a line into whitespace-divided parts
@@ -41,15 +298,19 @@ pub fn line_to_words(line: &str) -> Vec<String> {
 }
 
 // Parse an iterator (e.g. result of split) of length 2 into a tuple
-pub fn iter_to_pair<T, I>(mut elems: I) -> (T, T)
+pub fn iter_to_pair<T, I>(mut elems: I) -> ParseResult<(T, T)>
 where
     I: Iterator<Item = T>,
-    T: Debug + PartialEq,
+    T: Debug,
 {
-    let elem1 = elems.next().unwrap();
-    let elem2 = elems.next().unwrap();
-    assert_eq!(elems.next(), None);
-    (elem1, elem2)
+    match (elems.next(), elems.next(), elems.next()) {
+        (Some(elem1), Some(elem2), None) => Ok((elem1, elem2)),
+        (elem1, elem2, elem3) => Err(ParseError::new(format!(
+            "expected exactly 2 parts, found {}",
+            elem1.iter().chain(elem2.iter()).chain(elem3.iter()).count()
+                + elems.count(),
+        ))),
+    }
 }
 
 /* Useful iterators */
@@ -142,4 +403,59 @@ mod tests {
         assert!(!unique_1_to_n([1, 2, 4, 4, 5].iter()));
         assert!(!unique_1_to_n([1, 2, 3, 4, 6].iter()));
     }
+    #[test]
+    fn test_parse_lines_error() {
+        let lines: Vec<String> =
+            ["12", "34", "5x6"].iter().map(|s| s.to_string()).collect();
+        let err = parse_lines::<usize>(&lines).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.text.as_deref(), Some("5x6"));
+        assert!(err.cause.contains("invalid digit"));
+        let source = Error::source(&err).unwrap();
+        assert!(source.downcast_ref::<std::num::ParseIntError>().is_some());
+        let err = err.in_file("input.txt");
+        assert!(err.to_string().starts_with("input.txt:3: "));
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = ParseError::new("bad char").at_col(3).at_line(2, "ab?d");
+        assert_eq!(err.to_string(), "2:3: bad char\n    ab?d\n      ^");
+        assert_eq!(ParseError::new("oops").to_string(), "oops");
+    }
+
+    #[test]
+    fn test_reader_and_str_to_vec() {
+        let raw = "1\n2\n\n3\n";
+        let expected = vec!["1", "2", "", "3"];
+        assert_eq!(str_to_vec(raw), expected);
+        assert_eq!(reader_to_vec(raw.as_bytes()).unwrap(), expected);
+        let nums: Vec<usize> = reader_to_vec_parsed(&b"4\n5"[..]).unwrap();
+        assert_eq!(nums, vec![4, 5]);
+        assert!(str_to_vec_parsed::<usize>("4\n\n5").is_err());
+    }
+
+    #[test]
+    fn test_line_groups() {
+        let lines = str_to_vec("\na\nb\n\n\nc\n\n");
+        let groups: Vec<_> = line_groups(&lines).collect();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], (1, &lines[1..3]));
+        assert_eq!(groups[1], (5, &lines[5..6]));
+        assert_eq!(line_groups(&str_to_vec("\n\n")).count(), 0);
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let lines = str_to_vec("1\n2\n\n3");
+        let parsed = parse_groups_with(&lines, |group| {
+            parse_lines::<usize>(group).map(|nums| nums.iter().sum::<usize>())
+        });
+        assert_eq!(parsed.unwrap(), vec![3, 3]);
+        let err = parse_groups::<usize>(&lines).unwrap_err();
+        assert_eq!(err.line, Some(1));
+        let lines = str_to_vec("1\n\n2\nx");
+        let err = parse_groups_with(&lines, parse_lines::<usize>).unwrap_err();
+        assert_eq!((err.line, err.text.as_deref()), (Some(4), Some("x")));
+    }
 }
@@ -0,0 +1,300 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Generic 2D grid
+
+    Grid<T> is a rectangular grid of cells, stored in row-major order.
+    Positions are (row, col) pairs, and directions are (drow, dcol) offsets.
+    It supports:
+
+    - Parsing from lines of characters, one cell per character.
+
+    - Access: bounds-checked (get), with signed coordinates (get_signed),
+      with the columns repeating to the right (get_wrapping), or by
+      indexing (which panics out of bounds).
+
+    - Neighborhoods: the 4 orthogonal or all 8 surrounding positions.
+
+    - Ray casting: all positions from a cell in a given direction, up to the
+      edge of the grid.
+
+    - Transformations: rotation and reflection (returning a new grid).
+*/
+
+use super::{ParseError, ParseResult};
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+pub type Pos = (usize, usize);
+pub type Dir = (isize, isize);
+
+pub const DIRS_4: &[Dir] = &[(-1, 0), (0, 1), (1, 0), (0, -1)];
+pub const DIRS_8: &[Dir] =
+    &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /* Constructors */
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { rows, cols, cells: vec![fill; rows * cols] }
+    }
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(Pos) -> T,
+    {
+        let mut cells = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                cells.push(f((i, j)));
+            }
+        }
+        Self { rows, cols, cells }
+    }
+    /// Grid from a vector of rows, which should all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> ParseResult<Self> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        let mut cells = Vec::with_capacity(rows.len() * cols);
+        let num_rows = rows.len();
+        for row in rows {
+            if row.len() != cols {
+                return Err(ParseError::new(format!(
+                    "expected row of length {}, found {}",
+                    cols,
+                    row.len()
+                )));
+            }
+            cells.extend(row);
+        }
+        Ok(Self { rows: num_rows, cols, cells })
+    }
+    /// Parse lines of characters, converting each character with f
+    /// (None if the character is invalid).
+    pub fn parse_lines_with<F>(lines: &[String], mut f: F) -> ParseResult<Self>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        if lines.is_empty() {
+            return Err(ParseError::new("empty grid"));
+        }
+        let cols = lines[0].chars().count();
+        if cols == 0 {
+            return Err(ParseError::new("empty grid row").at_line(1, &lines[0]));
+        }
+        let mut cells = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let mut len = 0;
+            for (j, ch) in line.chars().enumerate() {
+                let cell = f(ch).ok_or_else(|| {
+                    ParseError::new(format!("invalid grid cell: {:?}", ch))
+                        .at_line(i + 1, line)
+                        .at_col(j + 1)
+                })?;
+                cells.push(cell);
+                len += 1;
+            }
+            if len != cols {
+                let msg =
+                    format!("expected row of length {}, found {}", cols, len);
+                return Err(ParseError::new(msg).at_line(i + 1, line));
+            }
+        }
+        Ok(Self { rows: lines.len(), cols, cells })
+    }
+
+    /* Dimensions and access */
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    pub fn in_bounds(&self, (i, j): Pos) -> bool {
+        i < self.rows && j < self.cols
+    }
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.in_bounds(pos) {
+            Some(&self.cells[pos.0 * self.cols + pos.1])
+        } else {
+            None
+        }
+    }
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.0 * self.cols + pos.1])
+        } else {
+            None
+        }
+    }
+    pub fn get_signed(&self, i: isize, j: isize) -> Option<&T> {
+        if i < 0 || j < 0 {
+            None
+        } else {
+            self.get((i as usize, j as usize))
+        }
+    }
+    /// Get a cell, treating the columns as repeating to the right.
+    pub fn get_wrapping(&self, (i, j): Pos) -> Option<&T> {
+        if self.cols == 0 {
+            None
+        } else {
+            self.get((i, j % self.cols))
+        }
+    }
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[(i * self.cols)..((i + 1) * self.cols)]
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(move |i| self.row(i))
+    }
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |i| (0..cols).map(move |j| (i, j)))
+    }
+    pub fn count<F: FnMut(&T) -> bool>(&self, mut pred: F) -> usize {
+        self.cells.iter().filter(|cell| pred(cell)).count()
+    }
+
+    /* Neighbors and rays */
+    /// Position one step in a direction, if in bounds.
+    pub fn step(&self, (i, j): Pos, (di, dj): Dir) -> Option<Pos> {
+        let pos = ((i as isize + di) as usize, (j as isize + dj) as usize);
+        // Negative positions wrap around to large values, so are out of bounds
+        if self.in_bounds(pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+    pub fn neighbors_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS_4.iter().filter_map(move |&dir| self.step(pos, dir))
+    }
+    pub fn neighbors_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS_8.iter().filter_map(move |&dir| self.step(pos, dir))
+    }
+    /// Positions from pos in direction dir (excluding pos itself),
+    /// up to the edge of the grid.
+    pub fn ray(&self, pos: Pos, dir: Dir) -> impl Iterator<Item = Pos> + '_ {
+        let mut curr = pos;
+        std::iter::from_fn(move || {
+            curr = self.step(curr, dir)?;
+            Some(curr)
+        })
+    }
+
+    /* Transformations */
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        let cells = self.cells.iter().map(f).collect();
+        Grid { rows: self.rows, cols: self.cols, cells }
+    }
+    /// Rotate clockwise by 90 degrees.
+    pub fn rotate(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |(i, j)| {
+            self[(self.rows - j - 1, i)].clone()
+        })
+    }
+    /// Reflect along the main diagonal (transpose).
+    pub fn reflect(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |(i, j)| self[(j, i)].clone())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| {
+            panic!("{:?} out of bounds ({} x {})", pos, self.rows, self.cols)
+        })
+    }
+}
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(pos).unwrap_or_else(|| {
+            panic!("{:?} out of bounds ({} x {})", pos, rows, cols)
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.iter_rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    fn example() -> Grid<char> {
+        Grid::parse_lines_with(&str_to_vec("abc\ndef"), Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_access() {
+        let grid = example();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_wrapping((0, 4)), Some(&'b'));
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        let err = Grid::<char>::parse_lines_with(&str_to_vec("ab\nc"), Some)
+            .unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let digits =
+            Grid::parse_lines_with(&str_to_vec("12\n3x"), |ch| ch.to_digit(10));
+        assert_eq!((digits.unwrap_err().col), Some(2));
+        let err = Grid::<char>::parse_lines_with(&str_to_vec("\n\n"), Some)
+            .unwrap_err();
+        assert_eq!(err.line, Some(1));
+    }
+
+    #[test]
+    fn test_neighbors_and_rays() {
+        let grid = example();
+        assert_eq!(grid.neighbors_4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbors_8((0, 1)).count(), 5);
+        let ray: Vec<Pos> = grid.ray((0, 0), (0, 1)).collect();
+        assert_eq!(ray, vec![(0, 1), (0, 2)]);
+        assert_eq!(grid.ray((1, 1), (1, 1)).count(), 0);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = example();
+        assert_eq!(grid.rotate().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.reflect().to_string(), "ad\nbe\ncf\n");
+        let mut rotated = grid.clone();
+        for _ in 0..4 {
+            rotated = rotated.rotate();
+        }
+        assert_eq!(rotated, grid);
+        assert_eq!(grid.map(|&ch| ch == 'e').count(|&b| b), 1);
+    }
+}
@@ -0,0 +1,144 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Verification against expected answers
+
+    The expected answers for each day's input are recorded in answers.toml:
+
+        [day01]
+        part1 = "805731"
+        part2 = "192684960"
+
+    Days can be missing (not yet solved); parts are compared as strings.
+*/
+
+use crate::solution::{solve_day, Part, BOTH_PARTS};
+use crate::util::ParseResult;
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+/// Expected answers to both parts of one day.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Expected {
+    pub part1: String,
+    pub part2: String,
+}
+impl Expected {
+    pub fn get(&self, part: Part) -> &str {
+        match part {
+            Part::One => &self.part1,
+            Part::Two => &self.part2,
+        }
+    }
+}
+
+/// Contents of an answers file: expected answers by day.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    days: BTreeMap<usize, Expected>,
+}
+impl Answers {
+    pub fn parse(toml_str: &str) -> Result<Self, String> {
+        let tables: BTreeMap<String, Expected> =
+            toml::from_str(toml_str).map_err(|err| err.to_string())?;
+        let mut days = BTreeMap::new();
+        for (key, expected) in tables {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("Expected [dayNN], found [{}]", key))?;
+            days.insert(day, expected);
+        }
+        Ok(Self { days })
+    }
+    pub fn load(path: &str) -> Result<Self, String> {
+        let toml_str = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path, err))?;
+        Self::parse(&toml_str).map_err(|err| format!("{}: {}", path, err))
+    }
+    pub fn get(&self, day: usize) -> Option<&Expected> {
+        self.days.get(&day)
+    }
+}
+
+/// A part whose answer differs from the expected answer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub day: usize,
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "day {} part {}:", self.day, self.part)?;
+        writeln!(f, "    - expected: {}", self.expected)?;
+        write!(f, "    + actual:   {}", self.actual)
+    }
+}
+
+/// Compare answers (to both parts, in order) against the expected answers.
+pub fn compare(
+    day: usize,
+    expected: &Expected,
+    answers: &[String],
+) -> Vec<Mismatch> {
+    BOTH_PARTS
+        .iter()
+        .zip(answers)
+        .filter(|(&part, actual)| expected.get(part) != actual.as_str())
+        .map(|(&part, actual)| Mismatch {
+            day,
+            part,
+            expected: expected.get(part).to_owned(),
+            actual: actual.clone(),
+        })
+        .collect()
+}
+
+/// Run a day on its input file and compare both parts.
+pub fn verify_day(
+    day: usize,
+    path: &str,
+    expected: &Expected,
+) -> ParseResult<Vec<Mismatch>> {
+    let outcome = solve_day(day, path, BOTH_PARTS)?;
+    Ok(compare(day, expected, &outcome.answer_strings()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers =
+            Answers::parse("[day07]\npart1 = \"1\"\npart2 = \"a,b\"\n")
+                .unwrap();
+        let expected = answers.get(7).unwrap();
+        assert_eq!(expected.get(Part::Two), "a,b");
+        assert_eq!(answers.get(8), None);
+        assert!(
+            Answers::parse("[seven]\npart1 = \"1\"\npart2 = \"2\"").is_err()
+        );
+        assert!(Answers::parse("[day07]\npart1 = \"1\"").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let expected =
+            Expected { part1: "1".to_owned(), part2: "2".to_owned() };
+        let actual = vec!["1".to_owned(), "3".to_owned()];
+        let mismatches = compare(5, &expected, &actual);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "day 5 part 2:\n    - expected: 2\n    + actual:   3"
+        );
+    }
+}
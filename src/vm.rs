@@ -0,0 +1,668 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Handheld console virtual machine
+
+    The day 8 machine, generalized. Each instruction is one line of
+    assembly; day 8 programs only use the first three:
+
+        acc +3        add to the accumulator
+        jmp -2        jump by an offset
+        nop +0        do nothing
+        set r1 acc    set a register to an operand (register or number)
+        add r1 +2     arithmetic on a register: add, sub, mul, div, mod
+        jnz r1 -3     conditional jump: jz, jnz, jlz (< 0), jgz (> 0)
+
+    There are NUM_REGISTERS registers: acc, then r1, r2, and so on.
+    A jump target is either an offset (+3, -2) or a label, defined by
+    `name:` at the start of a line. Anything after `;` is a comment.
+
+    To add an opcode, add a variant to ArithOp or Cond along with its
+    mnemonic in ALL and its semantics in apply/holds; parsing and
+    printing go through the mnemonic table.
+
+    Instructions print back in the same syntax (with labels replaced by
+    offsets), so Disassembly of a program can be assembled again.
+*/
+
+pub mod cfg;
+pub mod debugger;
+pub mod repair;
+
+use crate::util::{ParseError, ParseResult};
+use crate::{debug, info};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/*
+    Registers and operands
+*/
+
+pub const NUM_REGISTERS: usize = 8;
+
+/// A register: 0 is the accumulator.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Register(usize);
+impl Register {
+    pub const ACC: Register = Register(0);
+    pub fn new(index: usize) -> Option<Self> {
+        if index < NUM_REGISTERS {
+            Some(Register(index))
+        } else {
+            None
+        }
+    }
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+impl FromStr for Register {
+    type Err = ParseError;
+    fn from_str(s: &str) -> ParseResult<Self> {
+        if s == "acc" {
+            return Ok(Register::ACC);
+        }
+        s.strip_prefix('r')
+            .and_then(|index| index.parse().ok())
+            .filter(|&index| index > 0)
+            .and_then(Register::new)
+            .ok_or_else(|| {
+                let max = NUM_REGISTERS - 1;
+                ParseError::new(format!(
+                    "expected a register (acc or r1 to r{}): {}",
+                    max, s
+                ))
+            })
+    }
+}
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "acc"),
+            index => write!(f, "r{}", index),
+        }
+    }
+}
+
+pub type Registers = [isize; NUM_REGISTERS];
+
+/// Argument of set and arithmetic: a register or a number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operand {
+    Reg(Register),
+    Imm(isize),
+}
+impl Operand {
+    pub fn value(self, registers: &Registers) -> isize {
+        match self {
+            Operand::Reg(reg) => registers[reg.index()],
+            Operand::Imm(n) => n,
+        }
+    }
+}
+impl FromStr for Operand {
+    type Err = ParseError;
+    fn from_str(s: &str) -> ParseResult<Self> {
+        if s.starts_with(|ch: char| {
+            ch == '+' || ch == '-' || ch.is_ascii_digit()
+        }) {
+            parse_number(s).map(Operand::Imm)
+        } else {
+            s.parse().map(Operand::Reg)
+        }
+    }
+}
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Reg(reg) => write!(f, "{}", reg),
+            Operand::Imm(n) => write!(f, "{:+}", n),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> ParseResult<isize> {
+    s.parse().map_err(|err| {
+        ParseError::new(format!("expected a number: {} ({})", s, err))
+    })
+}
+
+/*
+    Opcodes
+*/
+
+/// Arithmetic operations, which fail on overflow or division by zero.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+impl ArithOp {
+    pub const ALL: &'static [(ArithOp, &'static str)] = &[
+        (ArithOp::Add, "add"),
+        (ArithOp::Sub, "sub"),
+        (ArithOp::Mul, "mul"),
+        (ArithOp::Div, "div"),
+        (ArithOp::Mod, "mod"),
+    ];
+    pub fn apply(self, left: isize, right: isize) -> Option<isize> {
+        match self {
+            ArithOp::Add => left.checked_add(right),
+            ArithOp::Sub => left.checked_sub(right),
+            ArithOp::Mul => left.checked_mul(right),
+            ArithOp::Div => left.checked_div(right),
+            ArithOp::Mod => left.checked_rem(right),
+        }
+    }
+}
+
+/// Conditions on a register for conditional jumps.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cond {
+    Zero,
+    NonZero,
+    Negative,
+    Positive,
+}
+impl Cond {
+    pub const ALL: &'static [(Cond, &'static str)] = &[
+        (Cond::Zero, "jz"),
+        (Cond::NonZero, "jnz"),
+        (Cond::Negative, "jlz"),
+        (Cond::Positive, "jgz"),
+    ];
+    pub fn holds(self, value: isize) -> bool {
+        match self {
+            Cond::Zero => value == 0,
+            Cond::NonZero => value != 0,
+            Cond::Negative => value < 0,
+            Cond::Positive => value > 0,
+        }
+    }
+}
+
+fn mnemonic<T: Copy + PartialEq>(
+    table: &[(T, &'static str)],
+    op: T,
+) -> &'static str {
+    table.iter().find(|&&(other, _)| other == op).unwrap().1
+}
+fn from_mnemonic<T: Copy>(
+    table: &[(T, &'static str)],
+    name: &str,
+) -> Option<T> {
+    table.iter().find(|&&(_, other)| other == name).map(|&(op, _)| op)
+}
+
+/*
+    Instructions and assembly
+*/
+
+/// A single instruction of the handheld game console.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Instruction {
+    Acc(isize),
+    Jmp(isize),
+    Nop(isize),
+    Set(Register, Operand),
+    Arith(ArithOp, Register, Operand),
+    JmpIf(Cond, Register, isize),
+}
+impl Instruction {
+    /// Swap jmp and nop (the day 8 corruption), if this is either.
+    pub fn flipped(&self) -> Option<Self> {
+        match *self {
+            Instruction::Jmp(n) => Some(Instruction::Nop(n)),
+            Instruction::Nop(n) => Some(Instruction::Jmp(n)),
+            _ => None,
+        }
+    }
+    /// Whether the next instruction depends on the registers.
+    pub fn is_conditional(&self) -> bool {
+        matches!(self, Instruction::JmpIf(..))
+    }
+    // Parse with jump targets resolved by the given function
+    fn parse_with<F>(s: &str, target: F) -> ParseResult<Self>
+    where
+        F: Fn(&str) -> ParseResult<isize>,
+    {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let (op, args) = match words.split_first() {
+            Some((op, args)) => (*op, args),
+            None => return Err(ParseError::new("expected an instruction")),
+        };
+        let expect_args = |n: usize| {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(ParseError::new(format!(
+                    "{} expects {} argument(s), found {}",
+                    op,
+                    n,
+                    args.len()
+                )))
+            }
+        };
+        let ins = match op {
+            "acc" => {
+                expect_args(1)?;
+                Instruction::Acc(parse_number(args[0])?)
+            }
+            "jmp" => {
+                expect_args(1)?;
+                Instruction::Jmp(target(args[0])?)
+            }
+            "nop" => {
+                expect_args(1)?;
+                Instruction::Nop(parse_number(args[0])?)
+            }
+            "set" => {
+                expect_args(2)?;
+                Instruction::Set(args[0].parse()?, args[1].parse()?)
+            }
+            _ => {
+                expect_args(2)?;
+                if let Some(arith) = from_mnemonic(ArithOp::ALL, op) {
+                    Instruction::Arith(
+                        arith,
+                        args[0].parse()?,
+                        args[1].parse()?,
+                    )
+                } else if let Some(cond) = from_mnemonic(Cond::ALL, op) {
+                    Instruction::JmpIf(cond, args[0].parse()?, target(args[1])?)
+                } else {
+                    let msg = format!("unknown instruction: {}", op);
+                    return Err(ParseError::new(msg));
+                }
+            }
+        };
+        Ok(ins)
+    }
+}
+impl FromStr for Instruction {
+    type Err = ParseError;
+    fn from_str(s: &str) -> ParseResult<Self> {
+        Self::parse_with(s, parse_number)
+    }
+}
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Acc(n) => write!(f, "acc {:+}", n),
+            Instruction::Jmp(n) => write!(f, "jmp {:+}", n),
+            Instruction::Nop(n) => write!(f, "nop {:+}", n),
+            Instruction::Set(reg, arg) => write!(f, "set {} {}", reg, arg),
+            Instruction::Arith(op, reg, arg) => {
+                write!(f, "{} {} {}", mnemonic(ArithOp::ALL, *op), reg, arg)
+            }
+            Instruction::JmpIf(cond, reg, n) => {
+                write!(f, "{} {} {:+}", mnemonic(Cond::ALL, *cond), reg, n)
+            }
+        }
+    }
+}
+
+pub type Program = Vec<Instruction>;
+
+/// Listing of a program, one instruction per line.
+pub struct Disassembly<'a>(pub &'a [Instruction]);
+impl fmt::Display for Disassembly<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ins in self.0 {
+            writeln!(f, "{}", ins)?;
+        }
+        Ok(())
+    }
+}
+
+// Split a line into its label (if any) and instruction, without comments
+fn split_line(line: &str) -> (Option<&str>, &str) {
+    let code = line.split(';').next().unwrap().trim();
+    match code.find(':') {
+        Some(i) => (Some(code[..i].trim()), code[i + 1..].trim()),
+        None => (None, code),
+    }
+}
+
+/// Assemble a program with labels and comments.
+pub fn assemble(lines: &[String]) -> ParseResult<Program> {
+    // First pass: find the instruction index of each label
+    let mut labels: HashMap<&str, isize> = HashMap::new();
+    let mut count = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let (label, code) = split_line(line);
+        if let Some(label) = label {
+            let valid = label.starts_with(|ch: char| ch.is_alphabetic())
+                && label.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+            if !valid {
+                let msg = format!("invalid label: {:?}", label);
+                return Err(ParseError::new(msg).at_line(i + 1, line));
+            }
+            if labels.insert(label, count).is_some() {
+                let msg = format!("duplicate label: {}", label);
+                return Err(ParseError::new(msg).at_line(i + 1, line));
+            }
+        }
+        if !code.is_empty() {
+            count += 1;
+        }
+    }
+    // Second pass: parse instructions, with labels as relative offsets
+    let mut program = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let (_, code) = split_line(line);
+        if code.is_empty() {
+            continue;
+        }
+        let here = program.len() as isize;
+        let target = |arg: &str| match labels.get(arg) {
+            Some(&index) => Ok(index - here),
+            None if arg.starts_with(|ch: char| ch.is_alphabetic()) => {
+                Err(ParseError::new(format!("undefined label: {}", arg)))
+            }
+            None => parse_number(arg),
+        };
+        let ins = Instruction::parse_with(code, target)
+            .map_err(|err| err.at_line(i + 1, line))?;
+        program.push(ins);
+    }
+    Ok(program)
+}
+
+/*
+    Execution
+*/
+
+/// Execution status of a program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Running,
+    LoopDetected,
+    HaltTop,    // program counter goes before the beginning
+    HaltBottom, // program counter goes past the end
+    Fault,      // arithmetic overflow or division by zero
+}
+
+/// Status after running for a limited number of steps.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RunResult {
+    /// Running if the limit was reached first
+    pub status: Status,
+    /// Instructions executed during the run
+    pub steps: usize,
+}
+
+/*
+    Loops are detected by revisiting an earlier state. Without conditional
+    jumps, the control flow doesn't depend on the registers, so it is
+    enough to revisit an instruction (as in day 8, where the accumulator
+    keeps changing around the loop). Otherwise the state includes the
+    registers, so a loop is only reported when the machine is back exactly
+    where it was.
+
+    The seen states take memory for every step executed. Without
+    conditional jumps that is at most one per instruction, but with them
+    a program can run for a very long time without repeating a state (say,
+    counting up a register), so use run_with_limit to bound the steps, and
+    with them the memory.
+*/
+/// Program state: the program, its counter, registers, and which states
+/// have been seen so far (to detect loops).
+#[derive(Clone, Debug)]
+pub struct State {
+    prog: Program,
+    prog_counter: isize,
+    registers: Registers,
+    seen: HashSet<(isize, Registers)>,
+    track_registers: bool,
+    status: Status,
+    steps: usize,
+}
+impl State {
+    /// Initial state for a program.
+    pub fn new(prog: Program) -> Self {
+        let track_registers = prog.iter().any(|ins| ins.is_conditional());
+        State {
+            prog,
+            prog_counter: 0,
+            registers: [0; NUM_REGISTERS],
+            seen: HashSet::new(),
+            track_registers,
+            status: Status::Running,
+            steps: 0,
+        }
+    }
+    pub fn program(&self) -> &[Instruction] {
+        &self.prog
+    }
+    pub fn acc(&self) -> isize {
+        self.registers[Register::ACC.index()]
+    }
+    pub fn register(&self, reg: Register) -> isize {
+        self.registers[reg.index()]
+    }
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+    pub fn prog_counter(&self) -> isize {
+        self.prog_counter
+    }
+    pub fn status(&self) -> Status {
+        self.status
+    }
+    pub fn is_running(&self) -> bool {
+        self.status == Status::Running
+    }
+    /// Number of instructions executed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+    /// Next instruction to execute, if the counter is in the program.
+    pub fn current(&self) -> Option<&Instruction> {
+        if self.prog_counter < 0 {
+            None
+        } else {
+            self.prog.get(self.prog_counter as usize)
+        }
+    }
+    fn loop_key(&self) -> (isize, Registers) {
+        if self.track_registers {
+            (self.prog_counter, self.registers)
+        } else {
+            (self.prog_counter, [0; NUM_REGISTERS])
+        }
+    }
+    /// Execute a single instruction, updating the status if we halt,
+    /// fault, or detect a loop.
+    pub fn step(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        let key = self.loop_key();
+        if self.seen.contains(&key) {
+            self.status = Status::LoopDetected;
+            return;
+        }
+        let ins = match self.current() {
+            Some(&ins) => ins,
+            None if self.prog_counter < 0 => {
+                self.status = Status::HaltTop;
+                return;
+            }
+            None => {
+                self.status = Status::HaltBottom;
+                return;
+            }
+        };
+        let mut offset = 1;
+        let result = match ins {
+            Instruction::Acc(n) => self.arith(ArithOp::Add, Register::ACC, n),
+            Instruction::Jmp(n) => {
+                offset = n;
+                Some(())
+            }
+            Instruction::Nop(_) => Some(()),
+            Instruction::Set(reg, arg) => {
+                self.registers[reg.index()] = arg.value(&self.registers);
+                Some(())
+            }
+            Instruction::Arith(op, reg, arg) => {
+                self.arith(op, reg, arg.value(&self.registers))
+            }
+            Instruction::JmpIf(cond, reg, n) => {
+                if cond.holds(self.register(reg)) {
+                    offset = n;
+                }
+                Some(())
+            }
+        };
+        if result.is_none() {
+            // Stay on the faulting instruction
+            self.status = Status::Fault;
+            return;
+        }
+        self.seen.insert(key);
+        // A jump too far to represent is past one end anyway, so
+        // saturating makes the next step halt (at the top or bottom)
+        self.prog_counter = self.prog_counter.saturating_add(offset);
+        self.steps += 1;
+    }
+    fn arith(&mut self, op: ArithOp, reg: Register, arg: isize) -> Option<()> {
+        let value = op.apply(self.register(reg), arg)?;
+        self.registers[reg.index()] = value;
+        Some(())
+    }
+    /// Run until the program halts, faults, or a loop is detected. With
+    /// conditional jumps, this may take unbounded time and memory (see
+    /// above); run_with_limit doesn't.
+    pub fn execute(&mut self) {
+        while self.is_running() {
+            debug!("{}", self);
+            self.step();
+        }
+        info!("{}", self);
+    }
+    /// Run for at most max_steps more instructions.
+    pub fn run_with_limit(&mut self, max_steps: usize) -> RunResult {
+        let start = self.steps;
+        while self.is_running() && self.steps - start < max_steps {
+            debug!("{}", self);
+            self.step();
+        }
+        // The final check (halting or loop) doesn't execute an instruction
+        if self.is_running() && self.steps - start == max_steps {
+            let key = self.loop_key();
+            if self.current().is_none() || self.seen.contains(&key) {
+                self.step();
+            }
+        }
+        RunResult { status: self.status, steps: self.steps - start }
+    }
+}
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "State: {{pos: {}, acc: {}, status: {:?}}})",
+            self.prog_counter,
+            self.acc(),
+            self.status,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+
+    const COUNTDOWN: &str = "\
+        set r1 +5       ; loop 5 times
+loop:   add acc r1
+        sub r1 +1
+        jnz r1 loop
+        mul acc -2
+";
+
+    #[test]
+    fn test_assemble_and_run() {
+        let program = assemble(&str_to_vec(COUNTDOWN)).unwrap();
+        assert_eq!(program.len(), 5);
+        assert_eq!(
+            program[3],
+            Instruction::JmpIf(Cond::NonZero, Register(1), -2)
+        );
+        let mut state = State::new(program);
+        state.execute();
+        assert_eq!(state.status(), Status::HaltBottom);
+        assert_eq!(state.acc(), -30);
+        assert_eq!(state.steps(), 17);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = assemble(&str_to_vec(COUNTDOWN)).unwrap();
+        let listing = Disassembly(&program).to_string();
+        assert_eq!(listing.lines().nth(3), Some("jnz r1 -2"));
+        assert_eq!(assemble(&str_to_vec(&listing)).unwrap(), program);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |raw: &str| assemble(&str_to_vec(raw)).unwrap_err();
+        assert_eq!(err("acc +1\njmp nowhere").line, Some(2));
+        assert!(err("a: nop +0\na: nop +0").cause.contains("duplicate"));
+        assert!(err("add r9 +1").cause.contains("register"));
+        assert!(err("mov r1 +1").cause.contains("unknown"));
+        assert!(err("acc +1 +2").cause.contains("argument"));
+    }
+
+    #[test]
+    fn test_run_with_limit() {
+        let program = assemble(&str_to_vec("acc +1\njmp -1")).unwrap();
+        let mut state = State::new(program.clone());
+        let result = state.run_with_limit(1);
+        assert_eq!(result, RunResult { status: Status::Running, steps: 1 });
+        let result = state.run_with_limit(10);
+        assert_eq!(
+            result,
+            RunResult { status: Status::LoopDetected, steps: 1 }
+        );
+        assert_eq!(state.acc(), 1);
+
+        let mut state = State::new(vec![Instruction::Acc(1)]);
+        let result = state.run_with_limit(1);
+        assert_eq!(result, RunResult { status: Status::HaltBottom, steps: 1 });
+
+        let program = assemble(&str_to_vec("set r1 +0\ndiv acc r1")).unwrap();
+        let mut state = State::new(program);
+        assert_eq!(state.run_with_limit(10).status, Status::Fault);
+        assert_eq!(state.prog_counter(), 1);
+    }
+
+    #[test]
+    fn test_huge_jumps() {
+        let program = vec![
+            Instruction::Nop(0),
+            Instruction::Jmp(isize::MAX),
+            Instruction::Jmp(isize::MIN),
+        ];
+        let mut state = State::new(program.clone());
+        state.execute();
+        assert_eq!(state.status(), Status::HaltBottom);
+        assert_eq!(state.prog_counter(), isize::MAX);
+        let mut state = State::new(program[2..].to_vec());
+        state.execute();
+        assert_eq!(state.status(), Status::HaltTop);
+        let cfg = cfg::Cfg::new(&program);
+        assert_eq!(
+            cfg.successors(cfg::Node::Ins(1)),
+            vec![cfg::Node::HaltBottom]
+        );
+    }
+}
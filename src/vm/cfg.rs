@@ -0,0 +1,353 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Control-flow analysis for handheld console programs
+
+    The control-flow graph has a node for each instruction, plus the two
+    ways to halt: jumping before the start (HaltTop) or past the end
+    (HaltBottom). Each instruction has an edge to the next instruction, or
+    to its jump target, or both for a conditional jump.
+
+    Without conditional jumps, each instruction has exactly one successor,
+    so the graph describes execution exactly: a program terminates iff
+    there is a path from the entry to HaltBottom. With conditional jumps it
+    over-approximates (a path may not be feasible for any register values).
+    Arithmetic faults are not modeled.
+
+    The graph makes day 8 part 2 linear: flipping an instruction that is
+    never executed changes nothing, and flipping one that is executed
+    terminates iff its new successor's path to HaltBottom exists in the
+    original graph and doesn't go back through the flipped instruction.
+    (When the original program loops, no path to HaltBottom can go through
+    an executed instruction; otherwise, see termination_tree.)
+*/
+
+use super::Instruction;
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// A node in the control-flow graph.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Node {
+    Ins(usize),
+    HaltTop,
+    HaltBottom,
+}
+
+/// Control-flow graph of a program.
+#[derive(Clone, Debug)]
+pub struct Cfg {
+    program: Vec<Instruction>,
+    // Successors and predecessors by node index (see index below)
+    succs: Vec<Vec<usize>>,
+    preds: Vec<Vec<usize>>,
+}
+impl Cfg {
+    pub fn new(program: &[Instruction]) -> Self {
+        let program = program.to_vec();
+        let nodes = program.len() + 2;
+        let mut succs = vec![Vec::new(); nodes];
+        let mut preds = vec![Vec::new(); nodes];
+        for i in 0..program.len() {
+            for node in successors_of(program[i], program.len(), i) {
+                let j = index(program.len(), node);
+                if !succs[i].contains(&j) {
+                    succs[i].push(j);
+                    preds[j].push(i);
+                }
+            }
+        }
+        Self { program, succs, preds }
+    }
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+    /// Whether the graph describes execution exactly (there are no
+    /// conditional jumps).
+    pub fn is_exact(&self) -> bool {
+        !self.program.iter().any(|ins| ins.is_conditional())
+    }
+    fn node(&self, index: usize) -> Node {
+        let len = self.program.len();
+        match index {
+            i if i < len => Node::Ins(i),
+            i if i == len => Node::HaltBottom,
+            _ => Node::HaltTop,
+        }
+    }
+    pub fn successors(&self, node: Node) -> Vec<Node> {
+        let i = index(self.program.len(), node);
+        self.succs[i].iter().map(|&j| self.node(j)).collect()
+    }
+    pub fn predecessors(&self, node: Node) -> Vec<Node> {
+        let i = index(self.program.len(), node);
+        self.preds[i].iter().map(|&j| self.node(j)).collect()
+    }
+
+    /*
+        Analyses
+    */
+    // Nodes reachable from start along the given edges (by node index)
+    fn search(&self, start: Node, edges: &[Vec<usize>]) -> Vec<bool> {
+        let mut found = vec![false; edges.len()];
+        let start = index(self.program.len(), start);
+        let mut queue = VecDeque::from(vec![start]);
+        found[start] = true;
+        while let Some(i) = queue.pop_front() {
+            for &j in &edges[i] {
+                if !found[j] {
+                    found[j] = true;
+                    queue.push_back(j);
+                }
+            }
+        }
+        found
+    }
+    /// Instructions reachable from the first instruction.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut found = self.search(Node::Ins(0), &self.succs);
+        found.truncate(self.program.len());
+        found
+    }
+    /// Instructions which can reach HaltBottom (terminate normally).
+    pub fn reaches_termination(&self) -> Vec<bool> {
+        let mut found = self.search(Node::HaltBottom, &self.preds);
+        found.truncate(self.program.len());
+        found
+    }
+    /// Instructions which can never be executed.
+    pub fn dead_code(&self) -> Vec<usize> {
+        let reachable = self.reachable();
+        (0..self.program.len()).filter(|&i| !reachable[i]).collect()
+    }
+    /*
+        In an exact graph, the paths to HaltBottom form a tree along the
+        predecessor edges, rooted at HaltBottom. Numbering it in depth-first
+        order gives each node the interval of its descendants: exactly the
+        nodes whose path to HaltBottom goes through it.
+    */
+    fn termination_tree(&self) -> Vec<Option<(usize, usize)>> {
+        let root = index(self.program.len(), Node::HaltBottom);
+        let mut intervals = vec![None; self.preds.len()];
+        intervals[root] = Some((0, 0));
+        let mut clock = 1;
+        let mut stack = vec![(root, 0)];
+        while let Some((i, child)) = stack.pop() {
+            if let Some(&j) = self.preds[i].get(child) {
+                stack.push((i, child + 1));
+                intervals[j] = Some((clock, 0));
+                clock += 1;
+                stack.push((j, 0));
+            } else if let Some((_, end)) = intervals[i].as_mut() {
+                *end = clock;
+            }
+        }
+        intervals
+    }
+    /// Analysis for checking in constant time whether replacing a single
+    /// instruction makes the program terminate. Linear time. Requires an
+    /// exact graph.
+    pub fn replacements(&self) -> Replacements {
+        assert!(self.is_exact(), "replacements need an exact graph");
+        let len = self.program.len();
+        let mut executed = vec![false; len];
+        // Follow the (only) path from the entry until it leaves or loops
+        let mut i = 0;
+        while i < len && !executed[i] {
+            executed[i] = true;
+            i = self.succs[i][0];
+        }
+        let terminates = self.node(i) == Node::HaltBottom;
+        Replacements { tree: self.termination_tree(), executed, terminates }
+    }
+    /// Instructions (jmp or nop) which make the program terminate normally
+    /// if flipped (jmp to nop or nop to jmp), in order. Linear time.
+    /// Requires an exact graph.
+    pub fn find_corrupted(&self) -> Vec<usize> {
+        let replacements = self.replacements();
+        (0..self.program.len())
+            .filter(|&i| match self.program[i].flipped() {
+                Some(flipped) => replacements.terminates_with(i, flipped),
+                None => false,
+            })
+            .collect()
+    }
+
+    /*
+        Export
+    */
+    /// The graph in Graphviz DOT format. Dead code is dashed, and
+    /// instructions which can't terminate normally are red.
+    pub fn to_dot(&self) -> String {
+        let reachable = self.reachable();
+        let terminates = self.reaches_termination();
+        let mut dot = String::from("digraph program {\n");
+        writeln!(dot, "    node [shape=box, fontname=monospace];").unwrap();
+        writeln!(dot, "    top [label=\"HaltTop\", shape=oval];").unwrap();
+        writeln!(dot, "    bottom [label=\"HaltBottom\", shape=oval];")
+            .unwrap();
+        for (i, ins) in self.program.iter().enumerate() {
+            let mut style = Vec::new();
+            if !reachable[i] {
+                style.push("style=dashed");
+            }
+            if !terminates[i] {
+                style.push("color=red");
+            }
+            let style: String =
+                style.iter().map(|attr| format!(", {}", attr)).collect();
+            writeln!(dot, "    n{} [label=\"{}: {}\"{}];", i, i, ins, style)
+                .unwrap();
+        }
+        let name = |node| match node {
+            Node::Ins(i) => format!("n{}", i),
+            Node::HaltTop => "top".to_owned(),
+            Node::HaltBottom => "bottom".to_owned(),
+        };
+        for i in 0..self.program.len() {
+            for succ in self.successors(Node::Ins(i)) {
+                writeln!(dot, "    n{} -> {};", i, name(succ)).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Which instructions a program executes and where their paths lead, to
+/// check replacing any one instruction (see Cfg::replacements).
+#[derive(Clone, Debug)]
+pub struct Replacements {
+    tree: Vec<Option<(usize, usize)>>,
+    executed: Vec<bool>,
+    terminates: bool,
+}
+impl Replacements {
+    /// Whether the program executes instruction i.
+    pub fn is_executed(&self, i: usize) -> bool {
+        self.executed[i]
+    }
+    /// Whether the program terminates normally as it is.
+    pub fn terminates(&self) -> bool {
+        self.terminates
+    }
+    /// Whether the program terminates normally after replacing instruction
+    /// i by ins (which must not be a conditional jump).
+    pub fn terminates_with(&self, i: usize, ins: Instruction) -> bool {
+        assert!(!ins.is_conditional());
+        if !self.executed[i] {
+            return self.terminates;
+        }
+        let len = self.executed.len();
+        let j = index(len, successors_of(ins, len, i)[0]);
+        match (self.tree[i], self.tree[j]) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some((start, end)), Some((pos, _))) => {
+                !(start..end).contains(&pos)
+            }
+        }
+    }
+}
+
+// Node index: instructions, then HaltBottom, then HaltTop
+fn index(len: usize, node: Node) -> usize {
+    match node {
+        Node::Ins(i) => i,
+        Node::HaltBottom => len,
+        Node::HaltTop => len + 1,
+    }
+}
+
+// Node reached by moving offset from instruction i
+fn target(len: usize, i: usize, offset: isize) -> Node {
+    let j = (i as isize).saturating_add(offset);
+    if j < 0 {
+        Node::HaltTop
+    } else if j as usize >= len {
+        Node::HaltBottom
+    } else {
+        Node::Ins(j as usize)
+    }
+}
+
+// Possible successors of an instruction at position i
+fn successors_of(ins: Instruction, len: usize, i: usize) -> Vec<Node> {
+    let next = target(len, i, 1);
+    match ins {
+        Instruction::Jmp(n) => vec![target(len, i, n)],
+        Instruction::JmpIf(_, _, n) => vec![next, target(len, i, n)],
+        _ => vec![next],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+    use crate::vm::assemble;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    fn cfg(raw: &str) -> Cfg {
+        Cfg::new(&assemble(&str_to_vec(raw)).unwrap())
+    }
+
+    #[test]
+    fn test_analyses() {
+        let cfg = cfg(EXAMPLE);
+        assert!(cfg.is_exact());
+        assert_eq!(cfg.successors(Node::Ins(2)), vec![Node::Ins(6)]);
+        assert_eq!(cfg.predecessors(Node::HaltBottom), vec![Node::Ins(8)]);
+        assert_eq!(cfg.dead_code(), vec![5, 8]);
+        let terminates: Vec<usize> =
+            (0..9).filter(|&i| cfg.reaches_termination()[i]).collect();
+        assert_eq!(terminates, vec![8]);
+        assert_eq!(cfg.find_corrupted(), vec![7]);
+    }
+
+    #[test]
+    fn test_find_corrupted_terminating() {
+        // These already terminate, but flips can still make them loop
+        let cfg1 = cfg("nop +2\nnop -1\nacc +1");
+        assert_eq!(cfg1.find_corrupted(), vec![0]);
+        let cfg3 = cfg("jmp +2\nnop -1\nacc +1");
+        assert_eq!(cfg3.find_corrupted(), vec![0, 1]);
+        let cfg2 = cfg("nop +0\nacc +1");
+        assert_eq!(cfg2.find_corrupted(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_conditional() {
+        let cfg = cfg("jz acc +2\njmp -2\nacc +1");
+        assert!(!cfg.is_exact());
+        assert_eq!(
+            cfg.successors(Node::Ins(0)),
+            vec![Node::Ins(1), Node::Ins(2)]
+        );
+        assert_eq!(cfg.successors(Node::Ins(1)), vec![Node::HaltTop]);
+        assert!(cfg.dead_code().is_empty());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = cfg(EXAMPLE).to_dot();
+        assert!(dot.starts_with("digraph program {"));
+        assert!(
+            dot.contains("n5 [label=\"5: acc -99\", style=dashed, color=red];")
+        );
+        assert!(dot.contains("n0 [label=\"0: nop +0\", color=red];"));
+        assert!(dot.contains("n8 -> bottom;"));
+        assert_eq!(dot.matches("->").count(), 9);
+    }
+}
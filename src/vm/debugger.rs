@@ -0,0 +1,389 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Interactive debugger for the handheld console
+
+    A Debugger wraps a State and runs commands one line at a time, either
+    from a REPL (see aoc debug) or directly from tests:
+
+        s, step [n]       execute n instructions (default 1)
+        rs, back [n]      undo the last n steps
+        c, continue       run until a breakpoint, a change to a watched acc,
+                          or the program stops
+        loop              run until the program stops, ignoring breakpoints
+        b, break [pc]     set a breakpoint (or list them)
+        d, delete <pc>    remove a breakpoint
+        w, watch          toggle stopping when acc changes
+        p, print          show the registers and status
+        l, list           show the program around the counter
+        seen              dump the set of seen states (for loop detection)
+        h, help           show this list
+        q, quit           exit
+
+    Reverse-stepping replays history: each step records what it changed
+    (the counter, registers, status, and any state it added to the seen
+    set), so undoing it restores the state exactly.
+*/
+
+use super::{Disassembly, Registers, State, Status};
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Commands:
+  s, step [n]     execute n instructions (default 1)
+  rs, back [n]    undo the last n steps (default 1)
+  c, continue     run to a breakpoint, an acc change (if watched) or the end
+  loop            run until the program stops, ignoring breakpoints
+  b, break [pc]   set a breakpoint at pc (or list breakpoints)
+  d, delete <pc>  remove a breakpoint
+  w, watch        toggle stopping when acc changes
+  p, print        show the registers and status
+  l, list         show the program around the counter
+  seen            dump the set of seen states
+  h, help         show this help
+  q, quit         exit";
+
+// Lines of the program shown on each side of the counter by list
+const LIST_CONTEXT: isize = 4;
+
+// What a single step changed, to undo it
+#[derive(Clone, Debug)]
+struct Undo {
+    prog_counter: isize,
+    registers: Registers,
+    status: Status,
+    steps: usize,
+    added: Option<(isize, Registers)>,
+}
+
+/// Debugger over a program state, with breakpoints and history.
+#[derive(Clone, Debug)]
+pub struct Debugger {
+    state: State,
+    breakpoints: BTreeSet<isize>,
+    watch_acc: bool,
+    history: Vec<Undo>,
+}
+impl Debugger {
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            breakpoints: BTreeSet::new(),
+            watch_acc: false,
+            history: Vec::new(),
+        }
+    }
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+    pub fn add_breakpoint(&mut self, pc: isize) {
+        self.breakpoints.insert(pc);
+    }
+    pub fn remove_breakpoint(&mut self, pc: isize) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+    pub fn set_watch_acc(&mut self, watch: bool) {
+        self.watch_acc = watch;
+    }
+
+    /*
+        Stepping forwards and backwards
+    */
+    /// Execute one instruction, recording it in the history. Returns
+    /// false if the program had already stopped.
+    pub fn step(&mut self) -> bool {
+        if !self.state.is_running() {
+            return false;
+        }
+        let key = self.state.loop_key();
+        let seen_before = self.state.seen.len();
+        let mut undo = Undo {
+            prog_counter: self.state.prog_counter,
+            registers: self.state.registers,
+            status: self.state.status,
+            steps: self.state.steps,
+            added: None,
+        };
+        self.state.step();
+        if self.state.seen.len() > seen_before {
+            undo.added = Some(key);
+        }
+        self.history.push(undo);
+        true
+    }
+    /// Undo the last step. Returns false if there is no history.
+    pub fn step_back(&mut self) -> bool {
+        let undo = match self.history.pop() {
+            Some(undo) => undo,
+            None => return false,
+        };
+        if let Some(key) = undo.added {
+            self.state.seen.remove(&key);
+        }
+        self.state.prog_counter = undo.prog_counter;
+        self.state.registers = undo.registers;
+        self.state.status = undo.status;
+        self.state.steps = undo.steps;
+        true
+    }
+    /// Run until a breakpoint (after at least one step), a change to acc
+    /// if watched, or the program stops. Returns why it stopped.
+    pub fn resume(&mut self) -> String {
+        let mut first = true;
+        while self.state.is_running() {
+            if !first && self.breakpoints.contains(&self.state.prog_counter) {
+                return format!("breakpoint at {}", self.state.prog_counter);
+            }
+            first = false;
+            let acc = self.state.acc();
+            self.step();
+            if self.watch_acc && self.state.acc() != acc {
+                return format!("acc: {} -> {}", acc, self.state.acc());
+            }
+        }
+        self.stopped()
+    }
+    /// Run until the program stops (halts, faults, or loops).
+    pub fn run_until_stopped(&mut self) -> String {
+        while self.step() {}
+        self.stopped()
+    }
+    fn stopped(&self) -> String {
+        format!(
+            "stopped: {:?} at {} (acc = {})",
+            self.state.status,
+            self.state.prog_counter,
+            self.state.acc()
+        )
+    }
+
+    /*
+        Inspecting the state
+    */
+    /// Registers, status and the next instruction.
+    pub fn print(&self) -> String {
+        let mut out = String::new();
+        write!(out, "pc = {}", self.state.prog_counter).unwrap();
+        for (i, value) in self.state.registers.iter().enumerate() {
+            let reg = super::Register(i);
+            if i == 0 || *value != 0 {
+                write!(out, ", {} = {}", reg, value).unwrap();
+            }
+        }
+        let (status, steps) = (self.state.status, self.state.steps);
+        write!(out, "\nstatus: {:?}, steps: {}", status, steps).unwrap();
+        if let Some(ins) = self.state.current() {
+            write!(out, "\nnext: {}", ins).unwrap();
+        }
+        out
+    }
+    /// The program around the counter, marking the counter (>) and
+    /// breakpoints (*).
+    pub fn list(&self) -> String {
+        let pc = self.state.prog_counter;
+        let listing = Disassembly(&self.state.prog).to_string();
+        let lines: Vec<String> = listing
+            .lines()
+            .enumerate()
+            .map(|(i, ins)| (i as isize, ins))
+            .filter(|(i, _)| (i - pc).abs() <= LIST_CONTEXT)
+            .map(|(i, ins)| {
+                let here = if i == pc { '>' } else { ' ' };
+                let brk = self.breakpoints.contains(&i);
+                let brk = if brk { '*' } else { ' ' };
+                format!("{}{} {:>4}  {}", here, brk, i, ins)
+            })
+            .collect();
+        if lines.is_empty() {
+            format!("pc = {} is outside the program", pc)
+        } else {
+            lines.join("\n")
+        }
+    }
+    /// The seen states: the counters, and the registers if the program
+    /// has conditional jumps (so loops depend on them).
+    pub fn seen(&self) -> String {
+        let mut seen: Vec<&(isize, Registers)> =
+            self.state.seen.iter().collect();
+        seen.sort();
+        let entries: Vec<String> = seen
+            .iter()
+            .map(|(pc, registers)| {
+                if self.state.track_registers {
+                    format!("{} {:?}", pc, registers)
+                } else {
+                    pc.to_string()
+                }
+            })
+            .collect();
+        format!("{} seen: {}", entries.len(), entries.join(", "))
+    }
+
+    /*
+        Commands
+    */
+    /// Run one command line, returning its output, or None to quit.
+    pub fn command(&mut self, line: &str) -> Option<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (cmd, args) = match words.split_first() {
+            Some((cmd, args)) => (*cmd, args),
+            None => return Some(String::new()),
+        };
+        let count = || match args.first() {
+            Some(raw) => raw.parse::<usize>().map_err(|_| {
+                format!("expected a number of steps, found {}", raw)
+            }),
+            None => Ok(1),
+        };
+        let pc = || match args.first() {
+            Some(raw) => raw
+                .parse::<isize>()
+                .map_err(|_| format!("expected an instruction index: {}", raw)),
+            None => Err("expected an instruction index".to_owned()),
+        };
+        let result = match cmd {
+            "s" | "step" => count().map(|n| {
+                let taken = (0..n).take_while(|_| self.step()).count();
+                if taken < n {
+                    self.stopped()
+                } else {
+                    self.print()
+                }
+            }),
+            "rs" | "back" => count().map(|n| {
+                let undone = (0..n).take_while(|_| self.step_back()).count();
+                if undone < n {
+                    format!("at the start of history\n{}", self.print())
+                } else {
+                    self.print()
+                }
+            }),
+            "c" | "continue" => Ok(self.resume()),
+            "loop" => Ok(self.run_until_stopped()),
+            "b" | "break" if args.is_empty() => {
+                let pcs: Vec<String> =
+                    self.breakpoints.iter().map(|pc| pc.to_string()).collect();
+                Ok(format!("breakpoints: {}", pcs.join(", ")))
+            }
+            "b" | "break" => pc().map(|pc| {
+                self.add_breakpoint(pc);
+                format!("breakpoint at {}", pc)
+            }),
+            "d" | "delete" => pc().and_then(|pc| {
+                if self.remove_breakpoint(pc) {
+                    Ok(format!("removed breakpoint at {}", pc))
+                } else {
+                    Err(format!("no breakpoint at {}", pc))
+                }
+            }),
+            "w" | "watch" => {
+                self.watch_acc = !self.watch_acc;
+                let on = if self.watch_acc { "on" } else { "off" };
+                Ok(format!("watching acc: {}", on))
+            }
+            "p" | "print" => Ok(self.print()),
+            "l" | "list" => Ok(self.list()),
+            "seen" => Ok(self.seen()),
+            "h" | "help" => Ok(HELP.to_owned()),
+            "q" | "quit" => return None,
+            _ => Err(format!("unknown command: {} (try help)", cmd)),
+        };
+        Some(result.unwrap_or_else(|err| format!("error: {}", err)))
+    }
+    /// Read commands until quit or end of input, printing a prompt before
+    /// each command and the output after it.
+    pub fn repl<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        mut output: W,
+    ) -> io::Result<()> {
+        write!(output, "(vm) ")?;
+        output.flush()?;
+        for line in input.lines() {
+            match self.command(&line?) {
+                Some(reply) if reply.is_empty() => (),
+                Some(reply) => writeln!(output, "{}", reply)?,
+                None => return Ok(()),
+            }
+            write!(output, "(vm) ")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+    use crate::vm::assemble;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    fn debugger() -> Debugger {
+        Debugger::new(State::new(assemble(&str_to_vec(EXAMPLE)).unwrap()))
+    }
+
+    #[test]
+    fn test_breakpoints_and_watch() {
+        let mut dbg = debugger();
+        dbg.command("break 3").unwrap();
+        assert_eq!(dbg.command("c").unwrap(), "breakpoint at 3");
+        assert_eq!(dbg.state().acc(), 2);
+        dbg.command("w").unwrap();
+        assert_eq!(dbg.command("continue").unwrap(), "acc: 2 -> 5");
+        dbg.command("delete 3").unwrap();
+        dbg.command("watch").unwrap();
+        let stopped = dbg.command("c").unwrap();
+        assert_eq!(stopped, "stopped: LoopDetected at 1 (acc = 5)");
+        assert!(dbg.command("d 3").unwrap().starts_with("error"));
+    }
+
+    #[test]
+    fn test_step_back() {
+        let mut dbg = debugger();
+        dbg.run_until_stopped();
+        assert_eq!(dbg.state().status(), Status::LoopDetected);
+        let seen = dbg.command("seen").unwrap();
+        assert_eq!(seen, "7 seen: 0, 1, 2, 3, 4, 6, 7");
+        // Undo detecting the loop, then jmp -3
+        dbg.command("back 2").unwrap();
+        assert_eq!(dbg.state().status(), Status::Running);
+        assert_eq!(dbg.state().prog_counter(), 4);
+        assert_eq!(dbg.state().acc(), 5);
+        let seen = dbg.command("seen").unwrap();
+        assert_eq!(seen, "6 seen: 0, 1, 2, 3, 6, 7");
+        // Replaying gives the same result
+        dbg.command("loop").unwrap();
+        assert_eq!((dbg.state().acc(), dbg.state().steps()), (5, 7));
+        let reply = dbg.command("rs 10").unwrap();
+        assert!(reply.starts_with("at the start of history"));
+        assert_eq!(dbg.state().steps(), 0);
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut dbg = debugger();
+        let mut output = Vec::new();
+        let input = "s 3\nl\nbogus\nq\np\n";
+        dbg.repl(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("pc = 6, acc = 1"));
+        assert!(output.contains(">     6  acc +1"));
+        assert!(output.contains("error: unknown command: bogus"));
+        // Nothing runs after quit
+        assert_eq!(output.matches("(vm) ").count(), 4);
+    }
+}
@@ -0,0 +1,426 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Repairing handheld console programs
+
+    Day 8 part 2 assumes exactly one instruction is corrupted, and that
+    flipping it (jmp to nop or nop to jmp) is the fix. find_repairs instead
+    searches for the smallest sets of edits which make a program terminate
+    normally (HaltBottom), where an edit is one of:
+
+    - Flip: swap a jmp and a nop.
+    - SetArg: change the offset of a jump to any offset which stays in
+      bounds (lands on an instruction or just past the end). Changing a
+      nop or acc argument never affects termination, so it is not
+      considered.
+    - Delete: remove an instruction, so later instructions move up by one
+      (jump offsets are unchanged).
+
+    Edits refer to instructions by their index in the original program, and
+    a repair lists them in order of index (a flip before a new offset for
+    the same instruction). Since deleting never changes an offset, the
+    offset given by SetArg is the jump's offset in the repaired program,
+    whatever is deleted around it.
+
+    The search tries 0 edits, then 1, and so on up to a maximum, returning
+    every repair of the smallest size found. It picks the edits in order of
+    index, so each set of edits is tried once. Editing an instruction that
+    never runs changes nothing, so while the partially edited program still
+    runs the same way, some later edit must change an executed instruction
+    or delete one; the last edit in particular must. For the last edit of a
+    program without conditional jumps, the control-flow graph checks each
+    flip or new offset in constant time.
+
+    The number of candidates grows quickly with the number of edits:
+    allowing offset changes, one edit is about n^2 candidates, so more than
+    one edit is only practical for small programs.
+*/
+
+use super::cfg::Cfg;
+use super::{Instruction, State, Status};
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An edit to a program, by index in the original program.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Edit {
+    Flip(usize),
+    SetArg(usize, isize),
+    Delete(usize),
+}
+impl Edit {
+    pub fn index(&self) -> usize {
+        match *self {
+            Edit::Flip(i) | Edit::SetArg(i, _) | Edit::Delete(i) => i,
+        }
+    }
+    // Position in the order edits are applied: by index, then kind
+    fn order(&self) -> (usize, u8) {
+        match *self {
+            Edit::Flip(i) => (i, 0),
+            Edit::SetArg(i, _) => (i, 1),
+            Edit::Delete(i) => (i, 2),
+        }
+    }
+}
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edit::Flip(i) => write!(f, "flip {}", i),
+            Edit::SetArg(i, arg) => write!(f, "set {} to {:+}", i, arg),
+            Edit::Delete(i) => write!(f, "delete {}", i),
+        }
+    }
+}
+
+/// A set of edits which makes a program terminate normally, and the
+/// final value of the accumulator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Repair {
+    pub edits: Vec<Edit>,
+    pub acc: isize,
+}
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let edits: Vec<String> =
+            self.edits.iter().map(|edit| edit.to_string()).collect();
+        if edits.is_empty() {
+            write!(f, "no edits: acc = {}", self.acc)
+        } else {
+            write!(f, "{}: acc = {}", edits.join(", "), self.acc)
+        }
+    }
+}
+
+/// Why there is no unique repair.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RepairError {
+    NoRepair { max_edits: usize },
+    Multiple(Vec<Repair>),
+}
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RepairError::NoRepair { max_edits } => {
+                write!(f, "no repair with at most {} edit(s)", max_edits)
+            }
+            RepairError::Multiple(repairs) => {
+                write!(f, "{} minimal repairs", repairs.len())?;
+                for repair in repairs {
+                    write!(f, "\n    {}", repair)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Which edits to consider, and limits on the search.
+#[derive(Clone, Debug)]
+pub struct RepairOptions {
+    pub max_edits: usize,
+    pub flip: bool,
+    pub set_arg: bool,
+    pub delete: bool,
+    /// Steps to run each candidate for (only reached by programs with
+    /// conditional jumps, which can run for a long time without looping)
+    pub max_steps: usize,
+}
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            max_edits: 2,
+            flip: true,
+            set_arg: true,
+            delete: true,
+            max_steps: 100_000,
+        }
+    }
+}
+impl RepairOptions {
+    /// Only flips, as in day 8.
+    pub fn flips_only(max_edits: usize) -> Self {
+        let flip = true;
+        let (set_arg, delete) = (false, false);
+        Self { max_edits, flip, set_arg, delete, ..Self::default() }
+    }
+}
+
+// A program being edited: each instruction with its original index
+type Edited = Vec<(usize, Instruction)>;
+
+fn instructions(prog: &[(usize, Instruction)]) -> Vec<Instruction> {
+    prog.iter().map(|&(_, ins)| ins).collect()
+}
+
+// The jump with its offset replaced
+fn with_arg(ins: Instruction, arg: isize) -> Option<Instruction> {
+    match ins {
+        Instruction::Jmp(_) => Some(Instruction::Jmp(arg)),
+        Instruction::JmpIf(cond, reg, _) => {
+            Some(Instruction::JmpIf(cond, reg, arg))
+        }
+        _ => None,
+    }
+}
+fn arg(ins: Instruction) -> Option<isize> {
+    match ins {
+        Instruction::Jmp(n) | Instruction::JmpIf(_, _, n) => Some(n),
+        _ => None,
+    }
+}
+
+struct Search<'a> {
+    options: &'a RepairOptions,
+    found: BTreeMap<Vec<Edit>, isize>,
+}
+impl Search<'_> {
+    fn run(&self, prog: &[(usize, Instruction)]) -> State {
+        let mut state = State::new(instructions(prog));
+        state.run_with_limit(self.options.max_steps);
+        state
+    }
+    // Candidate edits after the last edit, with the position they apply
+    // to and the new instruction (None to delete)
+    fn candidates(
+        &self,
+        prog: &[(usize, Instruction)],
+        executed: &[bool],
+        last: Option<&Edit>,
+        left: usize,
+    ) -> Vec<(Edit, usize, Option<Instruction>)> {
+        let after_last =
+            |edit: &Edit| last.is_none_or(|last| edit.order() > last.order());
+        // An edit to an instruction that doesn't run needs a later edit
+        // that changes how the program runs
+        let last_executed = prog
+            .iter()
+            .zip(executed)
+            .rev()
+            .find(|(_, &ex)| ex)
+            .map(|(&(i, _), _)| i);
+        let useful = |pos: usize, i: usize| {
+            executed[pos]
+                || left >= 2 && (self.options.delete || last_executed > Some(i))
+        };
+        let mut candidates = Vec::new();
+        for (pos, &(i, ins)) in prog.iter().enumerate() {
+            if !useful(pos, i) {
+                continue;
+            }
+            if let Some(flipped) = ins.flipped() {
+                if self.options.flip && after_last(&Edit::Flip(i)) {
+                    candidates.push((Edit::Flip(i), pos, Some(flipped)));
+                }
+            }
+            if let Some(old) = arg(ins) {
+                if !self.options.set_arg || !after_last(&Edit::SetArg(i, 0)) {
+                    continue;
+                }
+                // Offsets landing anywhere from the start to just past the end
+                let pos = pos as isize;
+                for new in -pos..=(prog.len() as isize - pos) {
+                    if new != old {
+                        let new_ins = with_arg(ins, new);
+                        let edit = Edit::SetArg(i, new);
+                        candidates.push((edit, pos as usize, new_ins));
+                    }
+                }
+            }
+        }
+        if self.options.delete {
+            for (pos, &(i, _)) in prog.iter().enumerate() {
+                if after_last(&Edit::Delete(i)) {
+                    candidates.push((Edit::Delete(i), pos, None));
+                }
+            }
+        }
+        candidates
+    }
+    // Try every set of exactly `left` more edits
+    fn search(
+        &mut self,
+        prog: &[(usize, Instruction)],
+        edits: &mut Vec<Edit>,
+        left: usize,
+    ) {
+        let state = self.run(prog);
+        if left == 0 {
+            if state.status() == Status::HaltBottom {
+                self.found.insert(edits.clone(), state.acc());
+            }
+            return;
+        }
+        let mut executed = vec![false; prog.len()];
+        for &(pc, _) in &state.seen {
+            executed[pc as usize] = true;
+        }
+        let candidates = self.candidates(prog, &executed, edits.last(), left);
+        let exact = prog.iter().all(|(_, ins)| !ins.is_conditional());
+        let replacements = if left == 1 && exact {
+            Some(Cfg::new(&instructions(prog)).replacements())
+        } else {
+            None
+        };
+        for (edit, pos, new_ins) in candidates {
+            let mut next: Edited = prog.to_vec();
+            match new_ins {
+                Some(new_ins) => {
+                    // Skip edits which the graph shows don't terminate
+                    if let Some(replacements) = &replacements {
+                        if !replacements.terminates_with(pos, new_ins) {
+                            continue;
+                        }
+                    }
+                    next[pos].1 = new_ins;
+                }
+                None => {
+                    next.remove(pos);
+                }
+            }
+            edits.push(edit);
+            self.search(&next, edits, left - 1);
+            edits.pop();
+        }
+    }
+}
+
+/// All repairs with the fewest edits (up to options.max_edits), in order
+/// of their edits. Empty if there is no repair.
+pub fn find_repairs(
+    program: &[Instruction],
+    options: &RepairOptions,
+) -> Vec<Repair> {
+    let prog: Edited = program.iter().copied().enumerate().collect();
+    for edits in 0..=options.max_edits {
+        let mut search = Search { options, found: BTreeMap::new() };
+        search.search(&prog, &mut Vec::new(), edits);
+        if !search.found.is_empty() {
+            return search
+                .found
+                .into_iter()
+                .map(|(edits, acc)| Repair { edits, acc })
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+/// The only repair with the fewest edits, or why there isn't one.
+pub fn unique_repair(
+    program: &[Instruction],
+    options: &RepairOptions,
+) -> Result<Repair, RepairError> {
+    let mut repairs = find_repairs(program, options);
+    match repairs.len() {
+        0 => Err(RepairError::NoRepair { max_edits: options.max_edits }),
+        1 => Ok(repairs.pop().unwrap()),
+        _ => Err(RepairError::Multiple(repairs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::str_to_vec;
+    use crate::vm::assemble;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    fn program(raw: &str) -> Vec<Instruction> {
+        assemble(&str_to_vec(raw)).unwrap()
+    }
+
+    #[test]
+    fn test_day8_example() {
+        let repair =
+            unique_repair(&program(EXAMPLE), &RepairOptions::flips_only(1));
+        let expected = Repair { edits: vec![Edit::Flip(7)], acc: 8 };
+        assert_eq!(repair, Ok(expected));
+        assert_eq!(repair.unwrap().to_string(), "flip 7: acc = 8");
+    }
+
+    #[test]
+    fn test_all_edits() {
+        let repairs =
+            find_repairs(&program(EXAMPLE), &RepairOptions::default());
+        // One edit: flip 7, delete 7, or point 7 or 4 somewhere that ends
+        assert!(repairs.iter().all(|repair| repair.edits.len() == 1));
+        assert!(
+            repairs.contains(&Repair { edits: vec![Edit::Flip(7)], acc: 8 })
+        );
+        assert!(
+            repairs.contains(&Repair { edits: vec![Edit::Delete(7)], acc: 8 })
+        );
+        assert!(repairs
+            .contains(&Repair { edits: vec![Edit::SetArg(7, 2)], acc: 2 }));
+        assert!(repairs
+            .contains(&Repair { edits: vec![Edit::SetArg(4, 5)], acc: 5 }));
+        let err = unique_repair(&program(EXAMPLE), &RepairOptions::default());
+        assert!(err.unwrap_err().to_string().contains("minimal repairs"));
+    }
+
+    #[test]
+    fn test_set_arg_jumps_only() {
+        // A nop's argument is never used, so it isn't worth setting
+        assert_eq!(arg(Instruction::Nop(3)), None);
+        assert_eq!(with_arg(Instruction::Nop(3), 1), None);
+        assert_eq!(arg(Instruction::Acc(3)), None);
+        assert_eq!(arg(Instruction::Jmp(-2)), Some(-2));
+        assert_eq!(
+            with_arg(Instruction::Jmp(-2), 1),
+            Some(Instruction::Jmp(1))
+        );
+    }
+
+    #[test]
+    fn test_two_faults() {
+        // Both jumps loop back, so both need flipping
+        let prog = program("acc +1\njmp -1\nacc +2\njmp -1\nacc +4");
+        let options = RepairOptions::flips_only(2);
+        let expected =
+            Repair { edits: vec![Edit::Flip(1), Edit::Flip(3)], acc: 7 };
+        assert_eq!(unique_repair(&prog, &options), Ok(expected));
+        let err = unique_repair(&prog, &RepairOptions::flips_only(1));
+        assert_eq!(err, Err(RepairError::NoRepair { max_edits: 1 }));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "no repair with at most 1 edit(s)"
+        );
+    }
+
+    #[test]
+    fn test_edit_order() {
+        // Flipping 1 only matters once 3 is flipped, so the search has to
+        // try an edit to an instruction that doesn't run yet
+        let prog = program("jmp +3\njmp +0\njmp +3\njmp +0\njmp -3");
+        let repairs = find_repairs(&prog, &RepairOptions::flips_only(2));
+        let edits: Vec<Vec<Edit>> =
+            repairs.into_iter().map(|repair| repair.edits).collect();
+        assert_eq!(
+            edits,
+            vec![
+                vec![Edit::Flip(0), Edit::Flip(1)],
+                vec![Edit::Flip(1), Edit::Flip(3)],
+                vec![Edit::Flip(3), Edit::Flip(4)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_already_terminates() {
+        let repairs =
+            find_repairs(&program("acc +1"), &RepairOptions::default());
+        assert_eq!(repairs, vec![Repair { edits: vec![], acc: 1 }]);
+    }
+}
@@ -0,0 +1,68 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Golden-answer regression tests
+
+    Runs each day on its input and compares both parts against answers.toml.
+    Days that take too long in a debug build are ignored by default; run
+    them with:
+        cargo test --release --test answers -- --include-ignored
+*/
+
+use aoc2020::solution::input_path;
+use aoc2020::verify::{verify_day, Answers, ANSWERS_PATH};
+
+fn check_day(day: usize) {
+    let answers = Answers::load(ANSWERS_PATH).unwrap();
+    let expected = answers
+        .get(day)
+        .unwrap_or_else(|| panic!("No expected answers for day {}", day));
+    let mismatches = verify_day(day, &input_path(day), expected)
+        .unwrap_or_else(|err| panic!("{}", err));
+    let report: Vec<String> =
+        mismatches.iter().map(|m| m.to_string()).collect();
+    assert!(mismatches.is_empty(), "\n{}", report.join("\n"));
+}
+
+macro_rules! day_tests {
+    ($($(#[$attr:meta])* $name:ident: $day:expr,)*) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $name() {
+                check_day($day);
+            }
+        )*
+    };
+}
+
+day_tests! {
+    day01: 1,
+    day02: 2,
+    day03: 3,
+    day04: 4,
+    day05: 5,
+    day06: 6,
+    day07: 7,
+    day08: 8,
+    day09: 9,
+    day10: 10,
+    day11: 11,
+    day12: 12,
+    day13: 13,
+    day14: 14,
+    #[ignore = "slow in debug builds"]
+    day15: 15,
+    day16: 16,
+    day17: 17,
+    day18: 18,
+    day19: 19,
+    #[ignore = "slow in debug builds"]
+    day20: 20,
+    day21: 21,
+    day22: 22,
+    #[ignore = "slow in debug builds"]
+    day23: 23,
+    day24: 24,
+    day25: 25,
+}
@@ -0,0 +1,118 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Differential tests against the reference solutions
+
+    Each test runs an optimized solver and its naive counterpart in
+    aoc2020::reference on small generated inputs and checks that they agree.
+    On failure, the seed is in the assertion message.
+*/
+
+use aoc2020::day10::{self, Day10};
+use aoc2020::day13::{self, Day13};
+use aoc2020::day18::Expression;
+use aoc2020::day23::CupGame;
+use aoc2020::generate::{self, Rng};
+use aoc2020::reference;
+use aoc2020::solution::Solution;
+use aoc2020::vm::assemble;
+use aoc2020::vm::cfg::Cfg;
+
+use std::str::FromStr;
+
+const SEEDS: u64 = 500;
+
+#[test]
+fn diff_corrupted_instructions() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let len = 1 + rng.below(30);
+        let lines = if rng.chance(0.5) {
+            generate::handheld_program(&mut rng, len)
+        } else {
+            generate::terminating_program(&mut rng, len)
+        };
+        let program = assemble(&lines).unwrap();
+        assert_eq!(
+            Cfg::new(&program).find_corrupted(),
+            reference::corrupted_instructions(&program),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn diff_adapter_arrangements() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let num_adapters = 1 + rng.below(16);
+        let lines = generate::adapters(&mut rng, num_adapters, &[1, 2, 3]);
+        let joltages = Day10::parse(&lines).unwrap();
+        assert_eq!(
+            day10::count_arrangements(&joltages),
+            reference::adapter_arrangements(&joltages),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn diff_bus_schedule() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let lines = generate::bus_schedule(&mut rng, 4);
+        let (target, buses) = Day13::parse(&lines).unwrap();
+        assert_eq!(
+            day13::solve_part1(target, &buses),
+            Some(reference::earliest_bus(target, &buses)),
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            day13::solve_part2(&buses),
+            Some(reference::earliest_timestamp(&buses)),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn diff_expressions() {
+    for seed in 0..SEEDS * 4 {
+        let mut rng = Rng::new(seed);
+        let raw = generate::expression(&mut rng, 12);
+        let expr = Expression::from_str(&raw).unwrap();
+        assert_eq!(
+            expr.eval_part1(),
+            Ok(reference::eval_part1(&raw) as i64),
+            "seed {}: {}",
+            seed,
+            raw
+        );
+        assert_eq!(
+            expr.eval_part2(),
+            Ok(reference::eval_part2(&raw) as i64),
+            "seed {}: {}",
+            seed,
+            raw
+        );
+    }
+}
+
+#[test]
+fn diff_cup_game() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let num_cups = 5 + rng.below(20);
+        let labels = generate::cup_labeling(&mut rng, num_cups);
+        let moves = rng.below(200);
+        let mut game = CupGame::new(&labels);
+        game.step_for(moves);
+        // CupGame numbers cups from 0
+        let cups: Vec<usize> = game.cups_clockwise().map(|i| i + 1).collect();
+        assert_eq!(cups, reference::play_cups(&labels, moves), "seed {}", seed);
+    }
+}
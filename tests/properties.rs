@@ -0,0 +1,358 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Property tests on randomly generated inputs
+
+    Each test checks an invariant across many seeded inputs from
+    aoc2020::generate. On failure, the seed is in the assertion message, so
+    the input can be regenerated with Rng::new(seed).
+*/
+
+use aoc2020::day07;
+use aoc2020::day08::{self, Status};
+use aoc2020::day18::{self, Expression};
+use aoc2020::day19::{
+    self, earley::EarleyParser, regular::Dfa, Engine, RegexCases,
+};
+use aoc2020::day20::{self, SortedPuzzle, UnsortedPuzzle};
+use aoc2020::day22::{self, SpaceCards};
+use aoc2020::day23::CupGame;
+use aoc2020::day24::{self, agg_path, HexCoord};
+use aoc2020::generate::{self, Rng};
+use aoc2020::util::{unique_0_to_n, unique_1_to_n};
+use aoc2020::vm::assemble;
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const SEEDS: u64 = 1000;
+
+/*
+    Day 7
+*/
+
+// Count bags inside a bag directly from the rule text
+fn count_inside(
+    rules: &HashMap<String, Vec<(usize, String)>>,
+    bag: &str,
+) -> usize {
+    rules[bag]
+        .iter()
+        .map(|(count, inner)| count * (1 + count_inside(rules, inner)))
+        .sum()
+}
+
+#[test]
+fn prop_bag_rules() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let num_bags = 1 + rng.below(30);
+        let lines = generate::bag_rules(&mut rng, num_bags);
+        let mut rules = HashMap::new();
+        for line in &lines {
+            let words: Vec<&str> = line.split(' ').collect();
+            let contents = words[4..]
+                .chunks(4)
+                .filter(|chunk| chunk[0] != "no")
+                .map(|chunk| (chunk[0].parse().unwrap(), chunk[1..3].join(" ")))
+                .collect();
+            rules.insert(words[..2].join(" "), contents);
+        }
+        let mut graph = day07::parse_input(&lines)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        assert!(day07::solve_part1(&graph) < num_bags, "seed {}", seed);
+        assert_eq!(
+            day07::solve_part2(&mut graph),
+            count_inside(&rules, "shiny gold"),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+/*
+    Day 8
+*/
+
+#[test]
+fn prop_programs_halt_or_loop() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let len = 1 + rng.below(50);
+        let program = generate::handheld_program(&mut rng, len);
+        let mut state = day08::State::new(assemble(&program).unwrap());
+        state.execute();
+        assert_ne!(state.status(), Status::Running, "seed {}", seed);
+
+        let program = generate::terminating_program(&mut rng, len);
+        let mut state = day08::State::new(assemble(&program).unwrap());
+        state.execute();
+        assert_eq!(state.status(), Status::HaltBottom, "seed {}", seed);
+    }
+}
+
+/*
+    Day 18
+*/
+
+#[test]
+fn prop_expression_printing() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let raw = generate::expression(&mut rng, 12);
+        let parsed = Expression::from_str(&raw).unwrap();
+        assert_eq!(parsed.to_string(), raw, "seed {}", seed);
+        for &table in &[day18::PART1, day18::PART2, day18::STANDARD] {
+            // Both printed forms parse back to the same tree
+            let expr = parsed.to_expr(table).unwrap();
+            for printed in &[expr.pretty(table).unwrap(), expr.to_string()] {
+                let reparsed = Expression::from_str(printed).unwrap();
+                let msg = format!("seed {}: {}", seed, printed);
+                assert_eq!(reparsed.to_expr(table).unwrap(), expr, "{}", msg);
+            }
+        }
+        let converted = parsed.to_part1();
+        assert_eq!(
+            converted.to_expr(day18::PART1).unwrap(),
+            parsed.to_expr(day18::PART2).unwrap(),
+            "seed {}: {}",
+            seed,
+            converted
+        );
+    }
+}
+
+/*
+    Day 19
+*/
+
+// Translate the (acyclic) rules into a regular expression
+fn to_regex(rules: &HashMap<&str, &str>, id: &str) -> String {
+    let body = rules[id];
+    if let Some(ch) = body.strip_prefix('"') {
+        return ch.trim_end_matches('"').to_owned();
+    }
+    let alts: Vec<String> = body
+        .split(" | ")
+        .map(|seq| seq.split(' ').map(|id| to_regex(rules, id)).collect())
+        .collect();
+    format!("(?:{})", alts.join("|"))
+}
+
+#[test]
+fn prop_matcher_agrees_with_regex() {
+    for seed in 0..SEEDS / 4 {
+        let mut rng = Rng::new(seed);
+        let num_rules = 3 + rng.below(20);
+        let lines = generate::rule_grammar(&mut rng, num_rules, 10);
+        let blank = lines.iter().position(|line| line.is_empty()).unwrap();
+        let rules: HashMap<&str, &str> = lines[..blank]
+            .iter()
+            .map(|line| {
+                let (id, body) = line.split_at(line.find(": ").unwrap());
+                (id, &body[2..])
+            })
+            .collect();
+        let regex =
+            Regex::new(&format!("^{}$", to_regex(&rules, "0"))).unwrap();
+        let (mut matcher, msgs) = day19::parse_input(&lines)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        for msg in &msgs {
+            assert_eq!(
+                matcher.eval(0, msg),
+                regex.is_match(msg),
+                "seed {}: {}",
+                seed,
+                msg
+            );
+        }
+    }
+}
+
+#[test]
+fn prop_dfa_agrees_with_matcher() {
+    for seed in 0..SEEDS / 4 {
+        let mut rng = Rng::new(seed);
+        let num_rules = 3 + rng.below(40);
+        let lines = generate::rule_grammar(&mut rng, num_rules, 10);
+        let (mut matcher, msgs) = day19::parse_input(&lines).unwrap();
+        // Acyclic rules are always regular
+        let dfa = Dfa::compile(&matcher, 0)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        for msg in &msgs {
+            let msg = msg.as_str();
+            let mutated = format!("{}a", &msg[..msg.len() / 2]);
+            for msg in &[msg, &mutated] {
+                assert_eq!(
+                    dfa.is_match(msg),
+                    matcher.eval(0, msg),
+                    "seed {}: {}",
+                    seed,
+                    msg
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn prop_earley_agrees_with_matcher() {
+    // SmartRegexMatcher is slow with loops
+    for seed in 0..SEEDS / 20 {
+        let mut rng = Rng::new(seed);
+        let num_rules = 3 + rng.below(20);
+        let lines = generate::rule_grammar(&mut rng, num_rules, 10);
+        let (mut matcher, msgs) = day19::parse_input(&lines).unwrap();
+        // A left-recursive rule for one or more matches of rule 0:
+        //     150: 0 | 151
+        //     151: 150 0
+        matcher.add_regex(150, RegexCases::Union(0, 151));
+        matcher.add_regex(151, RegexCases::Concat(150, 0));
+        let parser = EarleyParser::new(&matcher);
+        for msg in &msgs {
+            let msg = msg.as_str();
+            let mutated = format!("{}a", &msg[..msg.len() / 2]);
+            let twice = msg.repeat(2);
+            for msg in &[msg, &mutated, &twice] {
+                for &id in &[0, 150] {
+                    assert_eq!(
+                        parser.parse(id, msg).is_match(),
+                        matcher.eval(id, msg),
+                        "seed {}: rule {}: {}",
+                        seed,
+                        id,
+                        msg
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn prop_rule_syntax_engines_agree() {
+    // Every engine but the DFA is slow in debug builds
+    for seed in 0..SEEDS / 10 {
+        let mut rng = Rng::new(seed);
+        let num_rules = 1 + rng.below(4);
+        let lines = generate::rule_syntax(&mut rng, num_rules, 10);
+        let (mut matcher, msgs) = day19::parse_input(&lines)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        let parser = EarleyParser::new(&matcher);
+        let dfa = Dfa::compile(&matcher, 0).ok();
+        for msg in msgs.iter().map(String::as_str).chain(Some("")) {
+            let expected = parser.parse(0, msg).is_match();
+            let msg_info = format!("seed {}: {:?}", seed, msg);
+            assert_eq!(matcher.eval(0, msg), expected, "{}", msg_info);
+            if let Some(dfa) = &dfa {
+                assert_eq!(dfa.is_match(msg), expected, "{}", msg_info);
+            }
+        }
+        let count = day19::count_matches_with(Engine::Earley, &matcher, &msgs);
+        for &engine in &[Engine::Auto, Engine::Smart] {
+            assert_eq!(
+                day19::count_matches_with(engine, &matcher, &msgs),
+                count,
+                "seed {}: {:?}",
+                seed,
+                engine
+            );
+        }
+    }
+}
+
+/*
+    Day 20
+*/
+
+#[test]
+fn prop_corner_tiles() {
+    // Assembling tiles is slow in debug builds, so fewer seeds
+    for seed in 0..SEEDS / 50 {
+        let mut rng = Rng::new(seed);
+        let side = 2 + rng.below(3);
+        let (lines, corners) = generate::tile_set(&mut rng, side);
+        let tiles = day20::parse_input(&lines)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        assert_eq!(tiles.len(), side * side);
+        let sorted = SortedPuzzle::new(&UnsortedPuzzle::new(&tiles)).unwrap();
+        assert_eq!(
+            day20::part1_answer(&sorted),
+            corners.iter().product::<usize>(),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+/*
+    Day 22
+*/
+
+#[test]
+fn prop_recursive_combat_keeps_cards() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let num_cards = 2 + rng.below(20);
+        let lines = generate::decks(&mut rng, num_cards);
+        let (deck1, deck2) = day22::parse_input(&lines)
+            .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        let all_cards: Vec<usize> =
+            deck1.iter().chain(&deck2).copied().collect();
+        assert!(unique_1_to_n(all_cards.iter()), "seed {}", seed);
+        // Recursive Combat always terminates (unlike plain Combat)
+        let mut game = SpaceCards::new(&deck1, &deck2, true);
+        game.part2_execute();
+        assert!(game.check_cards(), "seed {}", seed);
+        let max_score = (1..=num_cards).map(|i| i * i).sum::<usize>();
+        assert!(game.winner_score() <= max_score, "seed {}", seed);
+    }
+}
+
+/*
+    Day 23
+*/
+
+#[test]
+fn prop_cup_game_invariant() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let num_cups = 5 + rng.below(20);
+        let labels = generate::cup_labeling(&mut rng, num_cups);
+        let mut game = CupGame::new(&labels);
+        for _ in 0..rng.below(100) {
+            game.step();
+            assert!(game.check_invariant(), "seed {}", seed);
+        }
+        // Cups are stored 0 to n - 1 internally
+        let cups: Vec<usize> = game.cups_clockwise().collect();
+        assert!(unique_0_to_n(cups.iter()), "seed {}", seed);
+    }
+}
+
+/*
+    Day 24
+*/
+
+#[test]
+fn prop_hex_paths() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let paths = generate::hex_paths(&mut rng, 10, 30);
+        for path in &paths {
+            let steps = day24::parse_line(path)
+                .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+            // Any reordering of a path ends at the same tile
+            let mut shuffled = steps.clone();
+            rng.shuffle(&mut shuffled);
+            assert_eq!(agg_path(&steps), agg_path(&shuffled), "seed {}", seed);
+            // Walking back along the opposite steps returns to the start
+            let back: Vec<HexCoord> =
+                steps.iter().map(|HexCoord(x, y)| HexCoord(-x, -y)).collect();
+            let there_and_back: Vec<HexCoord> =
+                steps.iter().chain(&back).cloned().collect();
+            assert_eq!(agg_path(&there_and_back), HexCoord(0, 0));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+/*
+    Advent of Code 2020
+    Caleb Stanford
+    Tests using the public library API
+*/
+
+use aoc2020::day07::BagGraph;
+use aoc2020::day19::{RegexCases, SmartRegexMatcher};
+use aoc2020::day23::CupGame;
+
+#[test]
+fn test_bag_graph() {
+    let mut graph = BagGraph::new();
+    let (outer, middle, inner) = ("outer", "middle", "inner");
+    graph.add_edge(&outer, &middle);
+    graph.add_edge(&outer, &middle);
+    graph.add_edge(&middle, &inner);
+    assert_eq!(graph.count_reachable(&inner), 2);
+    assert_eq!(graph.bags_inside(&outer), 4);
+}
+
+#[test]
+fn test_smart_regex_matcher() {
+    let mut matcher = SmartRegexMatcher::new();
+    matcher.add_regex(0, RegexCases::Concat(1, 2));
+    matcher.add_regex(1, RegexCases::Char('a'));
+    matcher.add_regex(2, RegexCases::Union(1, 3));
+    matcher.add_regex(3, RegexCases::Char('b'));
+    assert!(matcher.eval(0, "aa"));
+    assert!(matcher.eval(0, "ab"));
+    assert!(!matcher.eval(0, "ba"));
+}
+
+#[test]
+fn test_cup_game() {
+    let mut game = CupGame::new(&[3, 8, 9, 1, 2, 5, 4, 6, 7]);
+    game.step_for(10);
+    assert_eq!(game.display_from(1), "192658374");
+    assert!(game.check_invariant());
+}